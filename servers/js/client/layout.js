@@ -17,6 +17,20 @@ ADDRESS_TYPE_INPUT.addVariant(
 ADDRESS_TYPE_INPUT.addVariant(4, BufferLayout.nu64("index"), "serverChannel");
 ADDRESS_TYPE_INPUT.addVariant(5, BufferLayout.nu64("index"), "serverGroup");
 ADDRESS_TYPE_INPUT.addVariant(6, BufferLayout.nu64("index"), "groupChannel");
+ADDRESS_TYPE_INPUT.addVariant(7, BufferLayout.nu64("index"), "joinRequest");
+ADDRESS_TYPE_INPUT.addVariant(8, BufferLayout.nu64("index"), "channelOverwrite");
+ADDRESS_TYPE_INPUT.addVariant(9, BufferLayout.struct([]), "serverDirectory");
+ADDRESS_TYPE_INPUT.addVariant(10, BufferLayout.nu64("index"), "directoryEntry");
+ADDRESS_TYPE_INPUT.addVariant(11, BufferLayout.struct([]), "channelHistory");
+ADDRESS_TYPE_INPUT.addVariant(
+  12,
+  BufferLayout.nu64("index"),
+  "channelCheckpoint"
+);
+ADDRESS_TYPE_INPUT.addVariant(13, BufferLayout.struct([]), "serverAuditLog");
+ADDRESS_TYPE_INPUT.addVariant(14, BufferLayout.struct([]), "serverEvents");
+ADDRESS_TYPE_INPUT.addVariant(15, BufferLayout.nu64("index"), "serverEvent");
+ADDRESS_TYPE_INPUT.addVariant(16, BufferLayout.nu64("index"), "eventRsvp");
 
 const LAYOUT = BufferLayout.union(BufferLayout.u8("instruction"));
 LAYOUT.addVariant(0, ADDRESS_TYPE_INPUT, "createDerivedAccount");
@@ -39,7 +53,7 @@ const dwellerAccountLayout = BufferLayout.struct([
   BufferLayout.seq(BufferLayout.u8(), 32, "status"),
 ]);
 
-const serverAccountLayout = BufferLayout.struct([
+const serverFields = [
   BufferLayout.u8("version"),
   BufferLayout.seq(BufferLayout.u8(), 32, "owner"),
   BufferLayout.seq(BufferLayout.u8(), 32, "name"),
@@ -50,8 +64,28 @@ const serverAccountLayout = BufferLayout.struct([
   BufferLayout.nu64("administrators"),
   BufferLayout.nu64("channels"),
   BufferLayout.nu64("groups"),
+];
+
+const serverAccountLayout = BufferLayout.struct(serverFields);
+
+/// server migrated to V2, longer than V1
+const serverV2AccountLayout = BufferLayout.struct([
+  ...serverFields,
+  BufferLayout.u8("join_policy"),
+  BufferLayout.nu64("join_requests"),
+  BufferLayout.seq(BufferLayout.u8(), 64, "description_hash"),
+  BufferLayout.seq(BufferLayout.u8(), 64, "banner_hash"),
+  BufferLayout.u8("listed"),
 ]);
 
+/// decodes server account by its data length
+function decodeServerAccount(data) {
+  if (data.length >= serverV2AccountLayout.span) {
+    return serverV2AccountLayout.decode(data);
+  }
+  return serverAccountLayout.decode(data);
+}
+
 const instructionMaxSpan = Math.max(
   ...Object.values(LAYOUT.registry).map((r) => r.span)
 );
@@ -67,5 +101,7 @@ module.exports = {
   encodeInstructionData,
   dwellerAccountLayout,
   serverAccountLayout,
+  serverV2AccountLayout,
+  decodeServerAccount,
   ADDRESS_TYPE_INPUT,
 };
@@ -11,6 +11,7 @@ const {
   encodeInstructionData,
   dwellerAccountLayout,
   serverAccountLayout,
+  decodeServerAccount,
 } = require("./layout");
 const { stringToBuffer } = require("./helper");
 
@@ -22,6 +23,8 @@ const DWELLER_SERVER_SEED = "DwellerServer";
 
 const SERVER_MEMBER_SEED = "ServerMember";
 
+const DWELLER_MARKER_SEED = "DwellerMarker";
+
 function initializeDweller(dweller, name) {
   return new TransactionInstruction({
     keys: [{ pubkey: dweller.publicKey, isSigner: true, isWritable: true }],
@@ -37,6 +40,8 @@ function initializeServer(
   server,
   dwellerServer,
   serverMember,
+  memberMarker,
+  payer,
   name
 ) {
   return new TransactionInstruction({
@@ -45,6 +50,9 @@ function initializeServer(
       { pubkey: server, isSigner: true, isWritable: false },
       { pubkey: dwellerServer, isSigner: false, isWritable: true },
       { pubkey: serverMember, isSigner: false, isWritable: true },
+      { pubkey: memberMarker, isSigner: false, isWritable: true },
+      { pubkey: payer, isSigner: true, isWritable: true },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
     ],
     programId: SERVER_PROGRAM_ID,
     data: encodeInstructionData({
@@ -92,6 +100,19 @@ async function getDweller(connection, dwellerPubkey) {
   return info;
 }
 
+async function findMemberMarker(server, dweller) {
+  let marker = await PublicKey.findProgramAddress(
+    [
+      Buffer.from(DWELLER_MARKER_SEED),
+      Buffer.from(SERVER_MEMBER_SEED),
+      server.toBuffer(),
+      dweller.toBuffer(),
+    ],
+    SERVER_PROGRAM_ID
+  );
+  return marker[0];
+}
+
 async function createDerivedAccount(
   connection,
   payerAccount,
@@ -161,6 +182,10 @@ async function createServer(connection, payerAccount, dwellerAccount, name) {
     "serverMember"
   );
   console.log("ServerMember account created: ", serverMember.toBase58());
+  let memberMarker = await findMemberMarker(
+    server.publicKey,
+    dwellerAccount.publicKey
+  );
 
   const space = serverAccountLayout.span;
   const lamports = await connection.getMinimumBalanceForRentExemption(space);
@@ -181,6 +206,8 @@ async function createServer(connection, payerAccount, dwellerAccount, name) {
         server.publicKey,
         dwellerServer,
         serverMember,
+        memberMarker,
+        payerAccount.publicKey,
         name
       )
     );
@@ -202,7 +229,7 @@ async function getServer(connection, serverPubkey) {
   if (accountInfo === null) {
     throw "Error: cannot find the account";
   }
-  const info = serverAccountLayout.decode(Buffer.from(accountInfo.data));
+  const info = decodeServerAccount(Buffer.from(accountInfo.data));
   return info;
 }
 
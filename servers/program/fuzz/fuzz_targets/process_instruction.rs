@@ -0,0 +1,350 @@
+//! Runs random instructions over random accounts through [Processor::process_instruction].
+//!
+//! Accounts are serialized into the same input buffer the runtime passes to the program,
+//! so duplicate accounts share borrows and `realloc` has its padding.
+//! The processor must not panic and, when it succeeds with changes the runtime accepts,
+//! must change only accounts whose authority signed, see [authorities].
+
+#![no_main]
+
+use std::{collections::HashSet, mem::size_of};
+
+use arbitrary::Arbitrary;
+use borsh::BorshSerialize;
+use libfuzzer_sys::fuzz_target;
+use satellite_servers::{instruction::Instruction, processor::Processor, state::*};
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::{
+        deserialize, ProgramResult, MAX_PERMITTED_DATA_INCREASE, NON_DUP_MARKER, SUCCESS,
+    },
+    instruction::Instruction as CpiInstruction,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program, sysvar,
+};
+
+const MAX_ACCOUNTS: usize = 8;
+const MAX_METAS: usize = 16;
+
+/// Sysvars return defaults, cross program invocations do nothing and logs are dropped.
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_log(&self, _message: &str) {}
+
+    fn sol_invoke_signed(
+        &self,
+        _instruction: &CpiInstruction,
+        _account_infos: &[AccountInfo],
+        _signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        Ok(())
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Clock) = Clock::default() };
+        SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        SUCCESS
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+enum Key {
+    Unique,
+    Program,
+    System,
+    Rent,
+    Clock,
+}
+
+#[derive(Arbitrary, Debug)]
+enum Owner {
+    Program,
+    System,
+    Unique,
+}
+
+#[derive(Arbitrary, Debug)]
+enum Data {
+    Raw(Vec<u8>),
+    Dweller(DwellerV4),
+    DwellerHandle(DwellerHandle),
+    DwellerMarker(DwellerMarker),
+    DwellerServer(DwellerServer),
+    Server(ServerV4),
+    ServerMember(ServerMemberV4),
+    ServerMemberStatus(ServerMemberStatus),
+    JoinRequest(JoinRequest),
+    ServerAdministrator(ServerAdministrator),
+    ServerAuditLog(ServerAuditLog),
+    ServerDirectory(ServerDirectory),
+    DirectoryEntry(DirectoryEntry),
+    ServerChannel(ServerChannelV2),
+    ChannelOverwrite(ChannelOverwrite),
+    ChannelHistory(ChannelHistory),
+    ChannelCheckpoint(ChannelCheckpoint),
+    ServerEvents(ServerEvents),
+    ServerEvent(ServerEvent),
+    EventRsvp(EventRsvp),
+    ServerGroup(ServerGroupV2),
+    GroupChannel(GroupChannelV2),
+}
+
+impl Data {
+    fn pack(&self) -> Vec<u8> {
+        match self {
+            Data::Raw(data) => Ok(data.clone()),
+            Data::Dweller(x) => x.try_to_vec(),
+            Data::DwellerHandle(x) => x.try_to_vec(),
+            Data::DwellerMarker(x) => x.try_to_vec(),
+            Data::DwellerServer(x) => x.try_to_vec(),
+            Data::Server(x) => x.try_to_vec(),
+            Data::ServerMember(x) => x.try_to_vec(),
+            Data::ServerMemberStatus(x) => x.try_to_vec(),
+            Data::JoinRequest(x) => x.try_to_vec(),
+            Data::ServerAdministrator(x) => x.try_to_vec(),
+            Data::ServerAuditLog(x) => x.try_to_vec(),
+            Data::ServerDirectory(x) => x.try_to_vec(),
+            Data::DirectoryEntry(x) => x.try_to_vec(),
+            Data::ServerChannel(x) => x.try_to_vec(),
+            Data::ChannelOverwrite(x) => x.try_to_vec(),
+            Data::ChannelHistory(x) => x.try_to_vec(),
+            Data::ChannelCheckpoint(x) => x.try_to_vec(),
+            Data::ServerEvents(x) => x.try_to_vec(),
+            Data::ServerEvent(x) => x.try_to_vec(),
+            Data::EventRsvp(x) => x.try_to_vec(),
+            Data::ServerGroup(x) => x.try_to_vec(),
+            Data::GroupChannel(x) => x.try_to_vec(),
+        }
+        .unwrap()
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+struct Account {
+    key: Key,
+    owner: Owner,
+    lamports: u64,
+    data: Data,
+    /// zeroed bytes after data, fixed size state is often allocated larger
+    padding: u8,
+    is_signer: bool,
+    is_writable: bool,
+}
+
+#[derive(Arbitrary, Debug)]
+enum InstructionData {
+    Instruction(Box<Instruction>),
+    Raw(Vec<u8>),
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    bank: Vec<Account>,
+    /// indexes into bank, repeated index is duplicate account
+    metas: Vec<u8>,
+    data: InstructionData,
+}
+
+#[derive(PartialEq, Debug)]
+struct Snapshot {
+    lamports: u64,
+    owner: Pubkey,
+    data: Vec<u8>,
+}
+
+fn snapshot(account: &AccountInfo) -> Snapshot {
+    Snapshot {
+        lamports: account.lamports(),
+        owner: *account.owner,
+        data: account.data.borrow().to_vec(),
+    }
+}
+
+/// changes the runtime fails the instruction for after the program returns
+fn rejected(
+    program_id: &Pubkey,
+    account: &AccountInfo,
+    before: &Snapshot,
+    after: &Snapshot,
+) -> bool {
+    if before == after {
+        return false;
+    }
+    let owned = before.owner == *program_id;
+    !account.is_writable
+        || (!owned && (before.data != after.data || before.owner != after.owner))
+        || (!owned && after.lamports < before.lamports)
+}
+
+fn names(data: &[u8], key: &Pubkey) -> bool {
+    data.windows(size_of::<Pubkey>()).any(|x| x == key.as_ref())
+}
+
+/// keys allowed to authorize changes of accounts naming them: signers,
+/// program owned accounts naming a signer, like dweller of signing authority,
+/// server of signing owner or administrator entry of signing dweller,
+/// and accounts those name, like server of that administrator entry
+fn authorities(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    before: &[Snapshot],
+) -> HashSet<Pubkey> {
+    let signers: Vec<_> = accounts
+        .iter()
+        .filter(|x| x.is_signer)
+        .map(|x| *x.key)
+        .collect();
+    let mut keys: HashSet<_> = signers.iter().copied().collect();
+    for (account, before) in accounts.iter().zip(before.iter()) {
+        if before.owner == *program_id && signers.iter().any(|x| names(&before.data, x)) {
+            keys.insert(*account.key);
+            keys.extend(
+                accounts
+                    .iter()
+                    .filter(|x| names(&before.data, x.key))
+                    .map(|x| *x.key),
+            );
+        }
+    }
+    keys
+}
+
+fn write<T: Copy>(buffer: &mut Vec<u8>, value: T) {
+    let bytes =
+        unsafe { std::slice::from_raw_parts(&value as *const T as *const u8, size_of::<T>()) };
+    buffer.extend_from_slice(bytes);
+}
+
+/// serializes accounts the way the runtime does for programs with aligned input
+fn serialize(
+    program_id: &Pubkey,
+    bank: &[(Pubkey, &Account)],
+    metas: &[usize],
+    data: &[u8],
+) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    write(&mut buffer, metas.len() as u64);
+    for (position, index) in metas.iter().enumerate() {
+        if let Some(original) = metas[..position].iter().position(|x| x == index) {
+            write(&mut buffer, original as u8);
+            buffer.extend_from_slice(&[0; 7]);
+            continue;
+        }
+        let (key, account) = &bank[*index];
+        let owner = match account.owner {
+            Owner::Program => *program_id,
+            Owner::System => system_program::id(),
+            Owner::Unique => Pubkey::new_unique(),
+        };
+        let mut account_data = account.data.pack();
+        account_data.resize(account_data.len() + account.padding as usize, 0);
+        write(&mut buffer, NON_DUP_MARKER);
+        write(&mut buffer, account.is_signer as u8);
+        write(&mut buffer, account.is_writable as u8);
+        write(&mut buffer, 0u8);
+        write(&mut buffer, 0u32);
+        buffer.extend_from_slice(key.as_ref());
+        buffer.extend_from_slice(owner.as_ref());
+        write(&mut buffer, account.lamports);
+        write(&mut buffer, account_data.len() as u64);
+        buffer.extend_from_slice(&account_data);
+        buffer.resize(buffer.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+        buffer.resize(buffer.len() + (8 - buffer.len() % 8) % 8, 0);
+        write(&mut buffer, 0u64);
+    }
+    write(&mut buffer, data.len() as u64);
+    buffer.extend_from_slice(data);
+    buffer.extend_from_slice(program_id.as_ref());
+    buffer
+}
+
+fuzz_target!(|input: Input| {
+    set_syscall_stubs(Box::new(Stubs));
+    let program_id = satellite_servers::id();
+
+    let mut used = HashSet::new();
+    let bank: Vec<_> = input
+        .bank
+        .iter()
+        .take(MAX_ACCOUNTS)
+        .map(|account| {
+            let key = match account.key {
+                Key::Unique => Pubkey::new_unique(),
+                Key::Program => program_id,
+                Key::System => system_program::id(),
+                Key::Rent => sysvar::rent::id(),
+                Key::Clock => sysvar::clock::id(),
+            };
+            let key = if used.insert(key) {
+                key
+            } else {
+                Pubkey::new_unique()
+            };
+            (key, account)
+        })
+        .collect();
+    if bank.is_empty() {
+        return;
+    }
+    let metas: Vec<_> = input
+        .metas
+        .iter()
+        .take(MAX_METAS)
+        .map(|x| *x as usize % bank.len())
+        .collect();
+    let data = match &input.data {
+        InstructionData::Instruction(instruction) => instruction.try_to_vec().unwrap(),
+        InstructionData::Raw(data) => data.clone(),
+    };
+
+    // 8 byte aligned like the runtime input region
+    let buffer = serialize(&program_id, &bank, &metas, &data);
+    let mut aligned = vec![0u64; buffer.len() / 8 + 1];
+    let region =
+        unsafe { std::slice::from_raw_parts_mut(aligned.as_mut_ptr() as *mut u8, buffer.len()) };
+    region.copy_from_slice(&buffer);
+    let (program_id, accounts, data) = unsafe { deserialize(region.as_mut_ptr()) };
+
+    let before: Vec<_> = accounts.iter().map(snapshot).collect();
+    let result = Processor::process_instruction(program_id, &accounts, data);
+    if result.is_err() {
+        // runtime discards changes of failed instructions
+        return;
+    }
+
+    let after: Vec<_> = accounts.iter().map(snapshot).collect();
+    if accounts
+        .iter()
+        .zip(before.iter().zip(after.iter()))
+        .any(|(account, (before, after))| rejected(program_id, account, before, after))
+    {
+        return;
+    }
+
+    if matches!(Instruction::unpack(data), Ok(Instruction::ClearStaleAvatar)) {
+        return;
+    }
+    let authorities = authorities(program_id, &accounts, &before);
+    for (account, (before, after)) in accounts.iter().zip(before.iter().zip(after.iter())) {
+        let refunded = before.owner == after.owner
+            && before.data == after.data
+            && before.lamports <= after.lamports;
+        assert!(
+            refunded
+                || authorities.contains(account.key)
+                || authorities
+                    .iter()
+                    .any(|x| names(&before.data, x) || names(&after.data, x)),
+            "{} changed without authority",
+            account.key
+        );
+    }
+});
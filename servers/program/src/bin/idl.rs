@@ -0,0 +1,5 @@
+//! Prints JSON interface description of the program
+
+fn main() {
+    println!("{}", satellite_servers::idl::idl());
+}
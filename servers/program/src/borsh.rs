@@ -1,4 +1,7 @@
+use std::cell::{Ref, RefMut};
+
 use borsh::{BorshDeserialize, BorshSerialize};
+use bytemuck::Pod;
 use solana_program::{account_info::AccountInfo, program_error::ProgramError};
 
 /// Forces rust auto cast, it does not work with write in default serialize
@@ -54,3 +57,31 @@ impl<'a> AccountWithBorsh for AccountInfo<'a> {
         Ok((server_data, server_state))
     }
 }
+
+/// Reads and writes data prefix in place as `T`, without borsh (de)serialization of whole state
+pub trait AccountWithZeroCopy {
+    fn read_data_with_zero_copy<T: Pod>(&self) -> Result<Ref<'_, T>, ProgramError>;
+    fn read_data_with_zero_copy_mut<T: Pod>(&self) -> Result<RefMut<'_, T>, ProgramError>;
+}
+
+impl<'a> AccountWithZeroCopy for AccountInfo<'a> {
+    fn read_data_with_zero_copy<T: Pod>(&self) -> Result<Ref<'_, T>, ProgramError> {
+        let data = self.try_borrow_data()?;
+        let len = std::mem::size_of::<T>();
+        data.get(..len)
+            .and_then(|x| bytemuck::try_from_bytes::<T>(x).ok())
+            .ok_or(ProgramError::InvalidAccountData)?;
+        Ok(Ref::map(data, |x| bytemuck::from_bytes(&x[..len])))
+    }
+
+    fn read_data_with_zero_copy_mut<T: Pod>(&self) -> Result<RefMut<'_, T>, ProgramError> {
+        let data = self.try_borrow_mut_data()?;
+        let len = std::mem::size_of::<T>();
+        data.get(..len)
+            .and_then(|x| bytemuck::try_from_bytes::<T>(x).ok())
+            .ok_or(ProgramError::InvalidAccountData)?;
+        Ok(RefMut::map(data, |x| {
+            bytemuck::from_bytes_mut(&mut x[..len])
+        }))
+    }
+}
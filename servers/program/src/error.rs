@@ -67,6 +67,168 @@ pub enum Error {
     ///Invalid derived server member laast address
     #[error("Invalid derived server member laast address")]
     InvalidDerivedServerMemberLaastAddress,
+
+    ///Invalid derived join request address
+    #[error("Invalid derived join request address")]
+    InvalidDerivedJoinRequestAddress,
+
+    ///Server join policy does not accept join requests
+    #[error("Server join policy does not accept join requests")]
+    JoinRequestsNotAccepted,
+
+    ///Invalid channel kind
+    #[error("Invalid channel kind")]
+    InvalidChannelKind,
+
+    ///Invalid voice channel config
+    #[error("Invalid voice channel config")]
+    InvalidVoiceChannelConfig,
+
+    ///Account state version does not support operation
+    #[error("Account state version does not support operation")]
+    UnsupportedStateVersion,
+
+    ///Invalid derived channel overwrite address
+    #[error("Invalid derived channel overwrite address")]
+    InvalidDerivedChannelOverwriteAddress,
+
+    ///Dweller lacks channel permission
+    #[error("Dweller lacks channel permission")]
+    MissingChannelPermission,
+
+    ///Channel overwrites must be removed first
+    #[error("Channel overwrites must be removed first")]
+    ChannelHasOverwrites,
+
+    ///Dweller profile field exceeds its cap
+    #[error("Dweller profile field exceeds its cap")]
+    DwellerProfileFieldTooLong,
+
+    ///Invalid derived server directory address
+    #[error("Invalid derived server directory address")]
+    InvalidDerivedServerDirectoryAddress,
+
+    ///Invalid derived directory entry address
+    #[error("Invalid derived directory entry address")]
+    InvalidDerivedDirectoryEntryAddress,
+
+    ///Only public servers can be listed
+    #[error("Only public servers can be listed")]
+    ServerNotPublic,
+
+    ///Server is already listed
+    #[error("Server is already listed")]
+    ServerAlreadyListed,
+
+    ///Invalid derived channel history address
+    #[error("Invalid derived channel history address")]
+    InvalidDerivedChannelHistoryAddress,
+
+    ///Invalid derived channel checkpoint address
+    #[error("Invalid derived channel checkpoint address")]
+    InvalidDerivedChannelCheckpointAddress,
+
+    ///Checkpoint must not cover fewer messages than previous one
+    #[error("Checkpoint must not cover fewer messages than previous one")]
+    ChannelCheckpointOutdated,
+
+    ///No passed token account meets server join requirement
+    #[error("No passed token account meets server join requirement")]
+    JoinRequirementNotMet,
+
+    ///Member still meets server join requirement
+    #[error("Member still meets server join requirement")]
+    MemberMeetsJoinRequirement,
+
+    ///Token account is not the one member joined with
+    #[error("Token account is not the one member joined with")]
+    InvalidJoinRequirementToken,
+
+    ///Invalid server treasury address
+    #[error("Invalid server treasury address")]
+    InvalidTreasuryAddress,
+
+    ///No passed token account can pay server join fee
+    #[error("No passed token account can pay server join fee")]
+    JoinFeeNotPaid,
+
+    ///Server has no subscription fee
+    #[error("Server has no subscription fee")]
+    ServerHasNoSubscription,
+
+    ///Invalid derived server event address
+    #[error("Invalid derived server event address")]
+    InvalidDerivedServerEventAddress,
+
+    ///Invalid derived event RSVP address
+    #[error("Invalid derived event RSVP address")]
+    InvalidDerivedEventRsvpAddress,
+
+    ///Server event was cancelled
+    #[error("Server event was cancelled")]
+    ServerEventCancelled,
+
+    ///Handle must be 1 to 32 of letters, digits or underscore
+    #[error("Handle must be 1 to 32 of letters, digits or underscore")]
+    InvalidHandle,
+
+    ///Invalid handle address
+    #[error("Invalid handle address")]
+    InvalidHandleAddress,
+
+    ///Handle is claimed by other dweller
+    #[error("Handle is claimed by other dweller")]
+    HandleTaken,
+
+    ///Dweller already has handle
+    #[error("Dweller already has handle")]
+    DwellerHasHandle,
+
+    ///Token is not initialized NFT owned by dweller
+    #[error("Token is not initialized NFT owned by dweller")]
+    InvalidNftAvatar,
+
+    ///NFT avatar is still owned by dweller
+    #[error("NFT avatar is still owned by dweller")]
+    NftAvatarStillOwned,
+
+    ///Administrator is not member of server
+    #[error("Administrator is not member of server")]
+    AdministratorIsNotMember,
+
+    ///Rent payer of removed list entry is not provided writable
+    #[error("Rent payer of removed list entry is not provided writable")]
+    RentPayerNotProvided,
+
+    ///Dweller marker address is not derived from container and dweller
+    #[error("Dweller marker address is not derived from container and dweller")]
+    InvalidDwellerMarkerAddress,
+
+    ///Dweller already has pending join request or is member
+    #[error("Dweller already has pending join request or is member")]
+    JoinRequestPending,
+
+    ///Dweller already RSVPed to server event
+    #[error("Dweller already RSVPed to server event")]
+    AlreadyRsvped,
+
+    ///Server event has ended
+    #[error("Server event has ended")]
+    ServerEventEnded,
+
+    ///Server audit log is not passed or not created
+    #[error("Server audit log is not passed or not created")]
+    InvalidDerivedServerAuditLogAddress,
+
+    ///Dweller is already member of server or has pending join request
+    #[error("Dweller is already member of server or has pending join request")]
+    AlreadyMember,
+    ///Dweller is already administrator of server
+    #[error("Dweller is already administrator of server")]
+    AlreadyAdministrator,
+    ///Leaving administrator must pass its server administrator entry
+    #[error("Leaving administrator must pass its server administrator entry")]
+    AdministratorEntryRequired,
 }
 
 impl From<Error> for ProgramError {
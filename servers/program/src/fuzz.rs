@@ -0,0 +1,27 @@
+//! Fuzzing support, [arbitrary::Arbitrary] for foreign types used by state and instructions
+
+#![cfg(feature = "fuzz")]
+
+use arbitrary::{Arbitrary, Unstructured};
+use solana_program::pubkey::Pubkey;
+
+use crate::state::JoinRequirement;
+
+/// arbitrary key, used as `#[arbitrary(with = ...)]` for [Pubkey] fields
+pub fn pubkey(u: &mut Unstructured) -> arbitrary::Result<Pubkey> {
+    Ok(Pubkey::new_from_array(u.arbitrary()?))
+}
+
+// `BorshSchema` copies variant fields into helper structs, so keys of variants cannot use `with`
+impl<'a> Arbitrary<'a> for JoinRequirement {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(match u.int_in_range(0..=2u8)? {
+            0 => JoinRequirement::None,
+            1 => JoinRequirement::TokenBalance {
+                mint: pubkey(u)?,
+                amount: u.arbitrary()?,
+            },
+            _ => JoinRequirement::NftOwnership { mint: pubkey(u)? },
+        })
+    }
+}
@@ -0,0 +1,1064 @@
+//! JSON interface description of the program, generated from [BorshSchema] of instructions and state
+
+use std::{collections::BTreeMap, collections::HashMap, fmt};
+
+use borsh::schema::{BorshSchema, Declaration, Definition, Fields};
+use num_traits::FromPrimitive;
+
+use crate::{error::Error, instruction::Instruction, state::*};
+
+/// signer
+const S: u8 = 1;
+/// writable
+const W: u8 = 2;
+/// derived, see [SEEDS]
+const D: u8 = 4;
+/// zero or more trailing accounts
+const L: u8 = 8;
+
+/// Accounts of each [Instruction] as passed by [crate::instruction] builders,
+/// optional trailing accounts of `with_*` builders are not listed.
+const ACCOUNTS: &[(&str, &[(&str, u8)])] = &[
+    (
+        "CreateDerivedAccount",
+        &[
+            ("payer", S | W),
+            ("owner", 0),
+            ("base", 0),
+            ("account_to_create", W | D),
+            ("rent", 0),
+            ("system_program", 0),
+        ],
+    ),
+    ("InitializeDweller", &[("dweller", S | W)]),
+    (
+        "InitializeServer",
+        &[
+            ("dweller_owner", S | W),
+            ("server", S | W),
+            ("dweller_server", W | D),
+            ("server_member", W | D),
+            ("member_marker", W | D),
+            ("payer", S | W),
+            ("system_program", 0),
+        ],
+    ),
+    ("SetDwellerName", &[("dweller", S | W)]),
+    ("SetDwellerPhoto", &[("dweller", S | W)]),
+    ("SetDwellerStatus", &[("dweller", S | W)]),
+    (
+        "AddChannel",
+        &[
+            ("dweller_administrator", S | W),
+            ("server_administrator", D),
+            ("server", W),
+            ("server_channel", W | D),
+        ],
+    ),
+    (
+        "DeleteChannel",
+        &[
+            ("dweller_administrator", S),
+            ("server_administrator", D),
+            ("server", W),
+            ("server_channel", W | D),
+            ("server_channel_last", W | D),
+        ],
+    ),
+    (
+        "CreateGroup",
+        &[
+            ("dweller_administrator", S | W),
+            ("server_administrator", D),
+            ("server", W),
+            ("server_group", W | D),
+        ],
+    ),
+    (
+        "DeleteGroup",
+        &[
+            ("dweller_administrator", S | W),
+            ("server_administrator", D),
+            ("server", W),
+            ("server_group", W | D),
+            ("server_group_last", W | D),
+            ("group_channels", W | D | L),
+        ],
+    ),
+    (
+        "AddChannelToGroup",
+        &[
+            ("server", 0),
+            ("dweller_administrator", S),
+            ("server_administrator", D),
+            ("server_channel", D),
+            ("server_group", W | D),
+            ("group_channel", W | D),
+        ],
+    ),
+    (
+        "RemoveChannelFromGroup",
+        &[
+            ("server", W),
+            ("dweller_administrator", S),
+            ("server_administrator", D),
+            ("server_group", W | D),
+            ("group_channel", W | D),
+            ("group_channel_last", W | D),
+        ],
+    ),
+    (
+        "AddAdmin",
+        &[
+            ("owner", S | W),
+            ("dweller", W),
+            ("server", W),
+            ("server_administrator", W | D),
+            ("administrator_marker", W | D),
+            ("payer", S | W),
+            ("system_program", 0),
+        ],
+    ),
+    (
+        "RemoveAdmin",
+        &[
+            ("owner", S),
+            ("server", W),
+            ("server_administrator", W | D),
+            ("server_administrator_last", W | D),
+            ("administrator_marker", W | D),
+        ],
+    ),
+    (
+        "JoinServer",
+        &[
+            ("server", W),
+            ("server_member", W | D),
+            ("server_member_status", W | D),
+            ("dweller", S | W),
+            ("dweller_server", W | D),
+            ("server_member_status_last", W | D),
+            ("member_marker", W | D),
+            ("payer", S | W),
+            ("system_program", 0),
+        ],
+    ),
+    (
+        "LeaveServer",
+        &[
+            ("server", W),
+            ("server_member", W | D),
+            ("server_member_last", W | D),
+            ("dweller", S | W),
+            ("dweller_server", W | D),
+            ("dweller_server_last", W),
+            ("member_marker", W | D),
+            ("administrator_marker", W | D),
+        ],
+    ),
+    (
+        "InviteToServer",
+        &[
+            ("server", W),
+            ("dweller_administrator", S),
+            ("server_administrator", D),
+            ("dweller", 0),
+            ("member_status", W | D),
+        ],
+    ),
+    (
+        "RevokeInviteServer",
+        &[
+            ("server", W),
+            ("dweller_administrator", S),
+            ("server_administrator", D),
+            ("server_member_status", W | D),
+            ("server_member_status_last", W | D),
+        ],
+    ),
+    (
+        "SetServerName",
+        &[
+            ("server", S | W),
+            ("dweller_administrator", S),
+            ("server_administrator", S | D),
+        ],
+    ),
+    (
+        "SetServerDb",
+        &[
+            ("server", S | W),
+            ("dweller_administrator", S),
+            ("server_administrator", S | D),
+        ],
+    ),
+    (
+        "SetJoinPolicy",
+        &[
+            ("dweller_administrator", S),
+            ("server_administrator", D),
+            ("server", W),
+        ],
+    ),
+    (
+        "RequestJoinServer",
+        &[
+            ("server", W),
+            ("dweller", S),
+            ("join_request", W | D),
+            ("member_marker", W | D),
+            ("payer", S | W),
+            ("system_program", 0),
+        ],
+    ),
+    (
+        "ApproveJoinRequest",
+        &[
+            ("dweller_administrator", S),
+            ("server_administrator", D),
+            ("server", W),
+            ("join_request", W | D),
+            ("join_request_last", W | D),
+            ("server_member", W | D),
+            ("dweller", W),
+            ("dweller_server", W | D),
+            ("member_marker", D),
+        ],
+    ),
+    (
+        "RejectJoinRequest",
+        &[
+            ("dweller_administrator", S),
+            ("server_administrator", D),
+            ("server", W),
+            ("join_request", W | D),
+            ("join_request_last", W | D),
+            ("member_marker", W | D),
+        ],
+    ),
+    (
+        "DeclineInvite",
+        &[
+            ("server", W),
+            ("dweller", S),
+            ("server_member_status", W | D),
+            ("server_member_status_last", W | D),
+        ],
+    ),
+    (
+        "UpdateChannel",
+        &[
+            ("dweller_administrator", S),
+            ("server_administrator", D),
+            ("server", 0),
+            ("server_channel", W | D),
+            ("channel_overwrites", D | L),
+        ],
+    ),
+    (
+        "SetChannelOverwrite",
+        &[
+            ("dweller_administrator", S),
+            ("server_administrator", D),
+            ("server", 0),
+            ("server_channel", W | D),
+            ("channel_overwrite", W | D),
+            ("channel_overwrites", D | L),
+        ],
+    ),
+    (
+        "RemoveChannelOverwrite",
+        &[
+            ("dweller_administrator", S),
+            ("server_administrator", D),
+            ("server", 0),
+            ("server_channel", W | D),
+            ("channel_overwrite", W | D),
+            ("channel_overwrite_last", W | D),
+            ("channel_overwrites", D | L),
+        ],
+    ),
+    (
+        "RenameGroup",
+        &[
+            ("dweller_administrator", S),
+            ("server_administrator", D),
+            ("server", 0),
+            ("server_group", W | D),
+        ],
+    ),
+    (
+        "MoveGroupChannel",
+        &[
+            ("dweller_administrator", S),
+            ("server_administrator", D),
+            ("server", 0),
+            ("server_group", D),
+            ("group_channel", W | D),
+            ("group_channel_other", W | D),
+        ],
+    ),
+    (
+        "UpdateDwellerProfile",
+        &[("dweller", S | W), ("payer", S | W), ("system_program", 0)],
+    ),
+    (
+        "MigrateDweller",
+        &[("dweller", S | W), ("payer", S | W), ("system_program", 0)],
+    ),
+    ("SetDwellerAuthority", &[("dweller", S | W)]),
+    (
+        "SetServerPhoto",
+        &[
+            ("dweller_administrator", S),
+            ("server_administrator", D),
+            ("server", W),
+        ],
+    ),
+    (
+        "SetServerDescription",
+        &[
+            ("dweller_administrator", S),
+            ("server_administrator", D),
+            ("server", W),
+        ],
+    ),
+    (
+        "SetServerBanner",
+        &[
+            ("dweller_administrator", S),
+            ("server_administrator", D),
+            ("server", W),
+        ],
+    ),
+    (
+        "UpdateServerProfile",
+        &[
+            ("dweller_administrator", S),
+            ("server_administrator", D),
+            ("server", W),
+        ],
+    ),
+    (
+        "ListServer",
+        &[
+            ("dweller_owner", S),
+            ("server", W),
+            ("server_directory", W | D),
+            ("directory_entry", W | D),
+        ],
+    ),
+    (
+        "UnlistServer",
+        &[
+            ("dweller_owner", S),
+            ("server", W),
+            ("server_directory", W | D),
+            ("directory_entry", W | D),
+            ("directory_entry_last", W | D),
+        ],
+    ),
+    (
+        "SetMemberNickname",
+        &[
+            ("dweller", S),
+            ("server", 0),
+            ("server_member", W | D),
+            ("server_administrator", D),
+        ],
+    ),
+    (
+        "SetChannelCommitter",
+        &[
+            ("dweller_administrator", S),
+            ("server_administrator", D),
+            ("server", 0),
+            ("server_channel", D),
+            ("channel_history", W | D),
+        ],
+    ),
+    (
+        "CommitChannelCheckpoint",
+        &[
+            ("committer", S),
+            ("server_administrator", D),
+            ("server", 0),
+            ("server_channel", D),
+            ("channel_history", W | D),
+            ("channel_checkpoint", W | D),
+        ],
+    ),
+    (
+        "SetJoinRequirement",
+        &[
+            ("dweller_administrator", S),
+            ("server_administrator", D),
+            ("server", W),
+            ("payer", S | W),
+            ("system_program", 0),
+        ],
+    ),
+    (
+        "RecheckMember",
+        &[
+            ("server", W),
+            ("server_member", W | D),
+            ("server_member_last", W | D),
+            ("dweller", W),
+            ("dweller_server", W | D),
+            ("dweller_server_last", W),
+            ("token", 0),
+            ("member_marker", W | D),
+        ],
+    ),
+    (
+        "SetJoinFee",
+        &[
+            ("dweller_owner", S),
+            ("server", W),
+            ("payer", S | W),
+            ("system_program", 0),
+            ("treasury", W | D),
+            ("mint", 0),
+            ("token_program", 0),
+        ],
+    ),
+    (
+        "RenewMembership",
+        &[
+            ("server", 0),
+            ("server_member", W | D),
+            ("authority", S),
+            ("source", W),
+            ("treasury", W | D),
+            ("token_program", 0),
+        ],
+    ),
+    (
+        "WithdrawTreasury",
+        &[
+            ("dweller_owner", S),
+            ("server", 0),
+            ("treasury", W | D),
+            ("destination", W),
+            ("token_program", 0),
+        ],
+    ),
+    (
+        "CreateServerEvent",
+        &[
+            ("dweller_administrator", S),
+            ("server_administrator", D),
+            ("server", 0),
+            ("server_events", W | D),
+            ("server_event", W | D),
+            ("server_channel", D),
+        ],
+    ),
+    (
+        "CancelServerEvent",
+        &[
+            ("dweller_administrator", S),
+            ("server_administrator", D),
+            ("server", 0),
+            ("server_event", W | D),
+        ],
+    ),
+    (
+        "Rsvp",
+        &[
+            ("dweller", S),
+            ("server", 0),
+            ("server_member", D),
+            ("server_event", W | D),
+            ("event_rsvp", W | D),
+            ("event_rsvp_marker", W | D),
+            ("payer", S | W),
+            ("system_program", 0),
+        ],
+    ),
+    (
+        "CancelRsvp",
+        &[
+            ("dweller", S),
+            ("server", 0),
+            ("server_event", W | D),
+            ("event_rsvp", W | D),
+            ("event_rsvp_last", W | D),
+            ("event_rsvp_marker", W | D),
+        ],
+    ),
+    (
+        "ClaimHandle",
+        &[
+            ("dweller", S | W),
+            ("dweller_handle", W | D),
+            ("payer", S | W),
+            ("system_program", 0),
+        ],
+    ),
+    (
+        "ReleaseHandle",
+        &[
+            ("dweller", S | W),
+            ("dweller_handle", W | D),
+            ("destination", W),
+        ],
+    ),
+    (
+        "TransferHandle",
+        &[
+            ("dweller", S | W),
+            ("dweller_handle", W | D),
+            ("dweller_to", S | W),
+            ("payer", S | W),
+            ("system_program", 0),
+        ],
+    ),
+    (
+        "SetDwellerNftAvatar",
+        &[
+            ("dweller", S | W),
+            ("token", 0),
+            ("token_data", 0),
+            ("payer", S | W),
+            ("system_program", 0),
+        ],
+    ),
+    ("ClearStaleAvatar", &[("dweller", W), ("token", 0)]),
+    (
+        "Reindex",
+        &[
+            ("dweller_administrator", S),
+            ("server_administrator", D),
+            ("server", 0),
+            ("container", 0),
+            ("entries", W | D | L),
+        ],
+    ),
+    (
+        "ReindexDwellerServers",
+        &[("dweller", S), ("entries", W | D | L)],
+    ),
+];
+
+/// Accounts created with seed followed by index from program derived base of container,
+/// see [crate::program::create_base_index_with_seed].
+const SEEDS: &[(&str, &str, &str)] = &[
+    ("DwellerServer", DwellerServer::SEED, "Dweller"),
+    ("ServerMember", ServerMember::SEED, "Server"),
+    ("ServerMemberStatus", ServerMemberStatus::SEED, "Server"),
+    ("JoinRequest", JoinRequest::SEED, "Server"),
+    ("ServerAdministrator", ServerAdministrator::SEED, "Server"),
+    ("ServerChannel", ServerChannel::SEED, "Server"),
+    ("ServerGroup", ServerGroup::SEED, "Server"),
+    ("ServerAuditLog", ServerAuditLog::SEED, "Server"),
+    ("ServerEvents", ServerEvents::SEED, "Server"),
+    ("ServerEvent", ServerEvent::SEED, "Server"),
+    ("EventRsvp", EventRsvp::SEED, "ServerEvent"),
+    ("GroupChannel", GroupChannel::SEED, "ServerGroup"),
+    ("ChannelOverwrite", ChannelOverwrite::SEED, "ServerChannel"),
+    ("ChannelHistory", ChannelHistory::SEED, "ServerChannel"),
+    (
+        "ChannelCheckpoint",
+        ChannelCheckpoint::SEED,
+        "ServerChannel",
+    ),
+    ("ServerDirectory", ServerDirectory::SEED, "program"),
+    ("DirectoryEntry", DirectoryEntry::SEED, "ServerDirectory"),
+];
+
+/// State account layout with its hand maintained size, `None` if variable.
+struct Layout {
+    len: Option<u64>,
+    schema: borsh::schema::BorshSchemaContainer,
+}
+
+fn layout<T: BorshSchema>(len: Option<u64>) -> Layout {
+    Layout {
+        len,
+        schema: T::schema_container(),
+    }
+}
+
+fn layouts() -> Vec<Layout> {
+    vec![
+        layout::<Dweller>(Some(Dweller::LEN)),
+        layout::<DwellerV2>(None),
+        layout::<DwellerV3>(None),
+        layout::<DwellerV4>(None),
+        layout::<DwellerHandle>(Some(DwellerHandle::LEN)),
+        layout::<DwellerMarker>(Some(DwellerMarker::LEN)),
+        layout::<DwellerServer>(Some(DwellerServer::LEN)),
+        layout::<ListEntry>(Some(ListEntry::LEN)),
+        layout::<RentPayer>(Some(RentPayer::LEN)),
+        layout::<Server>(Some(Server::LEN)),
+        layout::<ServerV2>(Some(ServerV2::LEN)),
+        layout::<ServerV3>(Some(ServerV3::LEN)),
+        layout::<ServerV4>(Some(ServerV4::LEN)),
+        layout::<ServerMember>(Some(ServerMember::LEN)),
+        layout::<ServerMemberV2>(Some(ServerMemberV2::LEN)),
+        layout::<ServerMemberV3>(Some(ServerMemberV3::LEN)),
+        layout::<ServerMemberV4>(Some(ServerMemberV4::LEN)),
+        layout::<ServerMemberStatus>(Some(ServerMemberStatus::LEN)),
+        layout::<JoinRequest>(Some(JoinRequest::LEN)),
+        layout::<ServerAdministrator>(Some(ServerAdministrator::LEN)),
+        layout::<ServerAuditLog>(Some(ServerAuditLog::HEADER_LEN)),
+        layout::<AuditEntry>(Some(AuditEntry::LEN)),
+        layout::<ServerDirectory>(Some(ServerDirectory::LEN)),
+        layout::<DirectoryEntry>(Some(DirectoryEntry::LEN)),
+        layout::<ServerChannel>(Some(ServerChannel::LEN)),
+        layout::<ServerChannelV2>(Some(ServerChannelV2::LEN)),
+        layout::<ChannelOverwrite>(Some(ChannelOverwrite::LEN)),
+        layout::<ChannelHistory>(Some(ChannelHistory::LEN)),
+        layout::<ChannelCheckpoint>(Some(ChannelCheckpoint::LEN)),
+        layout::<ServerEvents>(Some(ServerEvents::LEN)),
+        layout::<ServerEvent>(Some(ServerEvent::LEN)),
+        layout::<EventRsvp>(Some(EventRsvp::LEN)),
+        layout::<ServerGroup>(Some(ServerGroup::LEN)),
+        layout::<ServerGroupV2>(Some(ServerGroupV2::LEN)),
+        layout::<GroupChannel>(Some(GroupChannel::LEN)),
+        layout::<GroupChannelV2>(Some(GroupChannelV2::LEN)),
+    ]
+}
+
+/// JSON value
+enum Json {
+    Null,
+    Bool(bool),
+    Number(u64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(&'static str, Json)>),
+    Map(BTreeMap<String, Json>),
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Json::String(value.to_string())
+    }
+}
+
+impl From<Option<u64>> for Json {
+    fn from(value: Option<u64>) -> Self {
+        value.map(Json::Number).unwrap_or(Json::Null)
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter, value: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in value.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(value) => write!(f, "{}", value),
+            Json::Number(value) => write!(f, "{}", value),
+            Json::String(value) => write_string(f, value),
+            Json::Array(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+            Json::Map(fields) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// packed size of declaration, `None` if variable
+fn packed_len(declaration: &str, definitions: &HashMap<Declaration, Definition>) -> Option<u64> {
+    match definitions.get(declaration) {
+        Some(Definition::Array { length, elements }) => {
+            packed_len(elements, definitions).map(|x| x * *length as u64)
+        }
+        Some(Definition::Sequence { .. }) => None,
+        Some(Definition::Tuple { elements }) => {
+            elements.iter().map(|x| packed_len(x, definitions)).sum()
+        }
+        Some(Definition::Enum { variants }) => variants
+            .iter()
+            .map(|(_, x)| packed_len(x, definitions))
+            .try_fold(0, |max, x| x.map(|x| max.max(x)))
+            .map(|x| x + 1),
+        Some(Definition::Struct { fields }) => match fields {
+            Fields::NamedFields(fields) => {
+                fields.iter().map(|(_, x)| packed_len(x, definitions)).sum()
+            }
+            Fields::UnnamedFields(fields) => {
+                fields.iter().map(|x| packed_len(x, definitions)).sum()
+            }
+            Fields::Empty => Some(0),
+        },
+        None => match declaration {
+            "nil" => Some(0),
+            "u8" | "i8" | "bool" => Some(1),
+            "u16" | "i16" => Some(2),
+            "u32" | "i32" | "f32" => Some(4),
+            "u64" | "i64" | "f64" => Some(8),
+            "u128" | "i128" => Some(16),
+            _ => None,
+        },
+    }
+}
+
+fn definition(definition: &Definition) -> Json {
+    let named = |name: &str, declaration: &str| {
+        Json::Object(vec![("name", name.into()), ("type", declaration.into())])
+    };
+    match definition {
+        Definition::Array { length, elements } => Json::Object(vec![
+            ("kind", "array".into()),
+            ("length", Json::Number(*length as u64)),
+            ("elements", elements.as_str().into()),
+        ]),
+        Definition::Sequence { elements } => Json::Object(vec![
+            ("kind", "sequence".into()),
+            ("elements", elements.as_str().into()),
+        ]),
+        Definition::Tuple { elements } => Json::Object(vec![
+            ("kind", "tuple".into()),
+            (
+                "elements",
+                Json::Array(elements.iter().map(|x| x.as_str().into()).collect()),
+            ),
+        ]),
+        Definition::Enum { variants } => Json::Object(vec![
+            ("kind", "enum".into()),
+            (
+                "variants",
+                Json::Array(variants.iter().map(|(x, y)| named(x, y)).collect()),
+            ),
+        ]),
+        Definition::Struct { fields } => Json::Object(vec![
+            ("kind", "struct".into()),
+            (
+                "fields",
+                match fields {
+                    Fields::NamedFields(fields) => {
+                        Json::Array(fields.iter().map(|(x, y)| named(x, y)).collect())
+                    }
+                    Fields::UnnamedFields(fields) => {
+                        Json::Array(fields.iter().map(|x| x.as_str().into()).collect())
+                    }
+                    Fields::Empty => Json::Array(vec![]),
+                },
+            ),
+        ]),
+    }
+}
+
+/// input declaration of instruction variant, `None` if it takes no input
+fn input<'a>(
+    variant: &str,
+    definitions: &'a HashMap<Declaration, Definition>,
+) -> Option<&'a Declaration> {
+    match definitions.get(variant) {
+        Some(Definition::Struct {
+            fields: Fields::UnnamedFields(fields),
+        }) => fields.first(),
+        _ => None,
+    }
+}
+
+fn instruction_variants() -> (Vec<(String, Declaration)>, HashMap<Declaration, Definition>) {
+    let schema = Instruction::schema_container();
+    let variants = match schema.definitions.get(&schema.declaration) {
+        Some(Definition::Enum { variants }) => variants.clone(),
+        _ => unreachable!("instruction is enum"),
+    };
+    (variants, schema.definitions)
+}
+
+/// JSON interface description with instructions, their accounts and inputs,
+/// state layouts with sizes, seeds of derived accounts and error codes.
+pub fn idl() -> String {
+    let (variants, mut definitions) = instruction_variants();
+    let instructions = variants
+        .iter()
+        .enumerate()
+        .map(|(index, (name, variant))| {
+            let accounts = ACCOUNTS
+                .iter()
+                .find(|(x, _)| x == name)
+                .map(|(_, accounts)| *accounts)
+                .unwrap_or(&[]);
+            let accounts = accounts
+                .iter()
+                .map(|(name, flags)| {
+                    Json::Object(vec![
+                        ("name", (*name).into()),
+                        ("signer", Json::Bool(flags & S != 0)),
+                        ("writable", Json::Bool(flags & W != 0)),
+                        ("derived", Json::Bool(flags & D != 0)),
+                        ("list", Json::Bool(flags & L != 0)),
+                    ])
+                })
+                .collect();
+            Json::Object(vec![
+                ("name", name.as_str().into()),
+                ("index", Json::Number(index as u64)),
+                ("accounts", Json::Array(accounts)),
+                (
+                    "input",
+                    input(variant, &definitions)
+                        .map(|x| x.as_str().into())
+                        .unwrap_or(Json::Null),
+                ),
+            ])
+        })
+        .collect();
+    let instruction_definitions: Vec<_> = variants.iter().map(|(_, x)| x.clone()).collect();
+    for x in instruction_definitions
+        .iter()
+        .chain(Some(&"Instruction".to_string()))
+    {
+        definitions.remove(x);
+    }
+
+    let accounts = layouts()
+        .into_iter()
+        .map(|x| {
+            let packed = packed_len(&x.schema.declaration, &x.schema.definitions);
+            let account = Json::Object(vec![
+                ("name", x.schema.declaration.as_str().into()),
+                ("len", x.len.into()),
+                ("packed_len", packed.into()),
+            ]);
+            definitions.extend(x.schema.definitions);
+            account
+        })
+        .collect();
+
+    let seeds = SEEDS
+        .iter()
+        .map(|(name, seed, container)| {
+            Json::Object(vec![
+                ("account", (*name).into()),
+                ("seed", (*seed).into()),
+                ("container", (*container).into()),
+                ("indexed", Json::Bool(true)),
+            ])
+        })
+        .chain(vec![
+            Json::Object(vec![
+                ("account", "DwellerHandle".into()),
+                (
+                    "seed",
+                    Json::Array(vec![
+                        std::str::from_utf8(DwellerHandle::SEED).unwrap().into(),
+                        "handle".into(),
+                    ]),
+                ),
+                ("container", Json::Null),
+                ("indexed", Json::Bool(false)),
+            ]),
+            Json::Object(vec![
+                ("account", "DwellerMarker".into()),
+                (
+                    "seed",
+                    Json::Array(vec![
+                        std::str::from_utf8(DwellerMarker::SEED).unwrap().into(),
+                        "seed of entry".into(),
+                        "container".into(),
+                        "dweller".into(),
+                    ]),
+                ),
+                ("container", Json::Null),
+                ("indexed", Json::Bool(false)),
+            ]),
+            Json::Object(vec![
+                ("account", "ServerTreasury".into()),
+                (
+                    "seed",
+                    Json::Array(vec![
+                        "server".into(),
+                        std::str::from_utf8(JoinFee::TREASURY_SEED).unwrap().into(),
+                        "mint".into(),
+                    ]),
+                ),
+                ("container", "Server".into()),
+                ("indexed", Json::Bool(false)),
+            ]),
+        ])
+        .collect();
+
+    let errors = (0..)
+        .map_while(Error::from_u32)
+        .enumerate()
+        .map(|(code, error)| {
+            Json::Object(vec![
+                ("code", Json::Number(code as u64)),
+                ("name", format!("{:?}", error).as_str().into()),
+                ("msg", error.to_string().as_str().into()),
+            ])
+        })
+        .collect();
+
+    let types = definitions
+        .iter()
+        .map(|(x, y)| (x.clone(), definition(y)))
+        .collect();
+
+    Json::Object(vec![
+        ("name", "satellite_servers".into()),
+        ("program_id", crate::id().to_string().as_str().into()),
+        ("instructions", Json::Array(instructions)),
+        ("accounts", Json::Array(accounts)),
+        ("seeds", Json::Array(seeds)),
+        ("types", Json::Map(types)),
+        ("errors", Json::Array(errors)),
+    ])
+    .to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn accounts() {
+        let (variants, _) = instruction_variants();
+        let names: Vec<_> = variants.iter().map(|(x, _)| x.as_str()).collect();
+        let listed: Vec<_> = ACCOUNTS.iter().map(|(x, _)| *x).collect();
+        assert_eq!(names, listed);
+    }
+
+    /// zero filled instruction input, only accounts matter
+    fn input<T: borsh::BorshDeserialize>() -> T {
+        T::deserialize(&mut &[0; 512][..]).unwrap()
+    }
+
+    #[test]
+    fn account_flags() {
+        use crate::instruction::*;
+        use solana_program::pubkey::Pubkey;
+
+        let k = &Pubkey::new_unique();
+        let built = [
+            create_derived_account(k, k, k, k, k, input()),
+            initialize_dweller(k, input()),
+            initialize_server(k, k, k, k, k, input()),
+            set_dweller_name(k, &input()),
+            set_dweller_photo(k, &input()),
+            set_dweller_status(k, &input()),
+            add_channel(k, k, k, k, &input()),
+            delete_channel(k, k, k, k, k),
+            create_group(k, k, k, k, &input()),
+            delete_group(k, k, k, k, k, &[]),
+            add_channel_to_group(k, k, k, k, k, k),
+            remove_channel_from_group(k, k, k, k, k, k),
+            add_admin(k, k, k, k, k),
+            remove_admin(k, k, k, k, k),
+            join_server(k, k, k, k, k, k, k),
+            leave_server(k, k, k, k, k, k),
+            invite_to_server(k, k, k, k, k),
+            revoke_invite_server(k, k, k, k, k),
+            set_server_name(k, k, k, &input()),
+            set_server_db(k, k, k, &input()),
+            set_join_policy(k, k, k, &input()),
+            request_join_server(k, k, k, k),
+            approve_join_request(k, k, k, k, k, k, k, k),
+            reject_join_request(k, k, k, k, k, k),
+            decline_invite(k, k, k, k),
+            update_channel(k, k, k, k, &[], &input()),
+            set_channel_overwrite(k, k, k, k, k, &[], &input()),
+            remove_channel_overwrite(k, k, k, k, k, k, &[]),
+            rename_group(k, k, k, k, &input()),
+            move_group_channel(k, k, k, k, k, k),
+            update_dweller_profile(k, k, &input()),
+            migrate_dweller(k, k),
+            set_dweller_authority(k, &input()),
+            set_server_photo(k, k, k, &input()),
+            set_server_description(k, k, k, &input()),
+            set_server_banner(k, k, k, &input()),
+            update_server_profile(k, k, k, &input()),
+            list_server(k, k, k, k, &input()),
+            unlist_server(k, k, k, k, k),
+            set_member_nickname(k, k, k, k, &input()),
+            set_channel_committer(k, k, k, k, k, &input()),
+            commit_channel_checkpoint(k, k, k, k, k, k, &input()),
+            set_join_requirement(k, k, k, k, &input()),
+            recheck_member(k, k, k, k, k, k, k),
+            set_join_fee(k, k, k, k, &input()),
+            renew_membership(k, k, k, k, k),
+            withdraw_treasury(k, k, k, &input()),
+            create_server_event(k, k, k, k, k, k, &input()),
+            cancel_server_event(k, k, k, k),
+            rsvp(k, k, k, k, k, k),
+            cancel_rsvp(k, k, k, k, k),
+            claim_handle(
+                k,
+                k,
+                &ClaimHandleInput {
+                    handle: "handle".to_string(),
+                },
+            ),
+            release_handle(k, k, k),
+            transfer_handle(k, k, k, k),
+            set_dweller_nft_avatar(k, k, k),
+            clear_stale_avatar(k, k),
+            reindex(k, k, k, k, &[], &input()),
+            reindex_dweller_servers(k, &[], &input()),
+        ];
+
+        let mut checked = Vec::new();
+        for x in built.iter() {
+            let x = x.as_ref().unwrap();
+            let variant = format!("{:?}", Instruction::unpack(&x.data).unwrap());
+            let name = variant.split('(').next().unwrap();
+            let (_, accounts) = ACCOUNTS.iter().find(|(x, _)| *x == name).unwrap();
+            let listed: Vec<_> = accounts
+                .iter()
+                .filter(|(_, flags)| flags & L == 0)
+                .map(|(account, flags)| (*account, flags & S != 0, flags & W != 0))
+                .collect();
+            let passed: Vec<_> = listed
+                .iter()
+                .zip(x.accounts.iter())
+                .map(|((account, ..), meta)| (*account, meta.is_signer, meta.is_writable))
+                .collect();
+            assert_eq!(listed.len(), x.accounts.len(), "{} accounts", name);
+            assert_eq!(listed, passed, "{} flags", name);
+            checked.push(name.to_string());
+        }
+
+        let listed: Vec<_> = ACCOUNTS.iter().map(|(x, _)| x.to_string()).collect();
+        assert_eq!(checked, listed);
+    }
+}
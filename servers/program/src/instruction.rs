@@ -1,18 +1,40 @@
 //! Instruction types
 
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
-use num_derive::ToPrimitive;
 use solana_program::{
-    instruction::AccountMeta, program_error::ProgramError, pubkey::Pubkey, system_program, sysvar,
+    instruction::AccountMeta, msg, program_error::ProgramError, pubkey::Pubkey, system_program,
+    sysvar,
+};
+
+use crate::state::{
+    DwellerHandle, DwellerMarker, EventRsvp, JoinFee, JoinPolicy, JoinRequirement, OverwriteTarget,
+    ServerAdministrator, ServerCategory, ServerMember, VoiceChannelConfig,
 };
 
 /// Instructions
-#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, ToPrimitive)]
+///
+/// Privileged instructions also take trailing write, derived server_audit_log, created by
+/// [Instruction::CreateDerivedAccount], see [crate::state::ServerAuditLog] and [with_server_audit_log].
+///
+/// Instructions signed by dweller_administrator also take trailing read, derived server_member
+/// of dweller_administrator, see [with_administrator_member].
+///
+/// Instructions removing list entries also take trailing write rent receiver, closing vacated
+/// last entry into it, required if entry records [crate::state::RentPayer], see [with_rent_receiver].
+///
+/// Instructions inserting list entries also take optional trailing signer, write payer, system program
+/// and bases of containers, creating not yet existing entries instead of [Instruction::CreateDerivedAccount],
+/// see [with_payer].
+///
+/// Variants carry their input, encoded as variant index followed by input,
+/// see [Instruction::unpack] for legacy encoding.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 pub enum Instruction {
     /// Create derived account
     ///
     /// Input: [AddressTypeInput]
-    CreateDerivedAccount,
+    CreateDerivedAccount(AddressTypeInput),
 
     /// [initialize_dweller]
     /// accounts
@@ -20,7 +42,7 @@ pub enum Instruction {
     ///
     /// Input:
     ///  [InitializeDwellerInput]
-    InitializeDweller,
+    InitializeDweller(InitializeDwellerInput),
 
     /// Initializes server and joins dweller_owner
     /// accounts
@@ -28,15 +50,18 @@ pub enum Instruction {
     /// - signer,  write          server
     /// - derived, write          dweller_server
     /// - derived, write          server_member
+    /// - derived, write          member_marker, see [crate::state::DwellerMarker]
+    /// - signer,  write          payer, funds member_marker
+    /// -                         system_program
     /// Input: [InitializeServerInput]
-    InitializeServer,
+    InitializeServer(InitializeServerInput),
 
     /// Change dweller's display name
     ///
     /// Accounts:
     /// - write, signer     dweller
     /// Input: [SetNameInput]
-    SetDwellerName,
+    SetDwellerName(SetNameInput),
 
     /// Change dweller's display photo. Consider using PNG or JPEG photos for usability.
     ///
@@ -44,7 +69,7 @@ pub enum Instruction {
     /// - signer, write   dweller
     ///
     /// Input: [SetHashInput]
-    SetDwellerPhoto,
+    SetDwellerPhoto(SetHashInput),
 
     /// Update the users status
     ///
@@ -52,7 +77,7 @@ pub enum Instruction {
     /// - signer, write   dweller owner
     ///
     /// Input: [SetDwellerStatusInput]
-    SetDwellerStatus,
+    SetDwellerStatus(SetDwellerStatusInput),
 
     /// Initialize channel and add it to server.
     ///
@@ -64,8 +89,10 @@ pub enum Instruction {
     ///
     /// Input:
     /// [AddChannelInput]
-    AddChannel,
+    AddChannel(AddChannelInput),
 
+    /// Fails if server_channel or server_channel_last has overwrites.
+    ///
     /// Accounts:
     /// - signer                 dweller_administrator
     /// - read, derived          server_administrator for dweller_administrator
@@ -84,7 +111,7 @@ pub enum Instruction {
     ///
     /// Input:
     /// - [CreateGroupInput]
-    CreateGroup,
+    CreateGroup(CreateGroupInput),
 
     /// Accounts:
     /// - signer             dweller_administrator    
@@ -108,7 +135,7 @@ pub enum Instruction {
     /// - write              server
     /// - signer             dweller_administrator
     /// - read, derived      server_administrator
-    /// - read               server_channel
+    /// - write, derived     server_group
     /// - write, derived     group_channel
     /// - write, derived     group_channel_last
     RemoveChannelFromGroup,
@@ -119,6 +146,9 @@ pub enum Instruction {
     /// - read               dweller to become admin
     /// - write              server
     /// - write, derived     server_administrator
+    /// - write, derived     administrator_marker, see [crate::state::DwellerMarker]
+    /// - write, signer      payer, funds administrator_marker
+    /// - read               system_program
     AddAdmin,
 
     /// Accounts:
@@ -126,14 +156,23 @@ pub enum Instruction {
     /// - write              server
     /// - write, derived     server_administrator
     /// - write, derived     server_administrator_last
+    /// - write, derived     administrator_marker of removed dweller, closed into its payer
     RemoveAdmin,
 
+    /// Consumes invite of dweller unless server is [JoinPolicy::Public].
+    ///
     /// Accounts:
     ///   - writeable                  server     
     ///   - writeable, derived         server_member
-    ///   - read, derived              server_member_status
+    ///   - writeable, derived         server_member_status, ignored if server is [JoinPolicy::Public]
     ///   - writeable signer           dweller
     ///   - writeable, derived         dweller_server
+    ///   - writeable, derived         server_member_status_last, ignored if server is [JoinPolicy::Public]
+    ///   - writeable, derived         member_marker, see [crate::state::DwellerMarker]
+    ///   - writeable signer           payer, funds member_marker
+    ///   -                            system_program
+    ///
+    /// If server has [crate::state::ServerV4::join_fee], see [with_join_fee].
     JoinServer,
 
     /// Accounts:
@@ -144,6 +183,11 @@ pub enum Instruction {
     /// - write, signer              dweller
     /// - write, derived             dweller_server
     /// - write, derived             dweller_server_last
+    /// - write, derived             member_marker, closed into its payer
+    /// - write, derived             administrator_marker, closed into its payer
+    ///
+    /// If dweller is administrator, pass its entries to remove them too, see [with_server_administrator],
+    /// required once administrator_marker exists.
     LeaveServer,
 
     /// Accounts:
@@ -168,7 +212,7 @@ pub enum Instruction {
     /// - write              server
     ///
     /// Input: [SetNameInput]
-    SetServerName,
+    SetServerName(SetNameInput),
 
     /// Accounts:
     /// - read, signer       dweller_administrator
@@ -176,11 +220,442 @@ pub enum Instruction {
     /// - write              server
     ///
     /// Input: [SetHashInput]        
-    SetServerDb,
+    SetServerDb(SetHashInput),
+
+    /// Fails for [crate::state::StateVersion::V1] server, which is [JoinPolicy::InviteOnly].
+    ///
+    /// Accounts:
+    /// - read, signer       dweller_administrator
+    /// - read, derived      server_administrator
+    /// - write              server
+    ///
+    /// Input: [SetJoinPolicyInput]
+    SetJoinPolicy(SetJoinPolicyInput),
+
+    /// Files join request to [JoinPolicy::ApprovalRequired] server, one pending per dweller.
+    ///
+    /// Accounts:
+    /// - write              server
+    /// - read, signer       dweller
+    /// - write, derived     join_request
+    /// - write, derived     member_marker, see [crate::state::DwellerMarker]
+    /// - write, signer      payer, funds member_marker
+    /// - read               system_program
+    RequestJoinServer,
+
+    /// Removes join request and joins dweller, while server is still [JoinPolicy::ApprovalRequired].
+    ///
+    /// Accounts:
+    /// - read, signer       dweller_administrator
+    /// - read, derived      server_administrator
+    /// - write              server
+    /// - write, derived     join_request
+    /// - write, derived     join_request_last
+    /// - write, derived     server_member
+    /// - write              dweller
+    /// - write, derived     dweller_server
+    /// - read, derived      member_marker, kept as marker of membership
+    ApproveJoinRequest,
+
+    /// Accounts:
+    /// - read, signer       dweller_administrator
+    /// - read, derived      server_administrator
+    /// - write              server
+    /// - write, derived     join_request
+    /// - write, derived     join_request_last
+    /// - write, derived     member_marker of requesting dweller, closed into its payer
+    RejectJoinRequest,
+
+    /// Removes invite without joining.
+    ///
+    /// Accounts:
+    /// - write              server
+    /// - read, signer       dweller
+    /// - write, derived     server_member_status
+    /// - write, derived     server_member_status_last
+    DeclineInvite,
+
+    /// Requires [crate::state::ChannelOverwrite::MANAGE].
+    ///
+    /// Accounts:
+    /// - read, signer       dweller_administrator
+    /// - read, derived      server_administrator
+    /// - read               server
+    /// - write, derived     server_channel
+    /// - read, derived      [crate::state::ChannelOverwrite] all overwrites of server_channel
+    ///
+    /// Input: [UpdateChannelInput]
+    UpdateChannel(UpdateChannelInput),
+
+    /// Creates or updates overwrite with same target.
+    /// Requires [crate::state::ChannelOverwrite::MANAGE].
+    ///
+    /// Accounts:
+    /// - read, signer       dweller_administrator
+    /// - read, derived      server_administrator
+    /// - read               server
+    /// - write, derived     server_channel
+    /// - write, derived     channel_overwrite, existing for target or next index
+    /// - read, derived      [crate::state::ChannelOverwrite] all overwrites of server_channel
+    ///
+    /// Input: [SetChannelOverwriteInput]
+    SetChannelOverwrite(SetChannelOverwriteInput),
+
+    /// Requires [crate::state::ChannelOverwrite::MANAGE].
+    ///
+    /// Accounts:
+    /// - read, signer       dweller_administrator
+    /// - read, derived      server_administrator
+    /// - read               server
+    /// - write, derived     server_channel
+    /// - write, derived     channel_overwrite
+    /// - write, derived     channel_overwrite_last
+    /// - read, derived      [crate::state::ChannelOverwrite] all overwrites of server_channel
+    RemoveChannelOverwrite,
+
+    /// Accounts:
+    /// - read, signer       dweller_administrator
+    /// - read, derived      server_administrator
+    /// - read               server
+    /// - write, derived     server_group
+    ///
+    /// Input: [SetNameInput]
+    RenameGroup(SetNameInput),
+
+    /// Swaps [crate::state::GroupChannelV2::position] of two channels in group.
+    ///
+    /// Accounts:
+    /// - read, signer       dweller_administrator
+    /// - read, derived      server_administrator
+    /// - read               server
+    /// - read, derived      server_group
+    /// - write, derived     group_channel
+    /// - write, derived     group_channel_other
+    MoveGroupChannel,
+
+    /// Sets [crate::state::DwellerV2] profile fields, resizing account to fit.
+    ///
+    /// Accounts:
+    /// - signer, write   dweller
+    /// - signer, write   payer, tops up or receives rent difference
+    /// - read            system_program
+    ///
+    /// Input: [UpdateDwellerProfileInput]
+    UpdateDwellerProfile(UpdateDwellerProfileInput),
+
+    /// Upgrades [crate::state::Dweller] to [crate::state::DwellerV2] in place.
+    ///
+    /// Accounts:
+    /// - signer, write   dweller
+    /// - signer, write   payer
+    /// - read            system_program
+    MigrateDweller,
+
+    /// Rotates [crate::state::DwellerV2::authority], dweller address and derived addresses are kept.
+    ///
+    /// Accounts:
+    /// - write           dweller
+    /// - signer          current authority, see [with_dweller_authority]
+    ///
+    /// Input: [SetDwellerAuthorityInput]
+    SetDwellerAuthority(SetDwellerAuthorityInput),
+
+    /// Accounts:
+    /// - read, signer       dweller_administrator
+    /// - read, derived      server_administrator
+    /// - write              server
+    ///
+    /// Input: [SetHashInput]
+    SetServerPhoto(SetHashInput),
+
+    /// Requires [crate::state::ServerV2].
+    ///
+    /// Accounts:
+    /// - read, signer       dweller_administrator
+    /// - read, derived      server_administrator
+    /// - write              server
+    ///
+    /// Input: [SetHashInput]
+    SetServerDescription(SetHashInput),
+
+    /// Requires [crate::state::ServerV2].
+    ///
+    /// Accounts:
+    /// - read, signer       dweller_administrator
+    /// - read, derived      server_administrator
+    /// - write              server
+    ///
+    /// Input: [SetHashInput]
+    SetServerBanner(SetHashInput),
+
+    /// Sets all provided fields at once.
+    /// Description and banner require [crate::state::ServerV2].
+    ///
+    /// Accounts:
+    /// - read, signer       dweller_administrator
+    /// - read, derived      server_administrator
+    /// - write              server
+    ///
+    /// Input: [UpdateServerProfileInput]
+    UpdateServerProfile(UpdateServerProfileInput),
+
+    /// Adds public [crate::state::ServerV2] to directory.
+    ///
+    /// Accounts:
+    /// - read, signer       dweller_owner
+    /// - write              server
+    /// - write, derived     server_directory
+    /// - write, derived     directory_entry
+    ///
+    /// Input: [ListServerInput]
+    ListServer(ListServerInput),
+
+    /// Accounts:
+    /// - read, signer       dweller_owner
+    /// - write              server
+    /// - write, derived     server_directory
+    /// - write, derived     directory_entry of server
+    /// - write, derived     directory_entry_last
+    UnlistServer,
+
+    /// Sets [crate::state::ServerMemberV2::nickname], by the member or an administrator.
+    /// Migrates [crate::state::StateVersion::V1] server_member to V2, growing it paid by payer of [with_payer].
+    ///
+    /// Accounts:
+    /// - read, signer       dweller, member or administrator
+    /// - read               server
+    /// - write, derived     server_member
+    /// - read, derived      server_administrator for dweller, or any account if dweller is member
+    ///
+    /// Input: [SetNameInput]
+    SetMemberNickname(SetNameInput),
+
+    /// Sets [crate::state::ChannelHistory::committer], initializes history if needed.
+    ///
+    /// Accounts:
+    /// - read, signer       dweller_administrator
+    /// - read, derived      server_administrator
+    /// - read               server
+    /// - read, derived      server_channel
+    /// - write, derived     channel_history
+    ///
+    /// Input: [SetChannelCommitterInput]
+    SetChannelCommitter(SetChannelCommitterInput),
+
+    /// Appends [crate::state::ChannelCheckpoint], initializes history if needed.
+    ///
+    /// Accounts:
+    /// - read, signer       committer, dweller_administrator or delegated committer
+    /// - read, derived      server_administrator, any account if delegated committer
+    /// - read               server
+    /// - read, derived      server_channel
+    /// - write, derived     channel_history
+    /// - write, derived     channel_checkpoint at [crate::state::ChannelHistory::checkpoints]
+    ///
+    /// Input: [CommitChannelCheckpointInput]
+    CommitChannelCheckpoint(CommitChannelCheckpointInput),
+
+    /// Sets [crate::state::ServerV3::join_requirement], migrates [crate::state::ServerV2] to V3.
+    ///
+    /// Accounts:
+    /// - read, signer       dweller_administrator
+    /// - read, derived      server_administrator
+    /// - write              server
+    /// - write, signer      payer, funds migration
+    /// - read               system_program
+    ///
+    /// Input: [SetJoinRequirementInput]
+    SetJoinRequirement(SetJoinRequirementInput),
+
+    /// Evicts member whose token account no longer meets join requirement, callable by anyone.
+    /// Members without recorded token, e.g. joined before requirement was set, are checked
+    /// against passed token. Server owner is never evicted.
+    ///
+    /// Accounts:
+    /// - write              server
+    /// - write, derived     server_member
+    /// - write, derived     server_member_last
+    /// - write              dweller
+    /// - write, derived     dweller_server
+    /// - write, derived     dweller_server_last
+    /// - read               token, [crate::state::ServerMemberV3::token] if any, else any of dweller
+    /// - write, derived     member_marker, closed into its payer
+    ///
+    /// Also evicts member whose [crate::state::ServerMemberV4::paid_until] passed.
+    RecheckMember,
+
+    /// Sets [crate::state::ServerV4::join_fee], migrates [crate::state::ServerV2] and V3 to V4.
+    /// Creates treasury token account of mint if needed.
+    ///
+    /// Accounts:
+    /// - read, signer       dweller_owner
+    /// - write              server
+    /// - write, signer      payer, funds migration and treasury
+    /// - read               system_program
+    /// - write, derived     treasury, see [crate::state::JoinFee::find_treasury_address]
+    /// - read               mint
+    /// - read               token_program
+    ///
+    /// Input: [SetJoinFeeInput]
+    SetJoinFee(SetJoinFeeInput),
+
+    /// Pays [crate::state::JoinFee::amount] to extend [crate::state::ServerMemberV4::paid_until]
+    /// by [crate::state::JoinFee::period], from now if already expired.
+    ///
+    /// Accounts:
+    /// - read               server
+    /// - write, derived     server_member
+    /// - read, signer       authority, owner of source
+    /// - write              source
+    /// - write, derived     treasury
+    /// - read               token_program
+    RenewMembership,
+
+    /// Transfers fees out of server treasury.
+    ///
+    /// Accounts:
+    /// - read, signer       dweller_owner
+    /// - read               server
+    /// - write, derived     treasury
+    /// - write              destination
+    /// - read               token_program
+    ///
+    /// Input: [WithdrawTreasuryInput]
+    WithdrawTreasury(WithdrawTreasuryInput),
+
+    /// Appends [crate::state::ServerEvent], initializes event counter if needed.
+    ///
+    /// Accounts:
+    /// - read, signer       dweller_administrator
+    /// - read, derived      server_administrator
+    /// - read               server
+    /// - write, derived     server_events
+    /// - write, derived     server_event at [crate::state::ServerEvents::events]
+    /// - read, derived      server_channel, ignored if [CreateServerEventInput::channel] is default
+    ///
+    /// Input: [CreateServerEventInput]
+    CreateServerEvent(CreateServerEventInput),
+
+    /// Marks [crate::state::ServerEvent::cancelled], existing RSVPs are kept.
+    ///
+    /// Accounts:
+    /// - read, signer       dweller_administrator
+    /// - read, derived      server_administrator
+    /// - read               server
+    /// - write, derived     server_event
+    CancelServerEvent,
+
+    /// Appends [crate::state::EventRsvp] of member, once per dweller, until [crate::state::ServerEvent::end].
+    ///
+    /// Accounts:
+    /// - read, signer       dweller
+    /// - read               server
+    /// - read, derived      server_member of dweller
+    /// - write, derived     server_event
+    /// - write, derived     event_rsvp at [crate::state::ServerEvent::attendees]
+    /// - write, derived     event_rsvp_marker, see [crate::state::DwellerMarker]
+    /// - write, signer      payer, funds event_rsvp_marker
+    /// - read               system_program
+    Rsvp,
+
+    /// Removes [crate::state::EventRsvp] of dweller.
+    ///
+    /// Accounts:
+    /// - read, signer       dweller
+    /// - read               server
+    /// - write, derived     server_event
+    /// - write, derived     event_rsvp
+    /// - write, derived     event_rsvp_last
+    /// - write, derived     event_rsvp_marker, closed into its payer
+    CancelRsvp,
+
+    /// Creates [crate::state::DwellerHandle] of dweller, migrates [crate::state::DwellerV2] to V3.
+    ///
+    /// Accounts:
+    /// - write, signer      dweller
+    /// - write, derived     dweller_handle, see [crate::state::DwellerHandle::find_address]
+    /// - write, signer      payer
+    /// - read               system_program
+    ///
+    /// Input: [ClaimHandleInput]
+    ClaimHandle(ClaimHandleInput),
+
+    /// Closes [crate::state::DwellerHandle] of dweller, so anyone can claim it.
+    ///
+    /// Accounts:
+    /// - write, signer      dweller
+    /// - write, derived     dweller_handle
+    /// - write              destination, receives lamports
+    ReleaseHandle,
+
+    /// Moves [crate::state::DwellerHandle] to other dweller without handle, both must sign.
+    ///
+    /// Accounts:
+    /// - write, signer      dweller
+    /// - write, derived     dweller_handle
+    /// - write, signer      dweller_to
+    /// - write, signer      payer, funds migration of dweller_to
+    /// - read               system_program
+    TransferHandle,
+
+    /// Sets [crate::state::DwellerV4::nft_avatar], migrates [crate::state::DwellerV2] and V3 to V4.
+    /// Token must be owned by dweller authority.
+    ///
+    /// Accounts:
+    /// - write, signer      dweller
+    /// - read               token, `spl_nft_erc_721` token
+    /// - read, derived      token_data, `spl_nft_erc_721` data of token
+    /// - write, signer      payer, funds migration
+    /// - read               system_program
+    SetDwellerNftAvatar,
+
+    /// Removes [crate::state::DwellerV4::nft_avatar] no longer owned by dweller authority, callable by anyone.
+    ///
+    /// Accounts:
+    /// - write              dweller
+    /// - read               token, [crate::state::DwellerV4::nft_avatar]
+    ClearStaleAvatar,
+
+    /// Rewrites [crate::state::ListEntry::index] of entries to match their addresses,
+    /// repairing lists corrupted by removals made before indexes were kept on swap.
+    ///
+    /// Accounts:
+    /// - read, signer       dweller_administrator
+    /// - read, derived      server_administrator
+    /// - read               server
+    /// - read, derived      container, server, or server_group of group channels, or server_channel of overwrites
+    /// - write, derived     [ReindexInput::count] entries addressed from [ReindexInput::first] onward
+    ///
+    /// Input: [ReindexInput]
+    Reindex(ReindexInput),
+
+    /// [Instruction::Reindex] for [crate::state::DwellerServer] list of dweller.
+    ///
+    /// Accounts:
+    /// - read, signer       dweller, or its authority, see [with_dweller_authority]
+    /// - write, derived     [ReindexInput::count] entries addressed from [ReindexInput::first] onward
+    ///
+    /// Input: [ReindexInput]
+    ReindexDwellerServers(ReindexInput),
+}
+
+impl Instruction {
+    /// Decodes instruction data.
+    /// Legacy clients serialize variant index and input separately, which is the same layout,
+    /// but may pad data after input, so trailing bytes are ignored until those are migrated.
+    /// Data carries no version tag, tolerating trailing bytes is the only legacy handling.
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        Self::try_from_slice(input).or_else(|_| {
+            let instruction = Self::deserialize(&mut &input[..])?;
+            msg!("Deprecated: trailing instruction data");
+            Ok(instruction)
+        })
+    }
 }
 
 /// Address type
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 pub enum AddressTypeInput {
     /// type
     DwellerServer(u64),
@@ -196,11 +671,44 @@ pub enum AddressTypeInput {
     ServerGroup(u64),
     /// type
     GroupChannel(u64),
+    /// type
+    JoinRequest(u64),
+    /// type
+    ChannelOverwrite(u64),
+    /// singleton, owner is program id
+    ServerDirectory,
+    /// type
+    DirectoryEntry(u64),
+    /// singleton, owner is channel
+    ChannelHistory,
+    /// type
+    ChannelCheckpoint(u64),
+    /// singleton, owner is server
+    ServerAuditLog,
+    /// singleton, owner is server
+    ServerEvents,
+    /// owner is server
+    ServerEvent(u64),
+    /// owner is event
+    EventRsvp(u64),
+}
+
+/// instruction data
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct ReindexInput {
+    /// list and index of first entry, one of server member, member status, administrator, channel,
+    /// group, join request, group channel or channel overwrite, or dweller server
+    pub first: AddressTypeInput,
+    /// number of entries passed, any accounts after them are trailing optional ones
+    pub count: u64,
 }
 
 /// instruction data
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 pub struct CreateGroupInput {
     /// name
     pub name: [u8; 32],
@@ -209,16 +717,36 @@ pub struct CreateGroupInput {
 /// instruction data
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 pub struct AddChannelInput {
-    /// type
+    /// [crate::state::ChannelKind] as byte
+    pub type_id: u8,
+    /// name
+    pub name: [u8; 32],
+}
+
+/// instruction data
+/// [crate::state::StateVersion::V1] channels accept only default `topic_hash`, `position` and `voice`
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct UpdateChannelInput {
+    /// [crate::state::ChannelKind] as byte
     pub type_id: u8,
     /// name
     pub name: [u8; 32],
+    /// IPFS hash of topic
+    pub topic_hash: [u8; 64],
+    /// UI ordering
+    pub position: u64,
+    /// must be default unless kind [crate::state::ChannelKind::is_voice]
+    pub voice: VoiceChannelConfig,
 }
 
 /// instruction data
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 pub struct SetNameInput {
     /// name
     pub name: [u8; 32],
@@ -227,6 +755,7 @@ pub struct SetNameInput {
 /// instruction data
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 pub struct SetDwellerStatusInput {
     /// status
     pub status: [u8; 32],
@@ -236,6 +765,7 @@ pub struct SetDwellerStatusInput {
 /// IPFS hash
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 pub struct SetHashInput {
     /// IPFS
     pub hash: [u8; 64],
@@ -244,6 +774,32 @@ pub struct SetHashInput {
 /// instruction data
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct SetChannelOverwriteInput {
+    /// to
+    pub target: OverwriteTarget,
+    /// only for [OverwriteTarget::Dweller]
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub dweller: Pubkey,
+    /// permission bits granted
+    pub allow: u64,
+    /// permission bits revoked
+    pub deny: u64,
+}
+
+/// instruction data
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct SetJoinPolicyInput {
+    /// policy
+    pub join_policy: JoinPolicy,
+}
+
+/// instruction data
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 pub struct InitializeDwellerInput {
     /// name
     pub name: [u8; 32],
@@ -252,41 +808,170 @@ pub struct InitializeDwellerInput {
 /// instruction data
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
-pub struct InitializeServerInput {
-    /// name
-    pub name: [u8; 32],
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct ListServerInput {
+    /// category
+    pub category: ServerCategory,
+    /// ASCII language code, zero padded
+    pub language: [u8; 8],
+    /// zero padded UTF-8 tags
+    pub tags: [[u8; 16]; 4],
 }
 
-/// [Instruction::InitializeDweller]
-#[allow(clippy::too_many_arguments)]
-pub fn initialize_dweller(
-    dweller: &Pubkey,
-    input: InitializeDwellerInput,
-) -> Result<solana_program::instruction::Instruction, ProgramError> {
-    let mut data = Instruction::InitializeDweller.try_to_vec()?;
-    let mut input = input.try_to_vec()?;
-    data.append(&mut input);
-    let accounts = vec![AccountMeta::new(*dweller, true)];
-    Ok(solana_program::instruction::Instruction {
-        program_id: crate::id(),
-        accounts,
-        data,
-    })
+/// instruction data
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct SetJoinRequirementInput {
+    /// requirement
+    pub join_requirement: JoinRequirement,
 }
 
-/// Create [Instruction::CreateDerivedAccount] instruction
-pub fn create_derived_account(
-    program_id: &Pubkey,
-    payer: &Pubkey,
-    owner_address: &Pubkey,
-    base_program_address: &Pubkey,
-    account_to_create: &Pubkey,
-    input: AddressTypeInput,
-) -> Result<solana_program::instruction::Instruction, ProgramError> {
-    let mut data = Instruction::CreateDerivedAccount.try_to_vec()?;
-    let mut input = input.try_to_vec()?;
-    data.append(&mut input);
-    let accounts = vec![
+/// instruction data
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct CreateServerEventInput {
+    /// title
+    pub title: [u8; 32],
+    /// description
+    pub description_hash: [u8; 64],
+    /// unix timestamp
+    pub start: i64,
+    /// unix timestamp, not before start
+    pub end: i64,
+    /// linked channel, default key if none
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub channel: Pubkey,
+}
+
+/// instruction data
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct ClaimHandleInput {
+    /// see [crate::state::DwellerHandle::normalize]
+    pub handle: String,
+}
+
+/// instruction data
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct SetJoinFeeInput {
+    /// `None` makes joining free
+    pub join_fee: Option<JoinFee>,
+}
+
+/// instruction data
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct WithdrawTreasuryInput {
+    /// mint of treasury
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub mint: Pubkey,
+    /// amount
+    pub amount: u64,
+}
+
+/// instruction data
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct SetChannelCommitterInput {
+    /// default key removes committer
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub committer: Pubkey,
+}
+
+/// instruction data
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct CommitChannelCheckpointInput {
+    /// [crate::merkle::root] of message log
+    pub root: [u8; 32],
+    /// messages in log
+    pub messages: u64,
+}
+
+/// instruction data, `None` keeps field
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct UpdateServerProfileInput {
+    /// name
+    pub name: Option<[u8; 32]>,
+    /// Multihash referencing IPFS hash of photo
+    pub photo_hash: Option<[u8; 64]>,
+    /// Multihash referencing IPFS hash of description
+    pub description_hash: Option<[u8; 64]>,
+    /// Multihash referencing IPFS hash of banner
+    pub banner_hash: Option<[u8; 64]>,
+}
+
+/// instruction data
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct SetDwellerAuthorityInput {
+    /// new authority
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub authority: Pubkey,
+}
+
+/// instruction data
+/// string caps are in [crate::state::DwellerV2]
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct UpdateDwellerProfileInput {
+    /// Multihash referencing IPFS hash of profile banner
+    pub banner_hash: [u8; 64],
+    /// UTF-8
+    pub bio: String,
+    /// UTF-8
+    pub pronouns: String,
+    /// UTF-8
+    pub links: Vec<String>,
+}
+
+/// instruction data
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct InitializeServerInput {
+    /// name
+    pub name: [u8; 32],
+}
+
+/// [Instruction::InitializeDweller]
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_dweller(
+    dweller: &Pubkey,
+    input: InitializeDwellerInput,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::InitializeDweller(input).try_to_vec()?;
+    let accounts = vec![AccountMeta::new(*dweller, true)];
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// Create [Instruction::CreateDerivedAccount] instruction
+pub fn create_derived_account(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    owner_address: &Pubkey,
+    base_program_address: &Pubkey,
+    account_to_create: &Pubkey,
+    input: AddressTypeInput,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::CreateDerivedAccount(input).try_to_vec()?;
+    let accounts = vec![
         AccountMeta::new(*payer, true),
         AccountMeta::new_readonly(*owner_address, false),
         AccountMeta::new_readonly(*base_program_address, false),
@@ -308,16 +993,20 @@ pub fn initialize_server(
     server: &Pubkey,
     dweller_server: &Pubkey,
     server_member: &Pubkey,
+    payer: &Pubkey,
     input: InitializeServerInput,
 ) -> Result<solana_program::instruction::Instruction, ProgramError> {
-    let mut data = Instruction::InitializeServer.try_to_vec()?;
-    let mut input = input.try_to_vec()?;
-    data.append(&mut input);
+    let (member_marker, _) =
+        DwellerMarker::find_address(&crate::id(), ServerMember::SEED, server, dweller_owner);
+    let data = Instruction::InitializeServer(input).try_to_vec()?;
     let accounts = vec![
         AccountMeta::new(*dweller_owner, true),
         AccountMeta::new(*server, true),
         AccountMeta::new(*dweller_server, false),
         AccountMeta::new(*server_member, false),
+        AccountMeta::new(member_marker, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
     ];
 
     Ok(solana_program::instruction::Instruction {
@@ -332,9 +1021,7 @@ pub fn set_dweller_name(
     dweller: &Pubkey,
     input: &SetNameInput,
 ) -> Result<solana_program::instruction::Instruction, ProgramError> {
-    let mut data = Instruction::SetDwellerName.try_to_vec()?;
-    let mut input = input.try_to_vec()?;
-    data.append(&mut input);
+    let data = Instruction::SetDwellerName(input.clone()).try_to_vec()?;
     let accounts = vec![AccountMeta::new(*dweller, true)];
 
     Ok(solana_program::instruction::Instruction {
@@ -349,9 +1036,7 @@ pub fn set_dweller_photo(
     dweller: &Pubkey,
     input: &SetHashInput,
 ) -> Result<solana_program::instruction::Instruction, ProgramError> {
-    let mut data = Instruction::SetDwellerPhoto.try_to_vec()?;
-    let mut input = input.try_to_vec()?;
-    data.append(&mut input);
+    let data = Instruction::SetDwellerPhoto(input.clone()).try_to_vec()?;
     let accounts = vec![AccountMeta::new(*dweller, true)];
 
     Ok(solana_program::instruction::Instruction {
@@ -366,9 +1051,7 @@ pub fn set_dweller_status(
     dweller: &Pubkey,
     input: &SetDwellerStatusInput,
 ) -> Result<solana_program::instruction::Instruction, ProgramError> {
-    let mut data = Instruction::SetDwellerStatus.try_to_vec()?;
-    let mut input = input.try_to_vec()?;
-    data.append(&mut input);
+    let data = Instruction::SetDwellerStatus(input.clone()).try_to_vec()?;
     let accounts = vec![AccountMeta::new(*dweller, true)];
 
     Ok(solana_program::instruction::Instruction {
@@ -386,9 +1069,7 @@ pub fn add_channel(
     server_channel: &Pubkey,
     input: &AddChannelInput,
 ) -> Result<solana_program::instruction::Instruction, ProgramError> {
-    let mut data = Instruction::AddChannel.try_to_vec()?;
-    let mut input = input.try_to_vec()?;
-    data.append(&mut input);
+    let data = Instruction::AddChannel(input.clone()).try_to_vec()?;
     let accounts = vec![
         AccountMeta::new(*dweller_administrator, true),
         AccountMeta::new_readonly(*server_administrator, false),
@@ -435,9 +1116,7 @@ pub fn create_group(
     server_group: &Pubkey,
     input: &CreateGroupInput,
 ) -> Result<solana_program::instruction::Instruction, ProgramError> {
-    let mut data = Instruction::CreateGroup.try_to_vec()?;
-    let mut input = input.try_to_vec()?;
-    data.append(&mut input);
+    let data = Instruction::CreateGroup(input.clone()).try_to_vec()?;
     let accounts = vec![
         AccountMeta::new(*dweller_administrator, true),
         AccountMeta::new_readonly(*server_administrator, false),
@@ -521,7 +1200,7 @@ pub fn remove_channel_from_group(
         AccountMeta::new(*server, false),
         AccountMeta::new_readonly(*dweller_administrator, true),
         AccountMeta::new_readonly(*server_administrator, false),
-        AccountMeta::new_readonly(*server_group, false),
+        AccountMeta::new(*server_group, false),
         AccountMeta::new(*group_channel, false),
         AccountMeta::new(*group_channel_last, false),
     ];
@@ -539,13 +1218,19 @@ pub fn add_admin(
     dweller: &Pubkey,
     server: &Pubkey,
     server_administrator: &Pubkey,
+    payer: &Pubkey,
 ) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let (administrator_marker, _) =
+        DwellerMarker::find_address(&crate::id(), ServerAdministrator::SEED, server, dweller);
     let data = Instruction::AddAdmin.try_to_vec()?;
     let accounts = vec![
         AccountMeta::new(*owner, true),
         AccountMeta::new(*dweller, false),
         AccountMeta::new(*server, false),
         AccountMeta::new(*server_administrator, false),
+        AccountMeta::new(administrator_marker, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
     ];
 
     Ok(solana_program::instruction::Instruction {
@@ -555,19 +1240,23 @@ pub fn add_admin(
     })
 }
 
-/// [Instruction::RemoveAdmin]
+/// [Instruction::RemoveAdmin], `dweller` is administrator being removed
 pub fn remove_admin(
     owner: &Pubkey,
     server: &Pubkey,
     server_administrator: &Pubkey,
     server_administrator_last: &Pubkey,
+    dweller: &Pubkey,
 ) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let (administrator_marker, _) =
+        DwellerMarker::find_address(&crate::id(), ServerAdministrator::SEED, server, dweller);
     let data = Instruction::RemoveAdmin.try_to_vec()?;
     let accounts = vec![
         AccountMeta::new_readonly(*owner, true),
         AccountMeta::new(*server, false),
         AccountMeta::new(*server_administrator, false),
         AccountMeta::new(*server_administrator_last, false),
+        AccountMeta::new(administrator_marker, false),
     ];
 
     Ok(solana_program::instruction::Instruction {
@@ -584,14 +1273,22 @@ pub fn join_server(
     server_member_status: &Pubkey,
     dweller: &Pubkey,
     dweller_server: &Pubkey,
+    server_member_status_last: &Pubkey,
+    payer: &Pubkey,
 ) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let (member_marker, _) =
+        DwellerMarker::find_address(&crate::id(), ServerMember::SEED, server, dweller);
     let data = Instruction::JoinServer.try_to_vec()?;
     let accounts = vec![
         AccountMeta::new(*server, false),
         AccountMeta::new(*server_member, false),
-        AccountMeta::new_readonly(*server_member_status, false),
+        AccountMeta::new(*server_member_status, false),
         AccountMeta::new(*dweller, true),
         AccountMeta::new(*dweller_server, false),
+        AccountMeta::new(*server_member_status_last, false),
+        AccountMeta::new(member_marker, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
     ];
 
     Ok(solana_program::instruction::Instruction {
@@ -610,6 +1307,10 @@ pub fn leave_server(
     dweller_server: &Pubkey,
     dweller_server_last: &Pubkey,
 ) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let (member_marker, _) =
+        DwellerMarker::find_address(&crate::id(), ServerMember::SEED, server, dweller);
+    let (administrator_marker, _) =
+        DwellerMarker::find_address(&crate::id(), ServerAdministrator::SEED, server, dweller);
     let data = Instruction::LeaveServer.try_to_vec()?;
     let accounts = vec![
         AccountMeta::new(*server, false),
@@ -618,6 +1319,8 @@ pub fn leave_server(
         AccountMeta::new(*dweller, true),
         AccountMeta::new(*dweller_server, false),
         AccountMeta::new(*dweller_server_last, false),
+        AccountMeta::new(member_marker, false),
+        AccountMeta::new(administrator_marker, false),
     ];
 
     Ok(solana_program::instruction::Instruction {
@@ -682,9 +1385,7 @@ pub fn set_server_name(
     server_administrator: &Pubkey,
     input: &SetNameInput,
 ) -> Result<solana_program::instruction::Instruction, ProgramError> {
-    let mut data = Instruction::SetServerName.try_to_vec()?;
-    let mut input = input.try_to_vec()?;
-    data.append(&mut input);
+    let data = Instruction::SetServerName(input.clone()).try_to_vec()?;
     let accounts = vec![
         AccountMeta::new(*server, true),
         AccountMeta::new_readonly(*dweller_administrator, true),
@@ -705,9 +1406,7 @@ pub fn set_server_db(
     server_administrator: &Pubkey,
     input: &SetHashInput,
 ) -> Result<solana_program::instruction::Instruction, ProgramError> {
-    let mut data = Instruction::SetServerDb.try_to_vec()?;
-    let mut input = input.try_to_vec()?;
-    data.append(&mut input);
+    let data = Instruction::SetServerDb(input.clone()).try_to_vec()?;
     let accounts = vec![
         AccountMeta::new(*server, true),
         AccountMeta::new_readonly(*dweller_administrator, true),
@@ -720,3 +1419,1097 @@ pub fn set_server_db(
         data,
     })
 }
+
+/// [Instruction::SetJoinPolicy]
+pub fn set_join_policy(
+    dweller_administrator: &Pubkey,
+    server_administrator: &Pubkey,
+    server: &Pubkey,
+    input: &SetJoinPolicyInput,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::SetJoinPolicy(input.clone()).try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*dweller_administrator, true),
+        AccountMeta::new_readonly(*server_administrator, false),
+        AccountMeta::new(*server, false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::RequestJoinServer]
+pub fn request_join_server(
+    server: &Pubkey,
+    dweller: &Pubkey,
+    join_request: &Pubkey,
+    payer: &Pubkey,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let (member_marker, _) =
+        DwellerMarker::find_address(&crate::id(), ServerMember::SEED, server, dweller);
+    let data = Instruction::RequestJoinServer.try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new(*server, false),
+        AccountMeta::new_readonly(*dweller, true),
+        AccountMeta::new(*join_request, false),
+        AccountMeta::new(member_marker, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::ApproveJoinRequest]
+#[allow(clippy::too_many_arguments)]
+pub fn approve_join_request(
+    dweller_administrator: &Pubkey,
+    server_administrator: &Pubkey,
+    server: &Pubkey,
+    join_request: &Pubkey,
+    join_request_last: &Pubkey,
+    server_member: &Pubkey,
+    dweller: &Pubkey,
+    dweller_server: &Pubkey,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let (member_marker, _) =
+        DwellerMarker::find_address(&crate::id(), ServerMember::SEED, server, dweller);
+    let data = Instruction::ApproveJoinRequest.try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*dweller_administrator, true),
+        AccountMeta::new_readonly(*server_administrator, false),
+        AccountMeta::new(*server, false),
+        AccountMeta::new(*join_request, false),
+        AccountMeta::new(*join_request_last, false),
+        AccountMeta::new(*server_member, false),
+        AccountMeta::new(*dweller, false),
+        AccountMeta::new(*dweller_server, false),
+        AccountMeta::new_readonly(member_marker, false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::RejectJoinRequest], `dweller` filed join_request
+pub fn reject_join_request(
+    dweller_administrator: &Pubkey,
+    server_administrator: &Pubkey,
+    server: &Pubkey,
+    join_request: &Pubkey,
+    join_request_last: &Pubkey,
+    dweller: &Pubkey,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let (member_marker, _) =
+        DwellerMarker::find_address(&crate::id(), ServerMember::SEED, server, dweller);
+    let data = Instruction::RejectJoinRequest.try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*dweller_administrator, true),
+        AccountMeta::new_readonly(*server_administrator, false),
+        AccountMeta::new(*server, false),
+        AccountMeta::new(*join_request, false),
+        AccountMeta::new(*join_request_last, false),
+        AccountMeta::new(member_marker, false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::DeclineInvite]
+pub fn decline_invite(
+    server: &Pubkey,
+    dweller: &Pubkey,
+    server_member_status: &Pubkey,
+    server_member_status_last: &Pubkey,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::DeclineInvite.try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new(*server, false),
+        AccountMeta::new_readonly(*dweller, true),
+        AccountMeta::new(*server_member_status, false),
+        AccountMeta::new(*server_member_status_last, false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::UpdateChannel]
+pub fn update_channel(
+    dweller_administrator: &Pubkey,
+    server_administrator: &Pubkey,
+    server: &Pubkey,
+    server_channel: &Pubkey,
+    channel_overwrites: &[&Pubkey],
+    input: &UpdateChannelInput,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::UpdateChannel(input.clone()).try_to_vec()?;
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*dweller_administrator, true),
+        AccountMeta::new_readonly(*server_administrator, false),
+        AccountMeta::new_readonly(*server, false),
+        AccountMeta::new(*server_channel, false),
+    ];
+
+    for account in channel_overwrites {
+        accounts.push(AccountMeta::new_readonly(**account, false));
+    }
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::SetChannelOverwrite]
+#[allow(clippy::too_many_arguments)]
+pub fn set_channel_overwrite(
+    dweller_administrator: &Pubkey,
+    server_administrator: &Pubkey,
+    server: &Pubkey,
+    server_channel: &Pubkey,
+    channel_overwrite: &Pubkey,
+    channel_overwrites: &[&Pubkey],
+    input: &SetChannelOverwriteInput,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::SetChannelOverwrite(input.clone()).try_to_vec()?;
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*dweller_administrator, true),
+        AccountMeta::new_readonly(*server_administrator, false),
+        AccountMeta::new_readonly(*server, false),
+        AccountMeta::new(*server_channel, false),
+        AccountMeta::new(*channel_overwrite, false),
+    ];
+
+    for account in channel_overwrites {
+        accounts.push(AccountMeta::new_readonly(**account, false));
+    }
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::RemoveChannelOverwrite]
+#[allow(clippy::too_many_arguments)]
+pub fn remove_channel_overwrite(
+    dweller_administrator: &Pubkey,
+    server_administrator: &Pubkey,
+    server: &Pubkey,
+    server_channel: &Pubkey,
+    channel_overwrite: &Pubkey,
+    channel_overwrite_last: &Pubkey,
+    channel_overwrites: &[&Pubkey],
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::RemoveChannelOverwrite.try_to_vec()?;
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*dweller_administrator, true),
+        AccountMeta::new_readonly(*server_administrator, false),
+        AccountMeta::new_readonly(*server, false),
+        AccountMeta::new(*server_channel, false),
+        AccountMeta::new(*channel_overwrite, false),
+        AccountMeta::new(*channel_overwrite_last, false),
+    ];
+
+    for account in channel_overwrites {
+        accounts.push(AccountMeta::new_readonly(**account, false));
+    }
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::RenameGroup]
+pub fn rename_group(
+    dweller_administrator: &Pubkey,
+    server_administrator: &Pubkey,
+    server: &Pubkey,
+    server_group: &Pubkey,
+    input: &SetNameInput,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::RenameGroup(input.clone()).try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*dweller_administrator, true),
+        AccountMeta::new_readonly(*server_administrator, false),
+        AccountMeta::new_readonly(*server, false),
+        AccountMeta::new(*server_group, false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::MoveGroupChannel]
+pub fn move_group_channel(
+    dweller_administrator: &Pubkey,
+    server_administrator: &Pubkey,
+    server: &Pubkey,
+    server_group: &Pubkey,
+    group_channel: &Pubkey,
+    group_channel_other: &Pubkey,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::MoveGroupChannel.try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*dweller_administrator, true),
+        AccountMeta::new_readonly(*server_administrator, false),
+        AccountMeta::new_readonly(*server, false),
+        AccountMeta::new_readonly(*server_group, false),
+        AccountMeta::new(*group_channel, false),
+        AccountMeta::new(*group_channel_other, false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::UpdateDwellerProfile]
+pub fn update_dweller_profile(
+    dweller: &Pubkey,
+    payer: &Pubkey,
+    input: &UpdateDwellerProfileInput,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::UpdateDwellerProfile(input.clone()).try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new(*dweller, true),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::MigrateDweller]
+pub fn migrate_dweller(
+    dweller: &Pubkey,
+    payer: &Pubkey,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::MigrateDweller.try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new(*dweller, true),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::SetDwellerAuthority], signed by dweller
+pub fn set_dweller_authority(
+    dweller: &Pubkey,
+    input: &SetDwellerAuthorityInput,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::SetDwellerAuthority(input.clone()).try_to_vec()?;
+    let accounts = vec![AccountMeta::new(*dweller, true)];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// Makes instruction signed by rotated [crate::state::DwellerV2::authority] instead of dweller.
+pub fn with_dweller_authority(
+    mut instruction: solana_program::instruction::Instruction,
+    dweller: &Pubkey,
+    authority: &Pubkey,
+) -> solana_program::instruction::Instruction {
+    for account in instruction.accounts.iter_mut() {
+        if account.pubkey == *dweller {
+            account.is_signer = false;
+        }
+    }
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(*authority, true));
+    instruction
+}
+
+/// Passes server_member of dweller_administrator proving administrator is still member of server.
+pub fn with_administrator_member(
+    mut instruction: solana_program::instruction::Instruction,
+    server_member: &Pubkey,
+) -> solana_program::instruction::Instruction {
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(*server_member, false));
+    instruction
+}
+
+/// Passes server_administrator of leaving dweller and last server_administrator to [Instruction::LeaveServer].
+pub fn with_server_administrator(
+    mut instruction: solana_program::instruction::Instruction,
+    server_administrator: &Pubkey,
+    server_administrator_last: &Pubkey,
+) -> solana_program::instruction::Instruction {
+    instruction
+        .accounts
+        .push(AccountMeta::new(*server_administrator, false));
+    instruction
+        .accounts
+        .push(AccountMeta::new(*server_administrator_last, false));
+    instruction
+}
+
+/// Passes [crate::state::RentPayer] of removed list entries receiving rent of closed accounts,
+/// removal fails without it.
+pub fn with_rent_receiver(
+    mut instruction: solana_program::instruction::Instruction,
+    receiver: &Pubkey,
+) -> solana_program::instruction::Instruction {
+    instruction
+        .accounts
+        .push(AccountMeta::new(*receiver, false));
+    instruction
+}
+
+/// Passes `payer` creating not yet existing list entries of `containers`.
+pub fn with_payer(
+    mut instruction: solana_program::instruction::Instruction,
+    payer: &Pubkey,
+    containers: &[&Pubkey],
+) -> solana_program::instruction::Instruction {
+    instruction.accounts.push(AccountMeta::new(*payer, true));
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(system_program::id(), false));
+    for container in containers {
+        let (base, _) =
+            Pubkey::find_program_address(&[&container.to_bytes()[..32]], &instruction.program_id);
+        instruction
+            .accounts
+            .push(AccountMeta::new_readonly(base, false));
+    }
+    instruction
+}
+
+/// Makes privileged instruction append to [crate::state::ServerAuditLog], required by all of them.
+pub fn with_server_audit_log(
+    mut instruction: solana_program::instruction::Instruction,
+    server_audit_log: &Pubkey,
+) -> solana_program::instruction::Instruction {
+    instruction
+        .accounts
+        .push(AccountMeta::new(*server_audit_log, false));
+    instruction
+}
+
+/// [Instruction::SetServerPhoto]
+pub fn set_server_photo(
+    dweller_administrator: &Pubkey,
+    server_administrator: &Pubkey,
+    server: &Pubkey,
+    input: &SetHashInput,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::SetServerPhoto(input.clone()).try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*dweller_administrator, true),
+        AccountMeta::new_readonly(*server_administrator, false),
+        AccountMeta::new(*server, false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::SetServerDescription]
+pub fn set_server_description(
+    dweller_administrator: &Pubkey,
+    server_administrator: &Pubkey,
+    server: &Pubkey,
+    input: &SetHashInput,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::SetServerDescription(input.clone()).try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*dweller_administrator, true),
+        AccountMeta::new_readonly(*server_administrator, false),
+        AccountMeta::new(*server, false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::SetServerBanner]
+pub fn set_server_banner(
+    dweller_administrator: &Pubkey,
+    server_administrator: &Pubkey,
+    server: &Pubkey,
+    input: &SetHashInput,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::SetServerBanner(input.clone()).try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*dweller_administrator, true),
+        AccountMeta::new_readonly(*server_administrator, false),
+        AccountMeta::new(*server, false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::UpdateServerProfile]
+pub fn update_server_profile(
+    dweller_administrator: &Pubkey,
+    server_administrator: &Pubkey,
+    server: &Pubkey,
+    input: &UpdateServerProfileInput,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::UpdateServerProfile(input.clone()).try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*dweller_administrator, true),
+        AccountMeta::new_readonly(*server_administrator, false),
+        AccountMeta::new(*server, false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::ListServer]
+pub fn list_server(
+    dweller_owner: &Pubkey,
+    server: &Pubkey,
+    server_directory: &Pubkey,
+    directory_entry: &Pubkey,
+    input: &ListServerInput,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::ListServer(input.clone()).try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*dweller_owner, true),
+        AccountMeta::new(*server, false),
+        AccountMeta::new(*server_directory, false),
+        AccountMeta::new(*directory_entry, false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::UnlistServer]
+pub fn unlist_server(
+    dweller_owner: &Pubkey,
+    server: &Pubkey,
+    server_directory: &Pubkey,
+    directory_entry: &Pubkey,
+    directory_entry_last: &Pubkey,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::UnlistServer.try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*dweller_owner, true),
+        AccountMeta::new(*server, false),
+        AccountMeta::new(*server_directory, false),
+        AccountMeta::new(*directory_entry, false),
+        AccountMeta::new(*directory_entry_last, false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::SetMemberNickname]
+pub fn set_member_nickname(
+    dweller: &Pubkey,
+    server: &Pubkey,
+    server_member: &Pubkey,
+    server_administrator: &Pubkey,
+    input: &SetNameInput,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::SetMemberNickname(input.clone()).try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*dweller, true),
+        AccountMeta::new_readonly(*server, false),
+        AccountMeta::new(*server_member, false),
+        AccountMeta::new_readonly(*server_administrator, false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::SetChannelCommitter]
+pub fn set_channel_committer(
+    dweller_administrator: &Pubkey,
+    server_administrator: &Pubkey,
+    server: &Pubkey,
+    server_channel: &Pubkey,
+    channel_history: &Pubkey,
+    input: &SetChannelCommitterInput,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::SetChannelCommitter(input.clone()).try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*dweller_administrator, true),
+        AccountMeta::new_readonly(*server_administrator, false),
+        AccountMeta::new_readonly(*server, false),
+        AccountMeta::new_readonly(*server_channel, false),
+        AccountMeta::new(*channel_history, false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::CommitChannelCheckpoint]
+pub fn commit_channel_checkpoint(
+    committer: &Pubkey,
+    server_administrator: &Pubkey,
+    server: &Pubkey,
+    server_channel: &Pubkey,
+    channel_history: &Pubkey,
+    channel_checkpoint: &Pubkey,
+    input: &CommitChannelCheckpointInput,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::CommitChannelCheckpoint(input.clone()).try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*committer, true),
+        AccountMeta::new_readonly(*server_administrator, false),
+        AccountMeta::new_readonly(*server, false),
+        AccountMeta::new_readonly(*server_channel, false),
+        AccountMeta::new(*channel_history, false),
+        AccountMeta::new(*channel_checkpoint, false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::SetJoinRequirement]
+pub fn set_join_requirement(
+    dweller_administrator: &Pubkey,
+    server_administrator: &Pubkey,
+    server: &Pubkey,
+    payer: &Pubkey,
+    input: &SetJoinRequirementInput,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::SetJoinRequirement(input.clone()).try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*dweller_administrator, true),
+        AccountMeta::new_readonly(*server_administrator, false),
+        AccountMeta::new(*server, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::RecheckMember]
+pub fn recheck_member(
+    server: &Pubkey,
+    server_member: &Pubkey,
+    server_member_last: &Pubkey,
+    dweller: &Pubkey,
+    dweller_server: &Pubkey,
+    dweller_server_last: &Pubkey,
+    token: &Pubkey,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let (member_marker, _) =
+        DwellerMarker::find_address(&crate::id(), ServerMember::SEED, server, dweller);
+    let data = Instruction::RecheckMember.try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new(*server, false),
+        AccountMeta::new(*server_member, false),
+        AccountMeta::new(*server_member_last, false),
+        AccountMeta::new(*dweller, false),
+        AccountMeta::new(*dweller_server, false),
+        AccountMeta::new(*dweller_server_last, false),
+        AccountMeta::new_readonly(*token, false),
+        AccountMeta::new(member_marker, false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// Passes token account meeting [crate::state::ServerV3::join_requirement] to
+/// [Instruction::JoinServer] or [Instruction::ApproveJoinRequest].
+pub fn with_join_requirement_token(
+    mut instruction: solana_program::instruction::Instruction,
+    token: &Pubkey,
+) -> solana_program::instruction::Instruction {
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(*token, false));
+    instruction
+}
+
+/// [Instruction::SetJoinFee]
+pub fn set_join_fee(
+    dweller_owner: &Pubkey,
+    server: &Pubkey,
+    payer: &Pubkey,
+    mint: &Pubkey,
+    input: &SetJoinFeeInput,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::SetJoinFee(input.clone()).try_to_vec()?;
+    let (treasury, _) = JoinFee::find_treasury_address(&crate::id(), server, mint);
+    let accounts = vec![
+        AccountMeta::new_readonly(*dweller_owner, true),
+        AccountMeta::new(*server, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(treasury, false),
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::RenewMembership]
+pub fn renew_membership(
+    server: &Pubkey,
+    server_member: &Pubkey,
+    authority: &Pubkey,
+    source: &Pubkey,
+    mint: &Pubkey,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::RenewMembership.try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*server, false),
+        AccountMeta::new(*server_member, false),
+        AccountMeta::new_readonly(*authority, true),
+    ];
+
+    Ok(with_join_fee(
+        solana_program::instruction::Instruction {
+            program_id: crate::id(),
+            accounts,
+            data,
+        },
+        server,
+        source,
+        mint,
+    ))
+}
+
+/// [Instruction::WithdrawTreasury]
+pub fn withdraw_treasury(
+    dweller_owner: &Pubkey,
+    server: &Pubkey,
+    destination: &Pubkey,
+    input: &WithdrawTreasuryInput,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::WithdrawTreasury(input.clone()).try_to_vec()?;
+    let (treasury, _) = JoinFee::find_treasury_address(&crate::id(), server, &input.mint);
+    let accounts = vec![
+        AccountMeta::new_readonly(*dweller_owner, true),
+        AccountMeta::new_readonly(*server, false),
+        AccountMeta::new(treasury, false),
+        AccountMeta::new(*destination, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// Passes `source` token account of signer paying [crate::state::ServerV4::join_fee] with treasury of `mint` to
+/// [Instruction::JoinServer] or [Instruction::RenewMembership].
+pub fn with_join_fee(
+    mut instruction: solana_program::instruction::Instruction,
+    server: &Pubkey,
+    source: &Pubkey,
+    mint: &Pubkey,
+) -> solana_program::instruction::Instruction {
+    let (treasury, _) = JoinFee::find_treasury_address(&crate::id(), server, mint);
+    instruction.accounts.push(AccountMeta::new(*source, false));
+    instruction.accounts.push(AccountMeta::new(treasury, false));
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(spl_token::id(), false));
+    instruction
+}
+
+/// [Instruction::CreateServerEvent]
+pub fn create_server_event(
+    dweller_administrator: &Pubkey,
+    server_administrator: &Pubkey,
+    server: &Pubkey,
+    server_events: &Pubkey,
+    server_event: &Pubkey,
+    server_channel: &Pubkey,
+    input: &CreateServerEventInput,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::CreateServerEvent(input.clone()).try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*dweller_administrator, true),
+        AccountMeta::new_readonly(*server_administrator, false),
+        AccountMeta::new_readonly(*server, false),
+        AccountMeta::new(*server_events, false),
+        AccountMeta::new(*server_event, false),
+        AccountMeta::new_readonly(*server_channel, false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::CancelServerEvent]
+pub fn cancel_server_event(
+    dweller_administrator: &Pubkey,
+    server_administrator: &Pubkey,
+    server: &Pubkey,
+    server_event: &Pubkey,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::CancelServerEvent.try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*dweller_administrator, true),
+        AccountMeta::new_readonly(*server_administrator, false),
+        AccountMeta::new_readonly(*server, false),
+        AccountMeta::new(*server_event, false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::Rsvp]
+pub fn rsvp(
+    dweller: &Pubkey,
+    server: &Pubkey,
+    server_member: &Pubkey,
+    server_event: &Pubkey,
+    event_rsvp: &Pubkey,
+    payer: &Pubkey,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let (event_rsvp_marker, _) =
+        DwellerMarker::find_address(&crate::id(), EventRsvp::SEED, server_event, dweller);
+    let data = Instruction::Rsvp.try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*dweller, true),
+        AccountMeta::new_readonly(*server, false),
+        AccountMeta::new_readonly(*server_member, false),
+        AccountMeta::new(*server_event, false),
+        AccountMeta::new(*event_rsvp, false),
+        AccountMeta::new(event_rsvp_marker, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::CancelRsvp]
+pub fn cancel_rsvp(
+    dweller: &Pubkey,
+    server: &Pubkey,
+    server_event: &Pubkey,
+    event_rsvp: &Pubkey,
+    event_rsvp_last: &Pubkey,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let (event_rsvp_marker, _) =
+        DwellerMarker::find_address(&crate::id(), EventRsvp::SEED, server_event, dweller);
+    let data = Instruction::CancelRsvp.try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*dweller, true),
+        AccountMeta::new_readonly(*server, false),
+        AccountMeta::new(*server_event, false),
+        AccountMeta::new(*event_rsvp, false),
+        AccountMeta::new(*event_rsvp_last, false),
+        AccountMeta::new(event_rsvp_marker, false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::ClaimHandle]
+pub fn claim_handle(
+    dweller: &Pubkey,
+    payer: &Pubkey,
+    input: &ClaimHandleInput,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let (dweller_handle, _) = DwellerHandle::find_address(&crate::id(), &input.handle)?;
+    let data = Instruction::ClaimHandle(input.clone()).try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new(*dweller, true),
+        AccountMeta::new(dweller_handle, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::ReleaseHandle]
+pub fn release_handle(
+    dweller: &Pubkey,
+    dweller_handle: &Pubkey,
+    destination: &Pubkey,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::ReleaseHandle.try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new(*dweller, true),
+        AccountMeta::new(*dweller_handle, false),
+        AccountMeta::new(*destination, false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::TransferHandle]
+pub fn transfer_handle(
+    dweller: &Pubkey,
+    dweller_handle: &Pubkey,
+    dweller_to: &Pubkey,
+    payer: &Pubkey,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::TransferHandle.try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new(*dweller, true),
+        AccountMeta::new(*dweller_handle, false),
+        AccountMeta::new(*dweller_to, true),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::SetDwellerNftAvatar]
+pub fn set_dweller_nft_avatar(
+    dweller: &Pubkey,
+    token: &Pubkey,
+    payer: &Pubkey,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let token_data = Pubkey::create_with_seed(
+        token,
+        spl_nft_erc_721::processor::Processor::TOKEN_DATA_SEED,
+        &spl_nft_erc_721::id(),
+    )?;
+    let data = Instruction::SetDwellerNftAvatar.try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new(*dweller, true),
+        AccountMeta::new_readonly(*token, false),
+        AccountMeta::new_readonly(token_data, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::ClearStaleAvatar]
+pub fn clear_stale_avatar(
+    dweller: &Pubkey,
+    token: &Pubkey,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::ClearStaleAvatar.try_to_vec()?;
+    let accounts = vec![
+        AccountMeta::new(*dweller, false),
+        AccountMeta::new_readonly(*token, false),
+    ];
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::Reindex]
+pub fn reindex(
+    dweller_administrator: &Pubkey,
+    server_administrator: &Pubkey,
+    server: &Pubkey,
+    container: &Pubkey,
+    entries: &[&Pubkey],
+    input: &ReindexInput,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::Reindex(input.clone()).try_to_vec()?;
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*dweller_administrator, true),
+        AccountMeta::new_readonly(*server_administrator, false),
+        AccountMeta::new_readonly(*server, false),
+        AccountMeta::new_readonly(*container, false),
+    ];
+
+    for account in entries {
+        accounts.push(AccountMeta::new(**account, false));
+    }
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+/// [Instruction::ReindexDwellerServers]
+pub fn reindex_dweller_servers(
+    dweller: &Pubkey,
+    entries: &[&Pubkey],
+    input: &ReindexInput,
+) -> Result<solana_program::instruction::Instruction, ProgramError> {
+    let data = Instruction::ReindexDwellerServers(input.clone()).try_to_vec()?;
+    let mut accounts = vec![AccountMeta::new_readonly(*dweller, true)];
+
+    for account in entries {
+        accounts.push(AccountMeta::new(**account, false));
+    }
+
+    Ok(solana_program::instruction::Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn legacy_encoding() {
+        let input = AddChannelInput {
+            name: [7; 32],
+            type_id: 1,
+        };
+        // variant index and input serialized separately
+        let mut data = vec![6];
+        data.append(&mut input.try_to_vec().unwrap());
+        assert_eq!(
+            data,
+            Instruction::AddChannel(input.clone()).try_to_vec().unwrap()
+        );
+        assert_eq!(
+            Instruction::unpack(&data).unwrap(),
+            Instruction::AddChannel(input.clone())
+        );
+
+        data.extend_from_slice(&[0; 8]);
+        assert_eq!(
+            Instruction::unpack(&data).unwrap(),
+            Instruction::AddChannel(input)
+        );
+        assert_eq!(
+            Instruction::unpack(&[14, 0]).unwrap(),
+            Instruction::JoinServer
+        );
+        assert!(Instruction::unpack(&[6]).is_err());
+    }
+
+    #[test]
+    fn js_padded_encoding() {
+        // js client encodes into buffer of largest variant span, 1 + 32 of InitializeServer
+        let mut data = [0; 33];
+        data[1] = 2;
+        data[2] = 5;
+        assert_eq!(
+            Instruction::unpack(&data).unwrap(),
+            Instruction::CreateDerivedAccount(AddressTypeInput::ServerMember(5))
+        );
+
+        let mut data = [0; 33];
+        data[0] = 14;
+        assert_eq!(Instruction::unpack(&data).unwrap(), Instruction::JoinServer);
+    }
+}
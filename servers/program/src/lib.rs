@@ -3,10 +3,14 @@
 
 mod borsh;
 pub mod error;
+pub mod fuzz;
+#[cfg(feature = "idl")]
+pub mod idl;
 
 /// instruction
 pub mod instruction;
 mod math;
+pub mod merkle;
 mod prelude;
 pub mod processor;
 
@@ -0,0 +1,95 @@
+//! Merkle tree of off chain channel messages, root is stored in [crate::state::ChannelCheckpoint]
+
+use solana_program::hash::hashv;
+
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+/// leaf of message bytes
+pub fn leaf_hash(message: &[u8]) -> [u8; 32] {
+    hashv(&[LEAF_PREFIX, message]).to_bytes()
+}
+
+/// parent of two nodes
+pub fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    hashv(&[NODE_PREFIX, left, right]).to_bytes()
+}
+
+fn next_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|x| node_hash(&x[0], x.get(1).unwrap_or(&x[0])))
+        .collect()
+}
+
+/// root of leaves, last node of odd level is paired with itself, empty tree is zeroes
+pub fn root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    if leaves.is_empty() {
+        return [0; 32];
+    }
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    level[0]
+}
+
+/// siblings from leaf at `index` up to root, `None` if out of range
+pub fn proof(leaves: &[[u8; 32]], mut index: usize) -> Option<Vec<[u8; 32]>> {
+    if index >= leaves.len() {
+        return None;
+    }
+    let mut siblings = Vec::new();
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        let sibling = level.get(index ^ 1).unwrap_or(&level[index]);
+        siblings.push(*sibling);
+        level = next_level(&level);
+        index /= 2;
+    }
+    Some(siblings)
+}
+
+/// true if message at `index` of log is included into `root`
+pub fn verify_inclusion(
+    root: &[u8; 32],
+    message: &[u8],
+    mut index: u64,
+    proof: &[[u8; 32]],
+) -> bool {
+    let mut node = leaf_hash(message);
+    for sibling in proof {
+        node = if index & 1 == 0 {
+            node_hash(&node, sibling)
+        } else {
+            node_hash(sibling, &node)
+        };
+        index >>= 1;
+    }
+    index == 0 && node == *root
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn inclusion() {
+        let messages: Vec<Vec<u8>> = (0..5u8).map(|x| vec![x; 3]).collect();
+        let leaves: Vec<_> = messages.iter().map(|x| leaf_hash(x)).collect();
+        let root = root(&leaves);
+
+        for (index, message) in messages.iter().enumerate() {
+            let proof = proof(&leaves, index).unwrap();
+            assert!(verify_inclusion(&root, message, index as u64, &proof));
+            assert!(!verify_inclusion(&root, &[9], index as u64, &proof));
+        }
+        assert!(!verify_inclusion(
+            &root,
+            &messages[0],
+            1,
+            &proof(&leaves, 0).unwrap()
+        ));
+        assert!(proof(&leaves, 5).is_none());
+    }
+}
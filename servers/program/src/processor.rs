@@ -1,17 +1,32 @@
 //! Program state processor
 
+use borsh::BorshSerialize;
+use num_traits::FromPrimitive;
 use solana_program::{
-    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
-    pubkey::Pubkey, rent::Rent, system_program, sysvar::Sysvar,
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program,
+    sysvar::Sysvar,
 };
 
 use super::borsh::*;
 
 use crate::{
-    borsh::{AccountWithBorsh, BorshSerializeConst},
+    borsh::{AccountWithBorsh, AccountWithZeroCopy, BorshSerializeConst},
     error::Error,
     instruction::*,
-    program::{create_index_with_seed, create_seeded_rent_except_account, swap_accounts},
+    program::{
+        close_account, create_base_index_with_seed, create_index_with_seed,
+        create_program_derived_account, create_seeded_list_entry_account,
+        create_seeded_rent_except_account, find_payer, resize_rent_exempt_account, swap_accounts,
+    },
     state::*,
 };
 
@@ -25,6 +40,9 @@ impl Processor {
         dweller: &AccountInfo<'a>,
         input: &InitializeDwellerInput,
     ) -> ProgramResult {
+        if !dweller.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
         let mut data = dweller.try_borrow_mut_data()?;
         let mut state = Dweller::deserialize_const(&data)?;
         if state.version == StateVersion::Uninitialized {
@@ -38,14 +56,15 @@ impl Processor {
     }
 
     fn set_dweller_name<'a>(
-        _program_id: &Pubkey,
+        program_id: &Pubkey,
         dweller: &AccountInfo<'a>,
         input: &SetNameInput,
+        signers: &[AccountInfo<'a>],
     ) -> ProgramResult {
-        if dweller.is_signer {
+        if is_dweller_authority(program_id, dweller, signers)? {
             let mut data = dweller.try_borrow_mut_data()?;
             let mut state = Dweller::deserialize_const(&data)?;
-            if state.version == StateVersion::V1 {
+            if state.version != StateVersion::Uninitialized {
                 state.name = input.name;
                 state.serialize_const(&mut data)?;
                 Ok(())
@@ -58,14 +77,15 @@ impl Processor {
     }
 
     fn set_dweller_photo<'a>(
-        _program_id: &Pubkey,
+        program_id: &Pubkey,
         dweller: &AccountInfo<'a>,
         input: &SetHashInput,
+        signers: &[AccountInfo<'a>],
     ) -> ProgramResult {
-        if dweller.is_signer {
+        if is_dweller_authority(program_id, dweller, signers)? {
             let mut data = dweller.try_borrow_mut_data()?;
             let mut state = Dweller::deserialize_const(&data)?;
-            if state.version == StateVersion::V1 {
+            if state.version != StateVersion::Uninitialized {
                 state.photo_hash = input.hash;
                 state.serialize_const(&mut data)?;
                 Ok(())
@@ -77,22 +97,134 @@ impl Processor {
         }
     }
 
+    fn update_dweller_profile<'a>(
+        program_id: &Pubkey,
+        dweller: &AccountInfo<'a>,
+        payer: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        input: &UpdateDwellerProfileInput,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        if !is_dweller_authority(program_id, dweller, signers)? {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let version = dweller.read_data_with_borsh::<Dweller>()?.version;
+        let mut state = match version {
+            StateVersion::V2 | StateVersion::V3 | StateVersion::V4 => {
+                dweller.read_data_with_borsh::<DwellerV2>()?
+            }
+            StateVersion::V1 => return Err(Error::UnsupportedStateVersion.into()),
+            StateVersion::Uninitialized => return Err(ProgramError::UninitializedAccount),
+        };
+        state.banner_hash = input.banner_hash;
+        state.bio = input.bio.clone();
+        state.pronouns = input.pronouns.clone();
+        state.links = input.links.clone();
+        if !state.is_valid() {
+            return Err(Error::DwellerProfileFieldTooLong.into());
+        }
+
+        match version {
+            StateVersion::V4 => {
+                let nft_avatar = dweller_nft_avatar(dweller)?;
+                let state = DwellerV3::from_v2(state, dweller_handle(dweller)?);
+                write_dweller(
+                    dweller,
+                    payer,
+                    system_program,
+                    &DwellerV4::from_v3(state, nft_avatar),
+                )
+            }
+            StateVersion::V3 => {
+                let handle = dweller_handle(dweller)?;
+                write_dweller(
+                    dweller,
+                    payer,
+                    system_program,
+                    &DwellerV3::from_v2(state, handle),
+                )
+            }
+            _ => write_dweller(dweller, payer, system_program, &state),
+        }
+    }
+
+    fn migrate_dweller<'a>(
+        program_id: &Pubkey,
+        dweller: &AccountInfo<'a>,
+        payer: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        if !is_dweller_authority(program_id, dweller, signers)? {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let state = dweller.read_data_with_borsh::<Dweller>()?;
+        match state.version {
+            StateVersion::V1 => write_dweller(
+                dweller,
+                payer,
+                system_program,
+                &DwellerV2::from_v1(state, *dweller.key),
+            ),
+            StateVersion::V2 | StateVersion::V3 | StateVersion::V4 => {
+                Err(ProgramError::AccountAlreadyInitialized)
+            }
+            StateVersion::Uninitialized => Err(ProgramError::UninitializedAccount),
+        }
+    }
+
+    fn set_dweller_authority<'a>(
+        program_id: &Pubkey,
+        dweller: &AccountInfo<'a>,
+        input: &SetDwellerAuthorityInput,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        if !is_dweller_authority(program_id, dweller, signers)? {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let (mut data, state) = dweller.read_data_with_borsh_mut::<Dweller>()?;
+        match state.version {
+            // authority is at same offset in later versions
+            StateVersion::V2 | StateVersion::V3 | StateVersion::V4 => {
+                let mut state = DwellerV2::deserialize_const(&data)?;
+                state.authority = input.authority;
+                state.serialize_const(&mut data)?;
+                Ok(())
+            }
+            StateVersion::V1 => Err(Error::UnsupportedStateVersion.into()),
+            StateVersion::Uninitialized => Err(ProgramError::UninitializedAccount),
+        }
+    }
+
     fn set_server_name<'a>(
         program_id: &Pubkey,
         dweller_administrator: &AccountInfo<'a>,
         server_administrator: &AccountInfo<'a>,
         server: &AccountInfo<'a>,
         input: &SetNameInput,
+        signers: &[AccountInfo<'a>],
     ) -> ProgramResult {
         require_admin(
             program_id,
             dweller_administrator,
             server,
             server_administrator,
+            signers,
+        )?;
+        append_audit_log(
+            program_id,
+            server,
+            signers,
+            dweller_administrator.key,
+            AuditAction::UpdateServerProfile,
+            server.key,
         )?;
         let mut data = server.try_borrow_mut_data()?;
         let mut state = Server::deserialize_const(&data)?;
-        if state.version == StateVersion::V1 {
+        if state.version != StateVersion::Uninitialized {
             state.name = input.name;
             state.serialize_const(&mut data)?;
             Ok(())
@@ -107,16 +239,26 @@ impl Processor {
         server_administrator: &AccountInfo<'a>,
         server: &AccountInfo<'a>,
         input: &SetHashInput,
+        signers: &[AccountInfo<'a>],
     ) -> ProgramResult {
         require_admin(
             program_id,
             dweller_administrator,
             server,
             server_administrator,
+            signers,
+        )?;
+        append_audit_log(
+            program_id,
+            server,
+            signers,
+            dweller_administrator.key,
+            AuditAction::SetServerDb,
+            server.key,
         )?;
         let mut data = server.try_borrow_mut_data()?;
         let mut state = Server::deserialize_const(&data)?;
-        if state.version == StateVersion::V1 {
+        if state.version != StateVersion::Uninitialized {
             state.db_hash = input.hash;
             state.serialize_const(&mut data)?;
             Ok(())
@@ -125,791 +267,3239 @@ impl Processor {
         }
     }
 
-    fn set_dweller_status<'a>(
-        _program_id: &Pubkey,
-        dweller: &AccountInfo<'a>,
-        input: &SetDwellerStatusInput,
+    fn update_server_profile<'a>(
+        program_id: &Pubkey,
+        dweller_administrator: &AccountInfo<'a>,
+        server_administrator: &AccountInfo<'a>,
+        server: &AccountInfo<'a>,
+        input: &UpdateServerProfileInput,
+        signers: &[AccountInfo<'a>],
     ) -> ProgramResult {
-        if dweller.is_signer {
-            let mut data = dweller.try_borrow_mut_data()?;
-            let mut state = Dweller::deserialize_const(&data)?;
-            if state.version == StateVersion::V1 {
-                state.status = input.status;
+        require_admin(
+            program_id,
+            dweller_administrator,
+            server,
+            server_administrator,
+            signers,
+        )?;
+        append_audit_log(
+            program_id,
+            server,
+            signers,
+            dweller_administrator.key,
+            AuditAction::UpdateServerProfile,
+            server.key,
+        )?;
+        let (mut data, mut state) = server.read_data_with_borsh_mut::<Server>()?;
+        if let Some(name) = input.name {
+            state.name = name;
+        }
+        if let Some(photo_hash) = input.photo_hash {
+            state.photo_hash = photo_hash;
+        }
+
+        match state.version {
+            StateVersion::V2 | StateVersion::V3 | StateVersion::V4 => {
+                let mut state_v2 = ServerV2::deserialize_const(&data)?;
+                state_v2.name = state.name;
+                state_v2.photo_hash = state.photo_hash;
+                if let Some(description_hash) = input.description_hash {
+                    state_v2.description_hash = description_hash;
+                }
+                if let Some(banner_hash) = input.banner_hash {
+                    state_v2.banner_hash = banner_hash;
+                }
+                state_v2.serialize_const(&mut data)?;
+                Ok(())
+            }
+            StateVersion::V1 => {
+                if input.description_hash.is_some() || input.banner_hash.is_some() {
+                    return Err(Error::UnsupportedStateVersion.into());
+                }
                 state.serialize_const(&mut data)?;
                 Ok(())
-            } else {
-                Err(ProgramError::UninitializedAccount)
             }
-        } else {
-            Err(ProgramError::MissingRequiredSignature)
+            StateVersion::Uninitialized => Err(ProgramError::UninitializedAccount),
         }
     }
 
-    fn initialize_server<'a>(
+    fn list_server<'a>(
         program_id: &Pubkey,
         dweller_owner: &AccountInfo<'a>,
         server: &AccountInfo<'a>,
-        dweller_server: &AccountInfo<'a>,
-        server_member: &AccountInfo<'a>,
-        input: &InitializeServerInput,
+        server_directory: &AccountInfo<'a>,
+        directory_entry: &AccountInfo<'a>,
+        input: &ListServerInput,
+        signers: &[AccountInfo<'a>],
     ) -> ProgramResult {
-        let mut dweller_data = dweller_owner.try_borrow_mut_data()?;
-        let mut dweller_state = Dweller::deserialize_const(&dweller_data)?;
-
-        let mut server_data = server.try_borrow_mut_data()?;
-        let mut server_state = Server::deserialize_const(&server_data)?;
+        if server.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
 
-        if server_state.version == StateVersion::Uninitialized {
-            server_state.name = input.name;
-            let server_member_key = create_index_with_seed(
-                program_id,
-                ServerMember::SEED,
-                server.key,
-                server_state.members,
-            )?;
+        let (mut server_data, server_state) = server.read_data_with_borsh_mut::<Server>()?;
+        require_owner(program_id, &server_state, dweller_owner, signers)?;
+        append_audit_log(
+            program_id,
+            server,
+            signers,
+            dweller_owner.key,
+            AuditAction::ListServer,
+            server.key,
+        )?;
+        if server_state.version == StateVersion::V1 {
+            return Err(Error::UnsupportedStateVersion.into());
+        }
 
-            let dweller_server_key = create_index_with_seed(
-                program_id,
-                DwellerServer::SEED,
-                dweller_owner.key,
-                dweller_state.servers,
-            )?;
+        let mut server_state = ServerV2::deserialize_const(&server_data)?;
+        if server_state.join_policy != JoinPolicy::Public {
+            return Err(Error::ServerNotPublic.into());
+        }
+        if server_state.listed {
+            return Err(Error::ServerAlreadyListed.into());
+        }
 
-            if *server_member.key == server_member_key && dweller_server_key == *dweller_server.key
-            {
-                let mut server_member_data = server_member.try_borrow_mut_data()?;
-                let mut server_member_state = ServerMember::deserialize_const(&server_member_data)?;
+        require_server_directory(program_id, server_directory)?;
+        let (mut directory_data, mut directory_state) =
+            server_directory.read_data_with_borsh_mut::<ServerDirectory>()?;
 
-                server_member_state.version = StateVersion::V1;
-                server_member_state.container = *server.key;
-                server_member_state.dweller = *dweller_owner.key;
-                server_member_state.index = server_state.members;
+        let directory_entry_key = create_index_with_seed(
+            program_id,
+            DirectoryEntry::SEED,
+            server_directory.key,
+            directory_state.entries,
+        )?;
 
-                server_member_state.serialize_const(&mut server_member_data)?;
+        if directory_entry_key != *directory_entry.key {
+            return Err(Error::InvalidDerivedDirectoryEntryAddress.into());
+        }
 
-                let mut dweller_server_data = dweller_server.try_borrow_mut_data()?;
-                let mut dweller_server_state =
-                    DwellerServer::deserialize_const(&dweller_server_data)?;
+        create_list_entry_if_missing::<DirectoryEntry>(
+            program_id,
+            DirectoryEntry::SEED,
+            server_directory,
+            directory_state.entries,
+            directory_entry,
+            signers,
+        )?;
+        let (mut entry_data, entry_state) =
+            directory_entry.read_data_with_borsh_mut::<DirectoryEntry>()?;
+        if entry_state.version != StateVersion::Uninitialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
 
-                dweller_server_state.version = StateVersion::V1;
-                dweller_server_state.server = *server.key;
-                dweller_server_state.container = *dweller_owner.key;
-                dweller_server_state.index = dweller_state.servers;
-                dweller_server_state.serialize_const(&mut dweller_server_data)?;
+        DirectoryEntry {
+            version: StateVersion::V1,
+            container: *server_directory.key,
+            index: directory_state.entries,
+            server: *server.key,
+            category: input.category,
+            language: input.language,
+            tags: input.tags,
+        }
+        .serialize_const(&mut entry_data)?;
 
-                dweller_state.servers = dweller_state.servers.error_increment()?;
-                dweller_state.serialize_const(&mut dweller_data)?;
+        directory_state.version = StateVersion::V1;
+        directory_state.entries = directory_state.entries.error_increment()?;
+        directory_state.serialize_const(&mut directory_data)?;
 
-                server_state.version = StateVersion::V1;
-                server_state.owner = *dweller_owner.key;
-                server_state.members = server_state.members.error_increment()?;
-                server_state.name = input.name;
-                server_state.serialize_const(&mut server_data)?;
+        server_state.listed = true;
+        server_state.serialize_const(&mut server_data)?;
 
-                Ok(())
-            } else {
-                Err(Error::InvalidDerivedAddress.into())
-            }
-        } else {
-            Err(ProgramError::AccountAlreadyInitialized)
-        }
+        Ok(())
     }
 
-    fn add_channel<'a>(
+    fn unlist_server<'a>(
         program_id: &Pubkey,
-        dweller_administrator: &AccountInfo<'a>,
-        server_administrator: &AccountInfo<'a>,
+        dweller_owner: &AccountInfo<'a>,
         server: &AccountInfo<'a>,
-        server_channel: &AccountInfo<'a>,
-        input: &AddChannelInput,
+        server_directory: &AccountInfo<'a>,
+        directory_entry: &AccountInfo<'a>,
+        directory_entry_last: &AccountInfo<'a>,
+        signers: &[AccountInfo<'a>],
     ) -> ProgramResult {
-        require_admin(
+        if server.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let (mut server_data, server_state) = server.read_data_with_borsh_mut::<Server>()?;
+        require_owner(program_id, &server_state, dweller_owner, signers)?;
+        append_audit_log(
             program_id,
-            dweller_administrator,
             server,
-            server_administrator,
+            signers,
+            dweller_owner.key,
+            AuditAction::UnlistServer,
+            server.key,
         )?;
+        if server_state.version == StateVersion::V1 {
+            return Err(Error::UnsupportedStateVersion.into());
+        }
+        let mut server_state = ServerV2::deserialize_const(&server_data)?;
 
-        let (mut server_data, mut server_state) = server.read_data_with_borsh_mut::<Server>()?;
+        require_server_directory(program_id, server_directory)?;
+        let (mut directory_data, mut directory_state) =
+            server_directory.read_data_with_borsh_mut::<ServerDirectory>()?;
 
-        let server_channel_key = create_index_with_seed(
+        let entry_state = directory_entry.read_data_with_borsh::<DirectoryEntry>()?;
+        let directory_entry_key = create_index_with_seed(
             program_id,
-            ServerChannel::SEED,
-            server.key,
-            server_state.channels,
+            DirectoryEntry::SEED,
+            server_directory.key,
+            entry_state.index,
         )?;
 
-        if server_channel_key == *server_channel.key {
-            let (mut channel_data, mut channel_state) =
-                server_channel.read_data_with_borsh_mut::<ServerChannel>()?;
+        let directory_entry_last_key = create_index_with_seed(
+            program_id,
+            DirectoryEntry::SEED,
+            server_directory.key,
+            directory_state.entries.error_decrement()?,
+        )?;
 
-            channel_state.version = StateVersion::V1;
-            channel_state.container = *server.key;
-            channel_state.type_id = input.type_id;
-            channel_state.name = input.name;
-            channel_state.index = server_state.channels;
+        if directory_entry_key == *directory_entry.key
+            && directory_entry_last_key == *directory_entry_last.key
+            && entry_state.server == *server.key
+        {
+            swap_accounts::<DirectoryEntry>(directory_entry, directory_entry_last, signers)?;
 
-            server_state.channels = server_state.channels.error_increment()?;
+            directory_state.entries = directory_state.entries.error_decrement()?;
+            directory_state.serialize_const(&mut directory_data)?;
 
-            channel_state.serialize_const(&mut channel_data)?;
+            server_state.listed = false;
             server_state.serialize_const(&mut server_data)?;
 
             Ok(())
         } else {
-            Err(Error::InvalidDerivedServerChannelAddress.into())
+            Err(Error::InvalidDerivedDirectoryEntryAddress.into())
         }
     }
 
-    fn create_group<'a>(
+    #[allow(clippy::too_many_arguments)]
+    fn set_channel_committer<'a>(
         program_id: &Pubkey,
         dweller_administrator: &AccountInfo<'a>,
         server_administrator: &AccountInfo<'a>,
         server: &AccountInfo<'a>,
-        server_group: &AccountInfo<'a>,
-        input: &CreateGroupInput,
+        server_channel: &AccountInfo<'a>,
+        channel_history: &AccountInfo<'a>,
+        input: &SetChannelCommitterInput,
+        signers: &[AccountInfo<'a>],
     ) -> ProgramResult {
         require_admin(
             program_id,
             dweller_administrator,
             server,
             server_administrator,
+            signers,
         )?;
-        let (mut server_data, mut server_state) = server.read_data_with_borsh_mut::<Server>()?;
-
-        let (mut group_data, mut group_state) =
-            server_group.read_data_with_borsh_mut::<ServerGroup>()?;
-
-        let server_group_key = create_index_with_seed(
+        append_audit_log(
             program_id,
-            ServerGroup::SEED,
-            server.key,
-            server_state.groups,
+            server,
+            signers,
+            dweller_administrator.key,
+            AuditAction::SetChannelCommitter,
+            server_channel.key,
         )?;
+        require_channel(program_id, server, server_channel)?;
 
-        if server_group_key == *server_group.key {
-            group_state.container = *server.key;
-            group_state.name = input.name;
-            group_state.version = StateVersion::V1;
-            group_state.index = server_state.groups;
-
-            server_state.groups = server_state.groups.error_increment()?;
-
-            group_state.serialize_const(&mut group_data)?;
-            server_state.serialize_const(&mut server_data)?;
-
-            Ok(())
-        } else {
-            Err(Error::InvalidDerivedServerGroupAddress.into())
-        }
+        let (mut history_data, mut history_state) =
+            read_channel_history(program_id, server_channel, channel_history)?;
+        history_state.committer = input.committer;
+        history_state.serialize_const(&mut history_data)?;
+        Ok(())
     }
 
-    fn add_admin<'a>(
+    #[allow(clippy::too_many_arguments)]
+    fn commit_channel_checkpoint<'a>(
         program_id: &Pubkey,
-        owner: &AccountInfo<'a>,
-        dweller: &AccountInfo<'a>,
-        server: &AccountInfo<'a>,
+        committer: &AccountInfo<'a>,
         server_administrator: &AccountInfo<'a>,
+        server: &AccountInfo<'a>,
+        server_channel: &AccountInfo<'a>,
+        channel_history: &AccountInfo<'a>,
+        channel_checkpoint: &AccountInfo<'a>,
+        input: &CommitChannelCheckpointInput,
+        signers: &[AccountInfo<'a>],
     ) -> ProgramResult {
-        if owner.is_signer {
-            let (mut server_data, mut server_state) =
-                server.read_data_with_borsh_mut::<Server>()?;
-            require_owner(&server_state, owner)?;
+        require_channel(program_id, server, server_channel)?;
+        let (mut history_data, mut history_state) =
+            read_channel_history(program_id, server_channel, channel_history)?;
+
+        let is_delegated = committer.is_signer
+            && history_state.committer != Pubkey::default()
+            && history_state.committer == *committer.key;
+        if !is_delegated {
+            require_admin(program_id, committer, server, server_administrator, signers)?;
+        }
+        append_audit_log(
+            program_id,
+            server,
+            signers,
+            committer.key,
+            AuditAction::CommitChannelCheckpoint,
+            channel_checkpoint.key,
+        )?;
 
-            let administrator_key = create_index_with_seed(
-                program_id,
-                ServerAdministrator::SEED,
-                server.key,
-                server_state.administrators,
-            )?;
+        if input.messages < history_state.messages {
+            return Err(Error::ChannelCheckpointOutdated.into());
+        }
 
-            if administrator_key == *server_administrator.key {
-                let (mut server_administrator_data, mut server_administrator_state) =
-                    server_administrator.read_data_with_borsh_mut::<ServerAdministrator>()?;
-                if server_administrator_state.version == StateVersion::Uninitialized {
-                    server_administrator_state.container = *server.key;
-                    server_administrator_state.dweller = *dweller.key;
-                    server_administrator_state.version = StateVersion::V1;
-                    server_administrator_state.index = server_state.administrators;
-                    server_administrator_state.serialize_const(&mut server_administrator_data)?;
+        let channel_checkpoint_key = create_index_with_seed(
+            program_id,
+            ChannelCheckpoint::SEED,
+            server_channel.key,
+            history_state.checkpoints,
+        )?;
+        if channel_checkpoint_key != *channel_checkpoint.key
+            || channel_checkpoint.owner != program_id
+        {
+            return Err(Error::InvalidDerivedChannelCheckpointAddress.into());
+        }
 
-                    server_state.administrators = server_state.administrators.error_increment()?;
-                    server_state.serialize_const(&mut server_data)?;
+        let (mut checkpoint_data, checkpoint_state) =
+            channel_checkpoint.read_data_with_borsh_mut::<ChannelCheckpoint>()?;
+        if checkpoint_state.version != StateVersion::Uninitialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
 
-                    Ok(())
-                } else {
-                    Err(ProgramError::AccountAlreadyInitialized)
-                }
-            } else {
-                Err(Error::InvalidDerivedServerAdministratorAddress.into())
-            }
-        } else {
-            Err(ProgramError::MissingRequiredSignature)
+        let clock = Clock::get()?;
+        ChannelCheckpoint {
+            version: StateVersion::V1,
+            container: *server_channel.key,
+            index: history_state.checkpoints,
+            root: input.root,
+            messages: input.messages,
+            slot: clock.slot,
+            unix_timestamp: clock.unix_timestamp,
         }
+        .serialize_const(&mut checkpoint_data)?;
+
+        history_state.checkpoints = history_state.checkpoints.error_increment()?;
+        history_state.messages = input.messages;
+        history_state.serialize_const(&mut history_data)?;
+        Ok(())
     }
 
-    fn remove_admin<'a>(
-        _program_id: &Pubkey,
-        owner: &AccountInfo<'a>,
+    fn set_join_requirement<'a>(
+        program_id: &Pubkey,
+        dweller_administrator: &AccountInfo<'a>,
+        server_administrator: &AccountInfo<'a>,
         server: &AccountInfo<'a>,
-        server_admin: &AccountInfo<'a>,
-        server_admin_last: &AccountInfo<'a>,
+        payer: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        input: &SetJoinRequirementInput,
+        signers: &[AccountInfo<'a>],
     ) -> ProgramResult {
-        let (mut server_data, mut server_state) = server.read_data_with_borsh_mut::<Server>()?;
-        if server_state.owner == *owner.key && owner.is_signer {
-            let server_admin_state = server_admin.read_data_with_borsh::<ServerAdministrator>()?;
-            let server_admin_key = crate::program::create_index_with_seed(
-                &crate::id(),
-                ServerAdministrator::SEED,
-                server.key,
-                server_admin_state.index,
-            )?;
+        require_admin(
+            program_id,
+            dweller_administrator,
+            server,
+            server_administrator,
+            signers,
+        )?;
+        append_audit_log(
+            program_id,
+            server,
+            signers,
+            dweller_administrator.key,
+            AuditAction::SetJoinRequirement,
+            server.key,
+        )?;
 
-            let server_admin_last_key = crate::program::create_index_with_seed(
-                &crate::id(),
-                ServerAdministrator::SEED,
-                server.key,
-                server_state.administrators.error_decrement()?,
-            )?;
+        let version = server.read_data_with_borsh::<Server>()?.version;
+        match version {
+            StateVersion::V4 => {
+                // requirement size may change, so whole layout with fee is rewritten
+                let (mut data, mut state) = server.read_data_with_borsh_mut::<ServerV4>()?;
+                state.join_requirement = input.join_requirement.clone();
+                state.serialize_const(&mut data)?;
+                return Ok(());
+            }
+            StateVersion::V3 => {}
+            StateVersion::V2 => resize_rent_exempt_account(
+                server,
+                payer,
+                system_program,
+                &Rent::get()?,
+                ServerV3::LEN as usize,
+            )?,
+            StateVersion::V1 => return Err(Error::UnsupportedStateVersion.into()),
+            StateVersion::Uninitialized => return Err(ProgramError::UninitializedAccount),
+        }
 
-            if server_admin_last_key == *server_admin_last.key
-                && server_admin_key == *server_admin.key
-            {
-                crate::program::swap_accounts::<ServerAdministrator>(
-                    server_admin,
-                    server_admin_last,
-                )?;
+        let (mut data, mut state) = server.read_data_with_borsh_mut::<ServerV3>()?;
+        state.version = StateVersion::V3;
+        state.join_requirement = input.join_requirement.clone();
+        state.serialize_const(&mut data)?;
+        Ok(())
+    }
 
-                server_state.administrators = server_state.administrators.error_decrement()?;
-                server_state.serialize_const(&mut server_data)?;
+    #[allow(clippy::too_many_arguments)]
+    fn recheck_member<'a>(
+        program_id: &Pubkey,
+        server: &AccountInfo<'a>,
+        server_member: &AccountInfo<'a>,
+        server_member_last: &AccountInfo<'a>,
+        dweller: &AccountInfo<'a>,
+        dweller_server: &AccountInfo<'a>,
+        dweller_server_last: &AccountInfo<'a>,
+        token: &AccountInfo<'a>,
+        member_marker: &AccountInfo<'a>,
+        accounts: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        let member_state = server_member.read_data_with_borsh::<ServerMember>()?;
+        let dweller_server_state = dweller_server.read_data_with_borsh::<DwellerServer>()?;
+        if member_state.container != *server.key
+            || member_state.dweller != *dweller.key
+            || dweller_server_state.server != *server.key
+        {
+            return Err(Error::InvalidDerivedServerMemberAddress.into());
+        }
 
-                Ok(())
-            } else {
-                Err(Error::InvalidDerivedServerAdministratorAddress.into())
+        // owner is exempt from requirement and fee
+        if server.read_data_with_borsh::<Server>()?.owner == *dweller.key {
+            return Err(Error::MemberMeetsJoinRequirement.into());
+        }
+
+        let meets_requirement = match join_requirement(server)? {
+            JoinRequirement::None => true,
+            requirement => {
+                let member_token = match member_state.version {
+                    StateVersion::V3 | StateVersion::V4 => {
+                        server_member
+                            .read_data_with_borsh::<ServerMemberV3>()?
+                            .token
+                    }
+                    _ => Pubkey::default(),
+                };
+                if member_token != Pubkey::default() && member_token != *token.key {
+                    return Err(Error::InvalidJoinRequirementToken.into());
+                }
+                // members without recorded token are checked against passed one
+                let owner = dweller_authority(program_id, dweller)?;
+                meets_join_requirement(&requirement, &owner, token)
             }
-        } else {
-            Err(ProgramError::MissingRequiredSignature)
+        };
+
+        let paid = match join_fee(server)? {
+            Some(join_fee) if join_fee.period != 0 => {
+                member_paid_until(server_member)? >= Clock::get()?.unix_timestamp
+            }
+            _ => true,
+        };
+
+        if meets_requirement && paid {
+            return Err(Error::MemberMeetsJoinRequirement.into());
         }
+
+        close_dweller_marker(
+            program_id,
+            ServerMember::SEED,
+            server.key,
+            dweller.key,
+            member_marker,
+            accounts,
+        )?;
+        remove_dweller_server(
+            program_id,
+            dweller,
+            dweller_server,
+            dweller_server_last,
+            accounts,
+        )?;
+        remove_server_member(
+            program_id,
+            server,
+            server_member,
+            server_member_last,
+            accounts,
+        )
     }
 
-    fn revoke_invite_server<'a>(
+    #[allow(clippy::too_many_arguments)]
+    fn set_join_fee<'a>(
+        program_id: &Pubkey,
+        dweller_owner: &AccountInfo<'a>,
+        server: &AccountInfo<'a>,
+        payer: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        treasury: &AccountInfo<'a>,
+        mint: &AccountInfo<'a>,
+        token_program: &AccountInfo<'a>,
+        input: &SetJoinFeeInput,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        let server_state = server.read_data_with_borsh::<Server>()?;
+        require_owner(program_id, &server_state, dweller_owner, signers)?;
+        append_audit_log(
+            program_id,
+            server,
+            signers,
+            dweller_owner.key,
+            AuditAction::SetJoinFee,
+            server.key,
+        )?;
+
+        match server_state.version {
+            StateVersion::V4 => {}
+            StateVersion::V2 | StateVersion::V3 => resize_rent_exempt_account(
+                server,
+                payer,
+                system_program,
+                &Rent::get()?,
+                ServerV4::LEN as usize,
+            )?,
+            StateVersion::V1 => return Err(Error::UnsupportedStateVersion.into()),
+            StateVersion::Uninitialized => return Err(ProgramError::UninitializedAccount),
+        }
+
+        if let Some(join_fee) = &input.join_fee {
+            if join_fee.mint != *mint.key || join_fee.period < 0 {
+                return Err(ProgramError::InvalidArgument);
+            }
+            let (treasury_key, bump) =
+                JoinFee::find_treasury_address(program_id, server.key, mint.key);
+            if treasury_key != *treasury.key || *token_program.key != spl_token::id() {
+                return Err(Error::InvalidTreasuryAddress.into());
+            }
+
+            if *treasury.owner == system_program::id() {
+                create_program_derived_account(
+                    payer,
+                    treasury,
+                    system_program,
+                    &Rent::get()?,
+                    spl_token::state::Account::LEN,
+                    &spl_token::id(),
+                    &[
+                        &server.key.to_bytes(),
+                        JoinFee::TREASURY_SEED,
+                        &mint.key.to_bytes(),
+                        &[bump],
+                    ],
+                )?;
+                invoke(
+                    &spl_token::instruction::initialize_account3(
+                        &spl_token::id(),
+                        treasury.key,
+                        mint.key,
+                        treasury.key,
+                    )?,
+                    &[treasury.clone(), mint.clone(), token_program.clone()],
+                )?;
+            }
+        }
+
+        let (mut data, mut state) = server.read_data_with_borsh_mut::<ServerV4>()?;
+        state.version = StateVersion::V4;
+        state.join_fee = input.join_fee.clone();
+        state.serialize_const(&mut data)?;
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn withdraw_treasury<'a>(
+        program_id: &Pubkey,
+        dweller_owner: &AccountInfo<'a>,
+        server: &AccountInfo<'a>,
+        treasury: &AccountInfo<'a>,
+        destination: &AccountInfo<'a>,
+        token_program: &AccountInfo<'a>,
+        input: &WithdrawTreasuryInput,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        let server_state = server.read_data_with_borsh::<Server>()?;
+        require_owner(program_id, &server_state, dweller_owner, signers)?;
+        append_audit_log(
+            program_id,
+            server,
+            signers,
+            dweller_owner.key,
+            AuditAction::WithdrawTreasury,
+            destination.key,
+        )?;
+
+        let (treasury_key, bump) =
+            JoinFee::find_treasury_address(program_id, server.key, &input.mint);
+        if treasury_key != *treasury.key || *token_program.key != spl_token::id() {
+            return Err(Error::InvalidTreasuryAddress.into());
+        }
+
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                &spl_token::id(),
+                treasury.key,
+                destination.key,
+                treasury.key,
+                &[],
+                input.amount,
+            )?,
+            &[treasury.clone(), destination.clone(), token_program.clone()],
+            &[&[
+                &server.key.to_bytes(),
+                JoinFee::TREASURY_SEED,
+                &input.mint.to_bytes(),
+                &[bump],
+            ]],
+        )
+    }
+
+    fn renew_membership<'a>(
         program_id: &Pubkey,
         server: &AccountInfo<'a>,
+        server_member: &AccountInfo<'a>,
+        authority: &AccountInfo<'a>,
+        accounts: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        let member_state = server_member.read_data_with_borsh::<ServerMember>()?;
+        let server_member_key = create_index_with_seed(
+            program_id,
+            ServerMember::SEED,
+            server.key,
+            member_state.index,
+        )?;
+        if server_member_key != *server_member.key
+            || member_state.container != *server.key
+            || server_member.owner != program_id
+        {
+            return Err(Error::InvalidDerivedServerMemberAddress.into());
+        }
+
+        let join_fee = match join_fee(server)? {
+            Some(join_fee) if join_fee.period != 0 => join_fee,
+            _ => return Err(Error::ServerHasNoSubscription.into()),
+        };
+        pay_join_fee(program_id, server, authority, &join_fee, accounts)?;
+        extend_membership(server_member, &join_fee)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn create_server_event<'a>(
+        program_id: &Pubkey,
         dweller_administrator: &AccountInfo<'a>,
         server_administrator: &AccountInfo<'a>,
-        member_status: &AccountInfo<'a>,
-        member_status_last: &AccountInfo<'a>,
+        server: &AccountInfo<'a>,
+        server_events: &AccountInfo<'a>,
+        server_event: &AccountInfo<'a>,
+        server_channel: &AccountInfo<'a>,
+        input: &CreateServerEventInput,
+        signers: &[AccountInfo<'a>],
     ) -> ProgramResult {
         require_admin(
             program_id,
             dweller_administrator,
             server,
             server_administrator,
+            signers,
         )?;
-
-        let (mut server_data, mut server_state) = server.read_data_with_borsh_mut::<Server>()?;
-        let member_status_state = member_status.read_data_with_borsh::<ServerMemberStatus>()?;
-
-        let member_status_last_key = create_index_with_seed(
+        append_audit_log(
             program_id,
-            ServerMemberStatus::SEED,
-            server.key,
-            server_state.member_statuses.error_decrement()?,
+            server,
+            signers,
+            dweller_administrator.key,
+            AuditAction::CreateServerEvent,
+            server_event.key,
         )?;
 
-        let member_status_key = create_index_with_seed(
+        if input.end < input.start {
+            return Err(ProgramError::InvalidArgument);
+        }
+        if input.channel != Pubkey::default() {
+            if input.channel != *server_channel.key {
+                return Err(Error::InvalidDerivedServerChannelAddress.into());
+            }
+            require_channel(program_id, server, server_channel)?;
+        }
+
+        let (mut events_data, mut events_state) =
+            read_server_events(program_id, server, server_events)?;
+
+        let server_event_key = create_index_with_seed(
             program_id,
-            ServerMemberStatus::SEED,
+            ServerEvent::SEED,
             server.key,
-            member_status_state.index,
+            events_state.events,
         )?;
+        if server_event_key != *server_event.key || server_event.owner != program_id {
+            return Err(Error::InvalidDerivedServerEventAddress.into());
+        }
 
-        if *member_status.key == member_status_key
-            && *member_status_last.key == member_status_last_key
-        {
-            crate::program::swap_accounts::<ServerMemberStatus>(member_status, member_status_last)?;
+        let (mut event_data, event_state) =
+            server_event.read_data_with_borsh_mut::<ServerEvent>()?;
+        if event_state.version != StateVersion::Uninitialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
 
-            server_state.member_statuses = server_state.member_statuses.error_decrement()?;
-            server_state.serialize_const(&mut server_data)?;
-            Ok(())
-        } else {
-            Err(Error::InvalidDerivedServerMemberStatusAddress.into())
+        ServerEvent {
+            version: StateVersion::V1,
+            container: *server.key,
+            index: events_state.events,
+            title: input.title,
+            description_hash: input.description_hash,
+            start: input.start,
+            end: input.end,
+            channel: input.channel,
+            attendees: 0,
+            cancelled: false,
         }
+        .serialize_const(&mut event_data)?;
+
+        events_state.events = events_state.events.error_increment()?;
+        events_state.serialize_const(&mut events_data)?;
+        Ok(())
     }
 
-    fn invite_to_server<'a>(
+    fn cancel_server_event<'a>(
         program_id: &Pubkey,
-        server: &AccountInfo<'a>,
         dweller_administrator: &AccountInfo<'a>,
         server_administrator: &AccountInfo<'a>,
-        dweller: &AccountInfo<'a>,
-        member_status: &AccountInfo<'a>,
+        server: &AccountInfo<'a>,
+        server_event: &AccountInfo<'a>,
+        signers: &[AccountInfo<'a>],
     ) -> ProgramResult {
         require_admin(
             program_id,
             dweller_administrator,
             server,
             server_administrator,
+            signers,
+        )?;
+        append_audit_log(
+            program_id,
+            server,
+            signers,
+            dweller_administrator.key,
+            AuditAction::CancelServerEvent,
+            server_event.key,
         )?;
 
-        let (mut server_data, mut server_state) = server.read_data_with_borsh_mut::<Server>()?;
+        let (mut event_data, mut event_state) =
+            read_server_event(program_id, server, server_event)?;
+        if event_state.cancelled {
+            return Err(Error::ServerEventCancelled.into());
+        }
+        event_state.cancelled = true;
+        event_state.serialize_const(&mut event_data)?;
+        Ok(())
+    }
 
-        let member_status_key = create_index_with_seed(
+    #[allow(clippy::too_many_arguments)]
+    fn rsvp<'a>(
+        program_id: &Pubkey,
+        dweller: &AccountInfo<'a>,
+        server: &AccountInfo<'a>,
+        server_member: &AccountInfo<'a>,
+        server_event: &AccountInfo<'a>,
+        event_rsvp: &AccountInfo<'a>,
+        event_rsvp_marker: &AccountInfo<'a>,
+        payer: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        if !is_dweller_authority(program_id, dweller, signers)? {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let member_state = server_member.read_data_with_borsh::<ServerMember>()?;
+        let server_member_key = create_index_with_seed(
             program_id,
-            ServerMemberStatus::SEED,
+            ServerMember::SEED,
             server.key,
-            server_state.member_statuses,
+            member_state.index,
         )?;
+        if server_member_key != *server_member.key
+            || member_state.container != *server.key
+            || member_state.dweller != *dweller.key
+            || server_member.owner != program_id
+        {
+            return Err(Error::InvalidDerivedServerMemberAddress.into());
+        }
 
-        if member_status_key == *member_status.key {
-            let (mut member_status_data, mut member_status_state) =
-                member_status.read_data_with_borsh_mut::<ServerMemberStatus>()?;
+        let (mut event_data, mut event_state) =
+            read_server_event(program_id, server, server_event)?;
+        if event_state.cancelled {
+            return Err(Error::ServerEventCancelled.into());
+        }
+        if Clock::get()?.unix_timestamp > event_state.end {
+            return Err(Error::ServerEventEnded.into());
+        }
+        create_dweller_marker(
+            program_id,
+            EventRsvp::SEED,
+            server_event,
+            dweller,
+            event_rsvp_marker,
+            payer,
+            system_program,
+            Error::AlreadyRsvped,
+        )?;
 
-            member_status_state.container = *server.key;
-            member_status_state.version = StateVersion::V1;
-            member_status_state.dweller = *dweller.key;
-            member_status_state.index = server_state.member_statuses;
+        let event_rsvp_key = create_index_with_seed(
+            program_id,
+            EventRsvp::SEED,
+            server_event.key,
+            event_state.attendees,
+        )?;
+        if event_rsvp_key != *event_rsvp.key {
+            return Err(Error::InvalidDerivedEventRsvpAddress.into());
+        }
+        create_list_entry_if_missing::<EventRsvp>(
+            program_id,
+            EventRsvp::SEED,
+            server_event,
+            event_state.attendees,
+            event_rsvp,
+            signers,
+        )?;
+        if event_rsvp.owner != program_id {
+            return Err(Error::InvalidDerivedEventRsvpAddress.into());
+        }
 
-            server_state.member_statuses = server_state.member_statuses.error_increment()?;
+        let (mut rsvp_data, rsvp_state) = event_rsvp.read_data_with_borsh_mut::<EventRsvp>()?;
+        if rsvp_state.version != StateVersion::Uninitialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
 
-            server_state.serialize_const(&mut server_data)?;
-            member_status_state.serialize_const(&mut member_status_data)?;
+        EventRsvp {
+            version: StateVersion::V1,
+            container: *server_event.key,
+            index: event_state.attendees,
+            dweller: *dweller.key,
+        }
+        .serialize_const(&mut rsvp_data)?;
+
+        event_state.attendees = event_state.attendees.error_increment()?;
+        event_state.serialize_const(&mut event_data)?;
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn cancel_rsvp<'a>(
+        program_id: &Pubkey,
+        dweller: &AccountInfo<'a>,
+        server: &AccountInfo<'a>,
+        server_event: &AccountInfo<'a>,
+        event_rsvp: &AccountInfo<'a>,
+        event_rsvp_last: &AccountInfo<'a>,
+        event_rsvp_marker: &AccountInfo<'a>,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        if !is_dweller_authority(program_id, dweller, signers)? {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let (mut event_data, mut event_state) =
+            read_server_event(program_id, server, server_event)?;
+        let rsvp_state = event_rsvp.read_data_with_borsh::<EventRsvp>()?;
+
+        let event_rsvp_key = create_index_with_seed(
+            program_id,
+            EventRsvp::SEED,
+            server_event.key,
+            rsvp_state.index,
+        )?;
+        let event_rsvp_last_key = create_index_with_seed(
+            program_id,
+            EventRsvp::SEED,
+            server_event.key,
+            event_state.attendees.error_decrement()?,
+        )?;
+
+        if event_rsvp_key == *event_rsvp.key
+            && event_rsvp_last_key == *event_rsvp_last.key
+            && rsvp_state.container == *server_event.key
+            && rsvp_state.dweller == *dweller.key
+        {
+            close_dweller_marker(
+                program_id,
+                EventRsvp::SEED,
+                server_event.key,
+                dweller.key,
+                event_rsvp_marker,
+                signers,
+            )?;
+            swap_accounts::<EventRsvp>(event_rsvp, event_rsvp_last, signers)?;
 
+            event_state.attendees = event_state.attendees.error_decrement()?;
+            event_state.serialize_const(&mut event_data)?;
             Ok(())
         } else {
-            Err(Error::InvalidDerivedServerMemberStatusAddress.into())
+            Err(Error::InvalidDerivedEventRsvpAddress.into())
         }
     }
 
-    /// Create derived
+    fn claim_handle<'a>(
+        program_id: &Pubkey,
+        dweller: &AccountInfo<'a>,
+        dweller_handle_account: &AccountInfo<'a>,
+        payer: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        input: &ClaimHandleInput,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        if !is_dweller_authority(program_id, dweller, signers)? {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if dweller.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        if dweller_handle(dweller)? != Pubkey::default() {
+            return Err(Error::DwellerHasHandle.into());
+        }
+
+        let handle = DwellerHandle::normalize(&input.handle)?;
+        let (handle_key, bump) = DwellerHandle::find_normalized_address(program_id, &handle);
+        if handle_key != *dweller_handle_account.key {
+            return Err(Error::InvalidHandleAddress.into());
+        }
+
+        if dweller_handle_account.owner == program_id {
+            return Err(Error::HandleTaken.into());
+        }
+        let len = handle.iter().position(|x| *x == 0).unwrap_or(32);
+        create_program_derived_account(
+            payer,
+            dweller_handle_account,
+            system_program,
+            &Rent::get()?,
+            DwellerHandle::LEN as usize,
+            program_id,
+            &[DwellerHandle::SEED, &handle[..len], &[bump]],
+        )?;
+
+        DwellerHandle {
+            version: StateVersion::V1,
+            dweller: *dweller.key,
+            handle,
+        }
+        .serialize_const(&mut dweller_handle_account.try_borrow_mut_data()?)?;
+
+        set_dweller_handle(dweller, payer, system_program, dweller_handle_account.key)
+    }
+
+    fn release_handle<'a>(
+        program_id: &Pubkey,
+        dweller: &AccountInfo<'a>,
+        dweller_handle_account: &AccountInfo<'a>,
+        destination: &AccountInfo<'a>,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        if !is_dweller_authority(program_id, dweller, signers)? {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        require_dweller_handle(program_id, dweller, dweller_handle_account)?;
+
+        let (mut data, mut state) = dweller.read_data_with_borsh_mut::<DwellerV3>()?;
+        state.handle = Pubkey::default();
+        state.serialize_const(&mut data)?;
+
+        close_account(dweller_handle_account, destination.key, signers)
+    }
+
+    fn transfer_handle<'a>(
+        program_id: &Pubkey,
+        dweller: &AccountInfo<'a>,
+        dweller_handle_account: &AccountInfo<'a>,
+        dweller_to: &AccountInfo<'a>,
+        payer: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        if !is_dweller_authority(program_id, dweller, signers)?
+            || !is_dweller_authority(program_id, dweller_to, signers)?
+        {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        require_dweller_handle(program_id, dweller, dweller_handle_account)?;
+        if dweller_to.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        if dweller_handle(dweller_to)? != Pubkey::default() {
+            return Err(Error::DwellerHasHandle.into());
+        }
+
+        let (mut data, mut state) = dweller.read_data_with_borsh_mut::<DwellerV3>()?;
+        state.handle = Pubkey::default();
+        state.serialize_const(&mut data)?;
+        drop(data);
+
+        let (mut handle_data, mut handle_state) =
+            dweller_handle_account.read_data_with_borsh_mut::<DwellerHandle>()?;
+        handle_state.dweller = *dweller_to.key;
+        handle_state.serialize_const(&mut handle_data)?;
+        drop(handle_data);
+
+        set_dweller_handle(
+            dweller_to,
+            payer,
+            system_program,
+            dweller_handle_account.key,
+        )
+    }
+
     #[allow(clippy::too_many_arguments)]
-    pub fn create_derived_address<'a>(
+    fn set_dweller_nft_avatar<'a>(
         program_id: &Pubkey,
-        payer_account_info: &AccountInfo<'a>,
-        owner_account_info: &AccountInfo<'a>,
-        base_account_info: &AccountInfo<'a>,
-        account_to_create_info: &AccountInfo<'a>,
-        rent_account_info: &AccountInfo<'a>,
-        _system_program: &AccountInfo<'a>,
-        input: &AddressTypeInput,
+        dweller: &AccountInfo<'a>,
+        token: &AccountInfo<'a>,
+        token_data: &AccountInfo<'a>,
+        payer: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        signers: &[AccountInfo<'a>],
     ) -> ProgramResult {
-        let rent = &Rent::from_account_info(rent_account_info)?;
-        if system_program::id() != *_system_program.key {
-            return Err(ProgramError::InvalidSeeds);
+        if !is_dweller_authority(program_id, dweller, signers)? {
+            return Err(ProgramError::MissingRequiredSignature);
         }
-        match input {
-            AddressTypeInput::DwellerServer(index) => create_seeded_rent_except_account(
-                DwellerServer::SEED,
-                owner_account_info,
-                index,
-                base_account_info,
-                account_to_create_info,
-                payer_account_info,
-                rent,
-                DwellerServer::LEN,
-                program_id,
-            ),
-            AddressTypeInput::ServerMemberStatus(index) => create_seeded_rent_except_account(
-                ServerMemberStatus::SEED,
-                owner_account_info,
-                index,
-                base_account_info,
-                account_to_create_info,
-                payer_account_info,
-                rent,
-                ServerMemberStatus::LEN,
+        if dweller.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let authority = dweller_authority(program_id, dweller)?;
+        if !is_nft_owner(token, &authority) {
+            return Err(Error::InvalidNftAvatar.into());
+        }
+
+        let token_data_key = Pubkey::create_with_seed(
+            token.key,
+            spl_nft_erc_721::processor::Processor::TOKEN_DATA_SEED,
+            &spl_nft_erc_721::id(),
+        )?;
+        let token_data_state =
+            token_data.read_data_with_borsh::<spl_nft_erc_721::state::TokenData>()?;
+        if token_data_key != *token_data.key
+            || *token_data.owner != spl_nft_erc_721::id()
+            || token_data_state.version != spl_nft_erc_721::state::TokenDataStatus::Initialized
+            || token_data_state.token != *token.key
+        {
+            return Err(Error::InvalidNftAvatar.into());
+        }
+
+        set_dweller_nft_avatar(dweller, payer, system_program, token.key)
+    }
+
+    fn clear_stale_avatar<'a>(
+        program_id: &Pubkey,
+        dweller: &AccountInfo<'a>,
+        token: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        if dweller.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let nft_avatar = dweller_nft_avatar(dweller)?;
+        if nft_avatar == Pubkey::default() || nft_avatar != *token.key {
+            return Err(Error::InvalidNftAvatar.into());
+        }
+        if is_nft_owner(token, &dweller_authority(program_id, dweller)?) {
+            return Err(Error::NftAvatarStillOwned.into());
+        }
+
+        let (mut data, mut state) = dweller.read_data_with_borsh_mut::<DwellerV4>()?;
+        state.nft_avatar = Pubkey::default();
+        state.serialize_const(&mut data)?;
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn reindex<'a>(
+        program_id: &Pubkey,
+        dweller_administrator: &AccountInfo<'a>,
+        server_administrator: &AccountInfo<'a>,
+        server: &AccountInfo<'a>,
+        container: &AccountInfo<'a>,
+        entries: &[AccountInfo<'a>],
+        input: &ReindexInput,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        require_admin(
+            program_id,
+            dweller_administrator,
+            server,
+            server_administrator,
+            signers,
+        )?;
+
+        let (seed, first) = match input.first {
+            AddressTypeInput::GroupChannel(index) => {
+                require_list_entry(program_id, ServerGroup::SEED, server, container)
+                    .map_err(|_| Error::InvalidDerivedServerGroupAddress)?;
+                (GroupChannel::SEED, index)
+            }
+            AddressTypeInput::ChannelOverwrite(index) => {
+                require_list_entry(program_id, ServerChannel::SEED, server, container)
+                    .map_err(|_| Error::InvalidDerivedServerChannelAddress)?;
+                (ChannelOverwrite::SEED, index)
+            }
+            _ if container.key != server.key => {
+                return Err(Error::InvalidDerivedAddressWrongServer.into())
+            }
+            AddressTypeInput::ServerMember(index) => (ServerMember::SEED, index),
+            AddressTypeInput::ServerMemberStatus(index) => (ServerMemberStatus::SEED, index),
+            AddressTypeInput::ServerAdministrator(index) => (ServerAdministrator::SEED, index),
+            AddressTypeInput::ServerChannel(index) => (ServerChannel::SEED, index),
+            AddressTypeInput::ServerGroup(index) => (ServerGroup::SEED, index),
+            AddressTypeInput::JoinRequest(index) => (JoinRequest::SEED, index),
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+
+        append_audit_log(
+            program_id,
+            server,
+            signers,
+            dweller_administrator.key,
+            AuditAction::Reindex,
+            container.key,
+        )?;
+
+        reindex_entries(program_id, seed, container, entries, first)
+    }
+
+    fn reindex_dweller_servers<'a>(
+        program_id: &Pubkey,
+        dweller: &AccountInfo<'a>,
+        entries: &[AccountInfo<'a>],
+        input: &ReindexInput,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        if !is_dweller_authority(program_id, dweller, signers)? {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let first = match input.first {
+            AddressTypeInput::DwellerServer(index) => index,
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+
+        reindex_entries(program_id, DwellerServer::SEED, dweller, entries, first)
+    }
+
+    fn set_member_nickname<'a>(
+        program_id: &Pubkey,
+        dweller: &AccountInfo<'a>,
+        server: &AccountInfo<'a>,
+        server_member: &AccountInfo<'a>,
+        server_administrator: &AccountInfo<'a>,
+        input: &SetNameInput,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        let member_state = server_member.read_data_with_borsh::<ServerMember>()?;
+        let server_member_key = create_index_with_seed(
+            program_id,
+            ServerMember::SEED,
+            server.key,
+            member_state.index,
+        )?;
+
+        if server_member_key != *server_member.key
+            || member_state.container != *server.key
+            || server_member.owner != program_id
+        {
+            return Err(Error::InvalidDerivedServerMemberAddress.into());
+        }
+
+        if member_state.dweller == *dweller.key {
+            if !is_dweller_authority(program_id, dweller, signers)? {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+        } else {
+            require_admin(program_id, dweller, server, server_administrator, signers)?;
+            append_audit_log(
                 program_id,
-            ),
-            AddressTypeInput::ServerAdministrator(index) => create_seeded_rent_except_account(
-                ServerAdministrator::SEED,
-                owner_account_info,
-                index,
-                base_account_info,
-                account_to_create_info,
-                payer_account_info,
-                rent,
-                ServerAdministrator::LEN,
+                server,
+                signers,
+                dweller.key,
+                AuditAction::SetMemberNickname,
+                &member_state.dweller,
+            )?;
+        }
+
+        match member_state.version {
+            StateVersion::V2 | StateVersion::V3 | StateVersion::V4 => {
+                let (mut member_data, mut member_state) =
+                    server_member.read_data_with_borsh_mut::<ServerMemberV2>()?;
+                member_state.nickname = input.name;
+                member_state.serialize_const(&mut member_data)?;
+                Ok(())
+            }
+            StateVersion::V1 => {
+                // entries allocated for later versions already fit
+                if server_member.data_len() < ServerMemberV2::LEN as usize {
+                    let (payer, system_program) = find_payer(signers)?;
+                    resize_rent_exempt_account(
+                        server_member,
+                        payer,
+                        system_program,
+                        &Rent::get()?,
+                        ServerMemberV2::LEN as usize,
+                    )?;
+                }
+                ServerMemberV2 {
+                    base: ServerMember {
+                        version: StateVersion::V2,
+                        ..member_state
+                    },
+                    nickname: input.name,
+                }
+                .serialize_const(&mut server_member.try_borrow_mut_data()?)?;
+                Ok(())
+            }
+            StateVersion::Uninitialized => Err(ProgramError::UninitializedAccount),
+        }
+    }
+
+    fn set_dweller_status<'a>(
+        program_id: &Pubkey,
+        dweller: &AccountInfo<'a>,
+        input: &SetDwellerStatusInput,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        if is_dweller_authority(program_id, dweller, signers)? {
+            let mut data = dweller.try_borrow_mut_data()?;
+            let mut state = Dweller::deserialize_const(&data)?;
+            if state.version != StateVersion::Uninitialized {
+                state.status = input.status;
+                state.serialize_const(&mut data)?;
+                Ok(())
+            } else {
+                Err(ProgramError::UninitializedAccount)
+            }
+        } else {
+            Err(ProgramError::MissingRequiredSignature)
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn initialize_server<'a>(
+        program_id: &Pubkey,
+        dweller_owner: &AccountInfo<'a>,
+        server: &AccountInfo<'a>,
+        dweller_server: &AccountInfo<'a>,
+        server_member: &AccountInfo<'a>,
+        member_marker: &AccountInfo<'a>,
+        payer: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        input: &InitializeServerInput,
+        accounts: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        create_dweller_marker(
+            program_id,
+            ServerMember::SEED,
+            server,
+            dweller_owner,
+            member_marker,
+            payer,
+            system_program,
+            Error::AlreadyMember,
+        )?;
+        let mut dweller_data = dweller_owner.try_borrow_mut_data()?;
+        let mut dweller_state = Dweller::deserialize_const(&dweller_data)?;
+
+        let mut server_data = server.try_borrow_mut_data()?;
+        let mut server_state = Server::deserialize_const(&server_data)?;
+
+        if server_state.version == StateVersion::Uninitialized {
+            server_state.name = input.name;
+            let server_member_key = create_index_with_seed(
                 program_id,
-            ),
-            AddressTypeInput::ServerMember(index) => create_seeded_rent_except_account(
                 ServerMember::SEED,
-                owner_account_info,
-                index,
-                base_account_info,
-                account_to_create_info,
-                payer_account_info,
-                rent,
-                ServerMember::LEN,
-                program_id,
-            ),
-            AddressTypeInput::ServerChannel(index) => create_seeded_rent_except_account(
-                ServerChannel::SEED,
-                owner_account_info,
-                index,
-                base_account_info,
-                account_to_create_info,
-                payer_account_info,
-                rent,
-                ServerChannel::LEN,
-                program_id,
-            ),
-            AddressTypeInput::ServerGroup(index) => create_seeded_rent_except_account(
-                ServerGroup::SEED,
-                owner_account_info,
-                index,
-                base_account_info,
-                account_to_create_info,
-                payer_account_info,
-                rent,
-                ServerGroup::LEN,
+                server.key,
+                server_state.members,
+            )?;
+
+            let dweller_server_key = create_index_with_seed(
                 program_id,
-            ),
-            AddressTypeInput::GroupChannel(index) => create_seeded_rent_except_account(
-                GroupChannel::SEED,
-                owner_account_info,
-                index,
-                base_account_info,
-                account_to_create_info,
-                payer_account_info,
-                rent,
-                GroupChannel::LEN,
+                DwellerServer::SEED,
+                dweller_owner.key,
+                dweller_state.servers,
+            )?;
+
+            if *server_member.key == server_member_key && dweller_server_key == *dweller_server.key
+            {
+                create_list_entry_if_missing::<ServerMember>(
+                    program_id,
+                    ServerMember::SEED,
+                    server,
+                    server_state.members,
+                    server_member,
+                    accounts,
+                )?;
+                create_list_entry_if_missing::<DwellerServer>(
+                    program_id,
+                    DwellerServer::SEED,
+                    dweller_owner,
+                    dweller_state.servers,
+                    dweller_server,
+                    accounts,
+                )?;
+                let mut server_member_data = server_member.try_borrow_mut_data()?;
+                write_new_server_member(
+                    &mut server_member_data,
+                    server.key,
+                    server_state.members,
+                    dweller_owner.key,
+                    &Pubkey::default(),
+                )?;
+
+                let mut dweller_server_data = dweller_server.try_borrow_mut_data()?;
+                let mut dweller_server_state =
+                    DwellerServer::deserialize_const(&dweller_server_data)?;
+
+                dweller_server_state.version = StateVersion::V1;
+                dweller_server_state.server = *server.key;
+                dweller_server_state.container = *dweller_owner.key;
+                dweller_server_state.index = dweller_state.servers;
+                dweller_server_state.serialize_const(&mut dweller_server_data)?;
+
+                dweller_state.servers = dweller_state.servers.error_increment()?;
+                dweller_state.serialize_const(&mut dweller_data)?;
+
+                server_state.version = if server_data.len() >= ServerV4::LEN as usize {
+                    StateVersion::V4
+                } else if server_data.len() >= ServerV3::LEN as usize {
+                    StateVersion::V3
+                } else if server_data.len() >= ServerV2::LEN as usize {
+                    StateVersion::V2
+                } else {
+                    StateVersion::V1
+                };
+                server_state.owner = *dweller_owner.key;
+                server_state.members = server_state.members.error_increment()?;
+                server_state.name = input.name;
+                server_state.serialize_const(&mut server_data)?;
+
+                Ok(())
+            } else {
+                Err(Error::InvalidDerivedAddress.into())
+            }
+        } else {
+            Err(ProgramError::AccountAlreadyInitialized)
+        }
+    }
+
+    fn add_channel<'a>(
+        program_id: &Pubkey,
+        dweller_administrator: &AccountInfo<'a>,
+        server_administrator: &AccountInfo<'a>,
+        server: &AccountInfo<'a>,
+        server_channel: &AccountInfo<'a>,
+        input: &AddChannelInput,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        require_admin(
+            program_id,
+            dweller_administrator,
+            server,
+            server_administrator,
+            signers,
+        )?;
+        append_audit_log(
+            program_id,
+            server,
+            signers,
+            dweller_administrator.key,
+            AuditAction::AddChannel,
+            server_channel.key,
+        )?;
+
+        let mut server_state = server.read_data_with_zero_copy_mut::<ServerPod>()?;
+        let channels = server_state.channels.into();
+
+        let server_channel_key =
+            create_index_with_seed(program_id, ServerChannel::SEED, server.key, channels)?;
+
+        if server_channel_key == *server_channel.key {
+            let kind = validate_channel(input.type_id, &VoiceChannelConfig::default())?;
+            create_list_entry_if_missing::<ServerChannel>(
                 program_id,
-            ),
+                ServerChannel::SEED,
+                server,
+                channels,
+                server_channel,
+                signers,
+            )?;
+            let (mut channel_data, mut channel_state) =
+                server_channel.read_data_with_borsh_mut::<ServerChannelV2>()?;
+
+            channel_state.version = StateVersion::V2;
+            channel_state.container = *server.key;
+            channel_state.kind = kind;
+            channel_state.name = input.name;
+            channel_state.index = channels;
+
+            server_state.channels = channels.error_increment()?.into();
+
+            channel_state.serialize_const(&mut channel_data)?;
+
+            Ok(())
+        } else {
+            Err(Error::InvalidDerivedServerChannelAddress.into())
         }
     }
 
-    /// Processes an instruction
-    pub fn process_instruction(
+    fn update_channel<'a>(
         program_id: &Pubkey,
-        accounts: &[AccountInfo],
-        input: &[u8],
+        dweller_administrator: &AccountInfo<'a>,
+        server_administrator: &AccountInfo<'a>,
+        server: &AccountInfo<'a>,
+        server_channel: &AccountInfo<'a>,
+        channel_overwrites: &[AccountInfo<'a>],
+        input: &UpdateChannelInput,
+        signers: &[AccountInfo<'a>],
     ) -> ProgramResult {
-        let instruction = Instruction::try_from_slice(&input[0..1])?;
-        match instruction {
-            Instruction::CreateDerivedAccount => {
-                msg!("Instruction: CreateDerivedAccount");
-                match accounts {
-                    [payer_account_info, owner_account_info, base_account_info, account_to_create_info, rent_account_info, system_program, ..] =>
-                    {
-                        let input =
-                            super::instruction::AddressTypeInput::deserialize_const(&input[1..])?;
+        require_admin(
+            program_id,
+            dweller_administrator,
+            server,
+            server_administrator,
+            signers,
+        )?;
+        append_audit_log(
+            program_id,
+            server,
+            signers,
+            dweller_administrator.key,
+            AuditAction::UpdateChannel,
+            server_channel.key,
+        )?;
+        let kind = validate_channel(input.type_id, &input.voice)?;
+        require_channel_permission(
+            program_id,
+            dweller_administrator,
+            server,
+            server_channel,
+            channel_overwrites,
+            ChannelOverwrite::MANAGE,
+        )?;
+
+        let channel_state = server_channel.read_data_with_borsh::<ServerChannel>()?;
+        let server_channel_key = create_index_with_seed(
+            program_id,
+            ServerChannel::SEED,
+            server.key,
+            channel_state.index,
+        )?;
+
+        if server_channel_key != *server_channel.key || channel_state.container != *server.key {
+            return Err(Error::InvalidDerivedServerChannelAddress.into());
+        }
+
+        match channel_state.version {
+            StateVersion::V2 => {
+                let (mut channel_data, mut channel_state) =
+                    server_channel.read_data_with_borsh_mut::<ServerChannelV2>()?;
+                channel_state.kind = kind;
+                channel_state.name = input.name;
+                channel_state.topic_hash = input.topic_hash;
+                channel_state.position = input.position;
+                channel_state.voice = input.voice.clone();
+                channel_state.serialize_const(&mut channel_data)?;
+                Ok(())
+            }
+            StateVersion::V1 => {
+                if input.topic_hash != [0; 64]
+                    || input.position != 0
+                    || input.voice != VoiceChannelConfig::default()
+                {
+                    return Err(Error::UnsupportedStateVersion.into());
+                }
+                let (mut channel_data, mut channel_state) =
+                    server_channel.read_data_with_borsh_mut::<ServerChannel>()?;
+                channel_state.type_id = input.type_id;
+                channel_state.name = input.name;
+                channel_state.serialize_const(&mut channel_data)?;
+                Ok(())
+            }
+            StateVersion::V3 | StateVersion::V4 => Err(Error::UnsupportedStateVersion.into()),
+            StateVersion::Uninitialized => Err(ProgramError::UninitializedAccount),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn set_channel_overwrite<'a>(
+        program_id: &Pubkey,
+        dweller_administrator: &AccountInfo<'a>,
+        server_administrator: &AccountInfo<'a>,
+        server: &AccountInfo<'a>,
+        server_channel: &AccountInfo<'a>,
+        channel_overwrite: &AccountInfo<'a>,
+        channel_overwrites: &[AccountInfo<'a>],
+        input: &SetChannelOverwriteInput,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        require_admin(
+            program_id,
+            dweller_administrator,
+            server,
+            server_administrator,
+            signers,
+        )?;
+        append_audit_log(
+            program_id,
+            server,
+            signers,
+            dweller_administrator.key,
+            AuditAction::SetChannelOverwrite,
+            server_channel.key,
+        )?;
+        require_channel_v2(program_id, server, server_channel)?;
+        let overwrites = require_channel_permission(
+            program_id,
+            dweller_administrator,
+            server,
+            server_channel,
+            channel_overwrites,
+            ChannelOverwrite::MANAGE,
+        )?;
+
+        let dweller = if input.target == OverwriteTarget::Dweller {
+            input.dweller
+        } else {
+            Pubkey::default()
+        };
+
+        let (mut channel_data, mut channel_state) =
+            server_channel.read_data_with_borsh_mut::<ServerChannelV2>()?;
+        create_list_entry_if_missing::<ChannelOverwrite>(
+            program_id,
+            ChannelOverwrite::SEED,
+            server_channel,
+            channel_state.overwrites,
+            channel_overwrite,
+            signers,
+        )?;
+        let (mut overwrite_data, mut overwrite_state) =
+            channel_overwrite.read_data_with_borsh_mut::<ChannelOverwrite>()?;
+
+        if overwrite_state.version == StateVersion::Uninitialized {
+            let channel_overwrite_key = create_index_with_seed(
+                program_id,
+                ChannelOverwrite::SEED,
+                server_channel.key,
+                channel_state.overwrites,
+            )?;
+
+            if channel_overwrite_key != *channel_overwrite.key
+                || overwrites
+                    .iter()
+                    .any(|x| x.same_target(input.target, &dweller))
+            {
+                return Err(Error::InvalidDerivedChannelOverwriteAddress.into());
+            }
+
+            overwrite_state.version = StateVersion::V1;
+            overwrite_state.container = *server_channel.key;
+            overwrite_state.index = channel_state.overwrites;
+            overwrite_state.target = input.target;
+            overwrite_state.dweller = dweller;
+
+            channel_state.overwrites = channel_state.overwrites.error_increment()?;
+            channel_state.serialize_const(&mut channel_data)?;
+        } else {
+            let channel_overwrite_key = create_index_with_seed(
+                program_id,
+                ChannelOverwrite::SEED,
+                server_channel.key,
+                overwrite_state.index,
+            )?;
+
+            if channel_overwrite_key != *channel_overwrite.key
+                || overwrite_state.container != *server_channel.key
+                || !overwrite_state.same_target(input.target, &dweller)
+            {
+                return Err(Error::InvalidDerivedChannelOverwriteAddress.into());
+            }
+        }
+
+        overwrite_state.allow = input.allow;
+        overwrite_state.deny = input.deny;
+        overwrite_state.serialize_const(&mut overwrite_data)?;
+
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn remove_channel_overwrite<'a>(
+        program_id: &Pubkey,
+        dweller_administrator: &AccountInfo<'a>,
+        server_administrator: &AccountInfo<'a>,
+        server: &AccountInfo<'a>,
+        server_channel: &AccountInfo<'a>,
+        channel_overwrite: &AccountInfo<'a>,
+        channel_overwrite_last: &AccountInfo<'a>,
+        channel_overwrites: &[AccountInfo<'a>],
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        require_admin(
+            program_id,
+            dweller_administrator,
+            server,
+            server_administrator,
+            signers,
+        )?;
+        append_audit_log(
+            program_id,
+            server,
+            signers,
+            dweller_administrator.key,
+            AuditAction::RemoveChannelOverwrite,
+            server_channel.key,
+        )?;
+        require_channel_v2(program_id, server, server_channel)?;
+        require_channel_permission(
+            program_id,
+            dweller_administrator,
+            server,
+            server_channel,
+            channel_overwrites,
+            ChannelOverwrite::MANAGE,
+        )?;
+
+        let (mut channel_data, mut channel_state) =
+            server_channel.read_data_with_borsh_mut::<ServerChannelV2>()?;
+        let overwrite_state = channel_overwrite.read_data_with_borsh::<ChannelOverwrite>()?;
+
+        let channel_overwrite_key = create_index_with_seed(
+            program_id,
+            ChannelOverwrite::SEED,
+            server_channel.key,
+            overwrite_state.index,
+        )?;
+
+        let channel_overwrite_last_key = create_index_with_seed(
+            program_id,
+            ChannelOverwrite::SEED,
+            server_channel.key,
+            channel_state.overwrites.error_decrement()?,
+        )?;
+
+        if channel_overwrite_key == *channel_overwrite.key
+            && channel_overwrite_last_key == *channel_overwrite_last.key
+        {
+            swap_accounts::<ChannelOverwrite>(channel_overwrite, channel_overwrite_last, signers)?;
+
+            channel_state.overwrites = channel_state.overwrites.error_decrement()?;
+            channel_state.serialize_const(&mut channel_data)?;
+
+            Ok(())
+        } else {
+            Err(Error::InvalidDerivedChannelOverwriteAddress.into())
+        }
+    }
+
+    fn create_group<'a>(
+        program_id: &Pubkey,
+        dweller_administrator: &AccountInfo<'a>,
+        server_administrator: &AccountInfo<'a>,
+        server: &AccountInfo<'a>,
+        server_group: &AccountInfo<'a>,
+        input: &CreateGroupInput,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        require_admin(
+            program_id,
+            dweller_administrator,
+            server,
+            server_administrator,
+            signers,
+        )?;
+        append_audit_log(
+            program_id,
+            server,
+            signers,
+            dweller_administrator.key,
+            AuditAction::CreateGroup,
+            server_group.key,
+        )?;
+        let mut server_state = server.read_data_with_zero_copy_mut::<ServerPod>()?;
+        let groups = server_state.groups.into();
+
+        let server_group_key =
+            create_index_with_seed(program_id, ServerGroup::SEED, server.key, groups)?;
+
+        if server_group_key == *server_group.key {
+            create_list_entry_if_missing::<ServerGroup>(
+                program_id,
+                ServerGroup::SEED,
+                server,
+                groups,
+                server_group,
+                signers,
+            )?;
+            let (mut group_data, mut group_state) =
+                server_group.read_data_with_borsh_mut::<ServerGroupV2>()?;
+            group_state.container = *server.key;
+            group_state.name = input.name;
+            group_state.version = StateVersion::V2;
+            group_state.index = groups;
+
+            server_state.groups = groups.error_increment()?.into();
+
+            group_state.serialize_const(&mut group_data)?;
+
+            Ok(())
+        } else {
+            Err(Error::InvalidDerivedServerGroupAddress.into())
+        }
+    }
+
+    fn rename_group<'a>(
+        program_id: &Pubkey,
+        dweller_administrator: &AccountInfo<'a>,
+        server_administrator: &AccountInfo<'a>,
+        server: &AccountInfo<'a>,
+        server_group: &AccountInfo<'a>,
+        input: &SetNameInput,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        require_admin(
+            program_id,
+            dweller_administrator,
+            server,
+            server_administrator,
+            signers,
+        )?;
+        append_audit_log(
+            program_id,
+            server,
+            signers,
+            dweller_administrator.key,
+            AuditAction::RenameGroup,
+            server_group.key,
+        )?;
+        require_group(program_id, server, server_group)?;
+
+        let (mut group_data, mut group_state) =
+            server_group.read_data_with_borsh_mut::<ServerGroup>()?;
+        group_state.name = input.name;
+        group_state.serialize_const(&mut group_data)?;
+
+        Ok(())
+    }
+
+    fn move_group_channel<'a>(
+        program_id: &Pubkey,
+        dweller_administrator: &AccountInfo<'a>,
+        server_administrator: &AccountInfo<'a>,
+        server: &AccountInfo<'a>,
+        server_group: &AccountInfo<'a>,
+        group_channel: &AccountInfo<'a>,
+        group_channel_other: &AccountInfo<'a>,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        require_admin(
+            program_id,
+            dweller_administrator,
+            server,
+            server_administrator,
+            signers,
+        )?;
+        append_audit_log(
+            program_id,
+            server,
+            signers,
+            dweller_administrator.key,
+            AuditAction::MoveGroupChannel,
+            server_group.key,
+        )?;
+        let group_state = require_group(program_id, server, server_group)?;
+        if group_state.version != StateVersion::V2 {
+            return Err(Error::UnsupportedStateVersion.into());
+        }
+
+        if group_channel.key == group_channel_other.key {
+            return Ok(());
+        }
+
+        let (mut channel_data, mut channel_state) =
+            group_channel.read_data_with_borsh_mut::<GroupChannelV2>()?;
+        let (mut other_data, mut other_state) =
+            group_channel_other.read_data_with_borsh_mut::<GroupChannelV2>()?;
+
+        let require_in_group = |key: &Pubkey, state: &GroupChannelV2| -> ProgramResult {
+            let group_channel_key = create_index_with_seed(
+                program_id,
+                GroupChannel::SEED,
+                server_group.key,
+                state.index,
+            )?;
+
+            if group_channel_key == *key
+                && state.container == *server_group.key
+                && state.index < group_state.channels
+            {
+                Ok(())
+            } else {
+                Err(Error::InvalidDerivedGroupChannelAddress.into())
+            }
+        };
+        require_in_group(group_channel.key, &channel_state)?;
+        require_in_group(group_channel_other.key, &other_state)?;
+
+        core::mem::swap(&mut channel_state.position, &mut other_state.position);
+        channel_state.serialize_const(&mut channel_data)?;
+        other_state.serialize_const(&mut other_data)?;
+
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn add_admin<'a>(
+        program_id: &Pubkey,
+        owner: &AccountInfo<'a>,
+        dweller: &AccountInfo<'a>,
+        server: &AccountInfo<'a>,
+        server_administrator: &AccountInfo<'a>,
+        administrator_marker: &AccountInfo<'a>,
+        payer: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        if is_dweller_authority(program_id, owner, signers)? {
+            create_dweller_marker(
+                program_id,
+                ServerAdministrator::SEED,
+                server,
+                dweller,
+                administrator_marker,
+                payer,
+                system_program,
+                Error::AlreadyAdministrator,
+            )?;
+            let (mut server_data, mut server_state) =
+                server.read_data_with_borsh_mut::<Server>()?;
+            require_owner(program_id, &server_state, owner, signers)?;
+            append_audit_log(
+                program_id,
+                server,
+                signers,
+                owner.key,
+                AuditAction::AddAdministrator,
+                dweller.key,
+            )?;
+
+            let administrator_key = create_index_with_seed(
+                program_id,
+                ServerAdministrator::SEED,
+                server.key,
+                server_state.administrators,
+            )?;
+
+            if administrator_key == *server_administrator.key {
+                create_list_entry_if_missing::<ServerAdministrator>(
+                    program_id,
+                    ServerAdministrator::SEED,
+                    server,
+                    server_state.administrators,
+                    server_administrator,
+                    signers,
+                )?;
+                let (mut server_administrator_data, mut server_administrator_state) =
+                    server_administrator.read_data_with_borsh_mut::<ServerAdministrator>()?;
+                if server_administrator_state.version == StateVersion::Uninitialized {
+                    server_administrator_state.container = *server.key;
+                    server_administrator_state.dweller = *dweller.key;
+                    server_administrator_state.version = StateVersion::V1;
+                    server_administrator_state.index = server_state.administrators;
+                    server_administrator_state.serialize_const(&mut server_administrator_data)?;
+
+                    server_state.administrators = server_state.administrators.error_increment()?;
+                    server_state.serialize_const(&mut server_data)?;
+
+                    Ok(())
+                } else {
+                    Err(ProgramError::AccountAlreadyInitialized)
+                }
+            } else {
+                Err(Error::InvalidDerivedServerAdministratorAddress.into())
+            }
+        } else {
+            Err(ProgramError::MissingRequiredSignature)
+        }
+    }
+
+    fn remove_admin<'a>(
+        program_id: &Pubkey,
+        owner: &AccountInfo<'a>,
+        server: &AccountInfo<'a>,
+        server_admin: &AccountInfo<'a>,
+        server_admin_last: &AccountInfo<'a>,
+        administrator_marker: &AccountInfo<'a>,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        let server_state = server.read_data_with_borsh::<Server>()?;
+        if server_state.owner == *owner.key && is_dweller_authority(program_id, owner, signers)? {
+            let server_admin_state = server_admin.read_data_with_borsh::<ServerAdministrator>()?;
+            append_audit_log(
+                program_id,
+                server,
+                signers,
+                owner.key,
+                AuditAction::RemoveAdministrator,
+                &server_admin_state.dweller,
+            )?;
+            close_dweller_marker(
+                program_id,
+                ServerAdministrator::SEED,
+                server.key,
+                &server_admin_state.dweller,
+                administrator_marker,
+                signers,
+            )?;
+            remove_server_administrator(
+                program_id,
+                server,
+                server_admin,
+                server_admin_last,
+                signers,
+            )
+        } else {
+            Err(ProgramError::MissingRequiredSignature)
+        }
+    }
+
+    fn revoke_invite_server<'a>(
+        program_id: &Pubkey,
+        server: &AccountInfo<'a>,
+        dweller_administrator: &AccountInfo<'a>,
+        server_administrator: &AccountInfo<'a>,
+        member_status: &AccountInfo<'a>,
+        member_status_last: &AccountInfo<'a>,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        require_admin(
+            program_id,
+            dweller_administrator,
+            server,
+            server_administrator,
+            signers,
+        )?;
+        append_audit_log(
+            program_id,
+            server,
+            signers,
+            dweller_administrator.key,
+            AuditAction::RevokeInvite,
+            &member_status
+                .read_data_with_borsh::<ServerMemberStatus>()?
+                .dweller,
+        )?;
+
+        remove_server_member_status(
+            program_id,
+            server,
+            member_status,
+            member_status_last,
+            signers,
+        )
+    }
+
+    fn decline_invite<'a>(
+        program_id: &Pubkey,
+        server: &AccountInfo<'a>,
+        dweller: &AccountInfo<'a>,
+        member_status: &AccountInfo<'a>,
+        member_status_last: &AccountInfo<'a>,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        if is_dweller_authority(program_id, dweller, signers)? {
+            let member_status_state = member_status.read_data_with_borsh::<ServerMemberStatus>()?;
+            if member_status_state.dweller == *dweller.key {
+                remove_server_member_status(
+                    program_id,
+                    server,
+                    member_status,
+                    member_status_last,
+                    signers,
+                )
+            } else {
+                Err(Error::InvalidDerivedServerMemberStatusAddress.into())
+            }
+        } else {
+            Err(ProgramError::MissingRequiredSignature)
+        }
+    }
+
+    fn invite_to_server<'a>(
+        program_id: &Pubkey,
+        server: &AccountInfo<'a>,
+        dweller_administrator: &AccountInfo<'a>,
+        server_administrator: &AccountInfo<'a>,
+        dweller: &AccountInfo<'a>,
+        member_status: &AccountInfo<'a>,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        require_admin(
+            program_id,
+            dweller_administrator,
+            server,
+            server_administrator,
+            signers,
+        )?;
+        append_audit_log(
+            program_id,
+            server,
+            signers,
+            dweller_administrator.key,
+            AuditAction::InviteToServer,
+            dweller.key,
+        )?;
+
+        let mut server_state = server.read_data_with_zero_copy_mut::<ServerPod>()?;
+        let member_statuses = server_state.member_statuses.into();
+
+        let member_status_key = create_index_with_seed(
+            program_id,
+            ServerMemberStatus::SEED,
+            server.key,
+            member_statuses,
+        )?;
+
+        if member_status_key == *member_status.key {
+            create_list_entry_if_missing::<ServerMemberStatus>(
+                program_id,
+                ServerMemberStatus::SEED,
+                server,
+                member_statuses,
+                member_status,
+                signers,
+            )?;
+            let (mut member_status_data, mut member_status_state) =
+                member_status.read_data_with_borsh_mut::<ServerMemberStatus>()?;
+
+            member_status_state.container = *server.key;
+            member_status_state.version = StateVersion::V1;
+            member_status_state.dweller = *dweller.key;
+            member_status_state.index = member_statuses;
+
+            server_state.member_statuses = member_statuses.error_increment()?.into();
+
+            member_status_state.serialize_const(&mut member_status_data)?;
+
+            Ok(())
+        } else {
+            Err(Error::InvalidDerivedServerMemberStatusAddress.into())
+        }
+    }
+
+    /// Create derived
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_derived_address<'a>(
+        program_id: &Pubkey,
+        payer_account_info: &AccountInfo<'a>,
+        owner_account_info: &AccountInfo<'a>,
+        base_account_info: &AccountInfo<'a>,
+        account_to_create_info: &AccountInfo<'a>,
+        rent_account_info: &AccountInfo<'a>,
+        _system_program: &AccountInfo<'a>,
+        input: &AddressTypeInput,
+    ) -> ProgramResult {
+        let rent = &Rent::from_account_info(rent_account_info)?;
+        if system_program::id() != *_system_program.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        match input {
+            AddressTypeInput::DwellerServer(index) => {
+                create_seeded_list_entry_account::<DwellerServer>(
+                    DwellerServer::SEED,
+                    owner_account_info,
+                    index,
+                    base_account_info,
+                    account_to_create_info,
+                    payer_account_info,
+                    rent,
+                    program_id,
+                )
+            }
+            AddressTypeInput::ServerMemberStatus(index) => {
+                create_seeded_list_entry_account::<ServerMemberStatus>(
+                    ServerMemberStatus::SEED,
+                    owner_account_info,
+                    index,
+                    base_account_info,
+                    account_to_create_info,
+                    payer_account_info,
+                    rent,
+                    program_id,
+                )
+            }
+            AddressTypeInput::ServerAdministrator(index) => {
+                create_seeded_list_entry_account::<ServerAdministrator>(
+                    ServerAdministrator::SEED,
+                    owner_account_info,
+                    index,
+                    base_account_info,
+                    account_to_create_info,
+                    payer_account_info,
+                    rent,
+                    program_id,
+                )
+            }
+            AddressTypeInput::ServerMember(index) => {
+                create_seeded_list_entry_account::<ServerMember>(
+                    ServerMember::SEED,
+                    owner_account_info,
+                    index,
+                    base_account_info,
+                    account_to_create_info,
+                    payer_account_info,
+                    rent,
+                    program_id,
+                )
+            }
+            AddressTypeInput::ServerChannel(index) => {
+                create_seeded_list_entry_account::<ServerChannel>(
+                    ServerChannel::SEED,
+                    owner_account_info,
+                    index,
+                    base_account_info,
+                    account_to_create_info,
+                    payer_account_info,
+                    rent,
+                    program_id,
+                )
+            }
+            AddressTypeInput::ServerGroup(index) => {
+                create_seeded_list_entry_account::<ServerGroup>(
+                    ServerGroup::SEED,
+                    owner_account_info,
+                    index,
+                    base_account_info,
+                    account_to_create_info,
+                    payer_account_info,
+                    rent,
+                    program_id,
+                )
+            }
+            AddressTypeInput::GroupChannel(index) => {
+                create_seeded_list_entry_account::<GroupChannel>(
+                    GroupChannel::SEED,
+                    owner_account_info,
+                    index,
+                    base_account_info,
+                    account_to_create_info,
+                    payer_account_info,
+                    rent,
+                    program_id,
+                )
+            }
+            AddressTypeInput::JoinRequest(index) => {
+                create_seeded_list_entry_account::<JoinRequest>(
+                    JoinRequest::SEED,
+                    owner_account_info,
+                    index,
+                    base_account_info,
+                    account_to_create_info,
+                    payer_account_info,
+                    rent,
+                    program_id,
+                )
+            }
+            AddressTypeInput::ServerDirectory => {
+                if owner_account_info.key != program_id {
+                    return Err(ProgramError::InvalidSeeds);
+                }
+                create_seeded_rent_except_account(
+                    ServerDirectory::SEED,
+                    owner_account_info,
+                    &0,
+                    base_account_info,
+                    account_to_create_info,
+                    payer_account_info,
+                    rent,
+                    ServerDirectory::LEN,
+                    program_id,
+                )
+            }
+            AddressTypeInput::DirectoryEntry(index) => {
+                create_seeded_list_entry_account::<DirectoryEntry>(
+                    DirectoryEntry::SEED,
+                    owner_account_info,
+                    index,
+                    base_account_info,
+                    account_to_create_info,
+                    payer_account_info,
+                    rent,
+                    program_id,
+                )
+            }
+            AddressTypeInput::ChannelOverwrite(index) => {
+                create_seeded_list_entry_account::<ChannelOverwrite>(
+                    ChannelOverwrite::SEED,
+                    owner_account_info,
+                    index,
+                    base_account_info,
+                    account_to_create_info,
+                    payer_account_info,
+                    rent,
+                    program_id,
+                )
+            }
+            AddressTypeInput::ServerAuditLog => create_seeded_rent_except_account(
+                ServerAuditLog::SEED,
+                owner_account_info,
+                &0,
+                base_account_info,
+                account_to_create_info,
+                payer_account_info,
+                rent,
+                ServerAuditLog::LEN,
+                program_id,
+            ),
+            AddressTypeInput::ChannelHistory => create_seeded_rent_except_account(
+                ChannelHistory::SEED,
+                owner_account_info,
+                &0,
+                base_account_info,
+                account_to_create_info,
+                payer_account_info,
+                rent,
+                ChannelHistory::LEN,
+                program_id,
+            ),
+            AddressTypeInput::ChannelCheckpoint(index) => create_seeded_rent_except_account(
+                ChannelCheckpoint::SEED,
+                owner_account_info,
+                index,
+                base_account_info,
+                account_to_create_info,
+                payer_account_info,
+                rent,
+                ChannelCheckpoint::LEN,
+                program_id,
+            ),
+            AddressTypeInput::ServerEvents => create_seeded_rent_except_account(
+                ServerEvents::SEED,
+                owner_account_info,
+                &0,
+                base_account_info,
+                account_to_create_info,
+                payer_account_info,
+                rent,
+                ServerEvents::LEN,
+                program_id,
+            ),
+            AddressTypeInput::ServerEvent(index) => create_seeded_rent_except_account(
+                ServerEvent::SEED,
+                owner_account_info,
+                index,
+                base_account_info,
+                account_to_create_info,
+                payer_account_info,
+                rent,
+                ServerEvent::LEN,
+                program_id,
+            ),
+            AddressTypeInput::EventRsvp(index) => create_seeded_list_entry_account::<EventRsvp>(
+                EventRsvp::SEED,
+                owner_account_info,
+                index,
+                base_account_info,
+                account_to_create_info,
+                payer_account_info,
+                rent,
+                program_id,
+            ),
+        }
+    }
+
+    /// Processes an instruction
+    pub fn process_instruction(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        input: &[u8],
+    ) -> ProgramResult {
+        let instruction = Instruction::unpack(input)?;
+        match instruction {
+            Instruction::CreateDerivedAccount(input) => {
+                msg!("Instruction: CreateDerivedAccount");
+                match accounts {
+                    [payer_account_info, owner_account_info, base_account_info, account_to_create_info, rent_account_info, system_program, ..] => {
                         Self::create_derived_address(
                             program_id,
-                            payer_account_info,
-                            owner_account_info,
-                            base_account_info,
-                            account_to_create_info,
-                            rent_account_info,
+                            payer_account_info,
+                            owner_account_info,
+                            base_account_info,
+                            account_to_create_info,
+                            rent_account_info,
+                            system_program,
+                            &input,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+            Instruction::InitializeDweller(input) => {
+                msg!("Instruction: InitializeDweller");
+                match accounts {
+                    [dweller, ..] => Self::initialize_dweller(program_id, dweller, &input),
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+            Instruction::InitializeServer(input) => {
+                msg!("Instruction: InitializeServer");
+                match accounts {
+                    [dweller_owner, server, dweller_server, server_member, member_marker, payer, system_program, ..] => {
+                        Self::initialize_server(
+                            program_id,
+                            dweller_owner,
+                            server,
+                            dweller_server,
+                            server_member,
+                            member_marker,
+                            payer,
+                            system_program,
+                            &input,
+                            accounts,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+            Instruction::SetDwellerName(input) => {
+                msg!("Instruction: SetDwellerName");
+                match accounts {
+                    [dweller, ..] => Self::set_dweller_name(program_id, dweller, &input, accounts),
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+            Instruction::SetDwellerPhoto(input) => {
+                msg!("Instruction: SetDwellerPhoto");
+                match accounts {
+                    [dweller, ..] => Self::set_dweller_photo(program_id, dweller, &input, accounts),
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+            Instruction::SetDwellerStatus(input) => {
+                msg!("Instruction: SetDwellerStatus");
+                match accounts {
+                    [dweller, ..] => {
+                        Self::set_dweller_status(program_id, dweller, &input, accounts)
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+            Instruction::AddChannel(input) => {
+                msg!("Instruction: AddChannel");
+                match accounts {
+                    [dweller_administrator, server_administrator, server, server_channel, ..] => {
+                        Self::add_channel(
+                            program_id,
+                            dweller_administrator,
+                            server_administrator,
+                            server,
+                            server_channel,
+                            &input,
+                            accounts,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+            Instruction::CreateGroup(input) => {
+                msg!("Instruction: CreateGroup");
+                match accounts {
+                    [dweller, server_administrator, server, server_group, ..] => {
+                        Self::create_group(
+                            program_id,
+                            dweller,
+                            server_administrator,
+                            server,
+                            server_group,
+                            &input,
+                            accounts,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+            Instruction::AddAdmin => {
+                msg!("Instruction: AddAdmin");
+                match accounts {
+                    [owner, dweller, server, server_administrator, administrator_marker, payer, system_program, ..] => {
+                        Self::add_admin(
+                            program_id,
+                            owner,
+                            dweller,
+                            server,
+                            server_administrator,
+                            administrator_marker,
+                            payer,
+                            system_program,
+                            accounts,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+            Instruction::InviteToServer => {
+                msg!("Instruction: InviteToServer");
+                match accounts {
+                    [server, dweller_administrator, server_administrator, dweller, member_status, ..] => {
+                        Self::invite_to_server(
+                            program_id,
+                            server,
+                            dweller_administrator,
+                            server_administrator,
+                            dweller,
+                            member_status,
+                            accounts,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+
+            Instruction::RemoveAdmin => {
+                msg!("Instruction: RemoveAdmin");
+                match accounts {
+                    [owner, server, server_admin, server_admin_last, administrator_marker, ..] => {
+                        Self::remove_admin(
+                            program_id,
+                            owner,
+                            server,
+                            server_admin,
+                            server_admin_last,
+                            administrator_marker,
+                            accounts,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+
+            Instruction::RevokeInviteServer => {
+                msg!("Instruction: RevokeInviteServer");
+                match accounts {
+                    [server, dweller_administrator, server_administrator, member_status, member_status_last, ..] => {
+                        Self::revoke_invite_server(
+                            program_id,
+                            server,
+                            dweller_administrator,
+                            server_administrator,
+                            member_status,
+                            member_status_last,
+                            accounts,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+
+            Instruction::SetServerName(input) => {
+                msg!("Instruction: SetServerName");
+                match accounts {
+                    [dweller_administrator, server_administrator, server, ..] => {
+                        Self::set_server_name(
+                            program_id,
+                            dweller_administrator,
+                            server_administrator,
+                            server,
+                            &input,
+                            accounts,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+            Instruction::SetServerDb(input) => {
+                msg!("Instruction: SetServerDb");
+                match accounts {
+                    [dweller_administrator, server_administrator, server, ..] => {
+                        Self::set_server_db(
+                            program_id,
+                            dweller_administrator,
+                            server_administrator,
+                            server,
+                            &input,
+                            accounts,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+            Instruction::JoinServer => {
+                msg!("Instruction: JoinServer");
+                match accounts {
+                    [server, server_member, server_member_status, dweller, dweller_server, server_member_status_last, member_marker, payer, system_program, ..] => {
+                        Self::join_server(
+                            program_id,
+                            server,
+                            server_member,
+                            server_member_status,
+                            dweller,
+                            dweller_server,
+                            server_member_status_last,
+                            member_marker,
+                            payer,
                             system_program,
+                            accounts,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+
+            Instruction::LeaveServer => {
+                msg!("Instruction: LeaveServer");
+                match accounts {
+                    [server, server_member, server_member_last, dweller, dweller_server, dweller_server_last, member_marker, administrator_marker, rest @ ..] =>
+                    {
+                        let server_administrator = rest
+                            .iter()
+                            .position(|x| x.owner == program_id)
+                            .and_then(|i| rest.get(i..i + 2))
+                            .map(|x| (&x[0], &x[1]));
+                        Self::leave_server(
+                            program_id,
+                            server,
+                            server_member,
+                            server_member_last,
+                            dweller,
+                            dweller_server,
+                            dweller_server_last,
+                            member_marker,
+                            administrator_marker,
+                            server_administrator,
+                            accounts,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+
+            Instruction::AddChannelToGroup => {
+                msg!("Instruction: AddChannelToGroup");
+                match accounts {
+                    [server, dweller_administrator, server_administrator, server_channel, server_group, group_channel, ..] => {
+                        Self::add_channel_to_group(
+                            program_id,
+                            server,
+                            dweller_administrator,
+                            server_administrator,
+                            server_channel,
+                            server_group,
+                            group_channel,
+                            accounts,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+
+            Instruction::RemoveChannelFromGroup => {
+                msg!("Instruction: RemoveChannelFromGroup");
+                match accounts {
+                    [server, dweller_administrator, server_administrator, server_group, group_channel, group_channel_last, ..] => {
+                        Self::remove_channel_from_group(
+                            program_id,
+                            server,
+                            dweller_administrator,
+                            server_administrator,
+                            server_group,
+                            group_channel,
+                            group_channel_last,
+                            accounts,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+
+            Instruction::DeleteChannel => {
+                msg!("Instruction: DeleteChannel");
+                // ISSUE: in original Solidity contract channels are not deleted from groups
+                match accounts {
+                    [dweller, server_administrator, server, server_channel, server_channel_last, ..] => {
+                        Self::delete_channel(
+                            program_id,
+                            dweller,
+                            server_administrator,
+                            server,
+                            server_channel,
+                            server_channel_last,
+                            accounts,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+
+            Instruction::DeleteGroup => {
+                msg!("Instruction: DeleteGroup");
+                match accounts {
+                    [dweller_administrator, server_administrator, server, server_group, server_group_last, ..] =>
+                    {
+                        let group_channels = &accounts[5..];
+                        Self::delete_group(
+                            program_id,
+                            dweller_administrator,
+                            server_administrator,
+                            server,
+                            server_group,
+                            server_group_last,
+                            group_channels,
+                            accounts,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+
+            Instruction::SetJoinPolicy(input) => {
+                msg!("Instruction: SetJoinPolicy");
+                match accounts {
+                    [dweller_administrator, server_administrator, server, ..] => {
+                        Self::set_join_policy(
+                            program_id,
+                            dweller_administrator,
+                            server_administrator,
+                            server,
                             &input,
+                            accounts,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+
+            Instruction::RequestJoinServer => {
+                msg!("Instruction: RequestJoinServer");
+                match accounts {
+                    [server, dweller, join_request, member_marker, payer, system_program, ..] => {
+                        Self::request_join_server(
+                            program_id,
+                            server,
+                            dweller,
+                            join_request,
+                            member_marker,
+                            payer,
+                            system_program,
+                            accounts,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+
+            Instruction::ApproveJoinRequest => {
+                msg!("Instruction: ApproveJoinRequest");
+                match accounts {
+                    [dweller_administrator, server_administrator, server, join_request, join_request_last, server_member, dweller, dweller_server, member_marker, ..] => {
+                        Self::approve_join_request(
+                            program_id,
+                            dweller_administrator,
+                            server_administrator,
+                            server,
+                            join_request,
+                            join_request_last,
+                            server_member,
+                            dweller,
+                            dweller_server,
+                            member_marker,
+                            accounts,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+
+            Instruction::RejectJoinRequest => {
+                msg!("Instruction: RejectJoinRequest");
+                match accounts {
+                    [dweller_administrator, server_administrator, server, join_request, join_request_last, member_marker, ..] => {
+                        Self::reject_join_request(
+                            program_id,
+                            dweller_administrator,
+                            server_administrator,
+                            server,
+                            join_request,
+                            join_request_last,
+                            member_marker,
+                            accounts,
                         )
                     }
                     _ => Err(ProgramError::NotEnoughAccountKeys),
                 }
             }
-            Instruction::InitializeDweller => {
-                msg!("Instruction: InitializeDweller");
+
+            Instruction::UpdateChannel(input) => {
+                msg!("Instruction: UpdateChannel");
                 match accounts {
-                    [dweller, ..] => {
-                        let input = super::instruction::InitializeDwellerInput::deserialize_const(
-                            &input[1..],
-                        )?;
+                    [dweller_administrator, server_administrator, server, server_channel, ..] => {
+                        let channel_overwrites = &accounts[4..];
 
-                        Self::initialize_dweller(program_id, dweller, &input)
+                        Self::update_channel(
+                            program_id,
+                            dweller_administrator,
+                            server_administrator,
+                            server,
+                            server_channel,
+                            channel_overwrites,
+                            &input,
+                            accounts,
+                        )
                     }
                     _ => Err(ProgramError::NotEnoughAccountKeys),
                 }
             }
-            Instruction::InitializeServer => {
-                msg!("Instruction: InitializeServer");
-                match accounts {
-                    [dweller_owner, server, dweller_server, server_member, ..] => {
-                        let input = super::instruction::InitializeServerInput::deserialize_const(
-                            &input[1..],
-                        )?;
 
-                        Self::initialize_server(
+            Instruction::RenameGroup(input) => {
+                msg!("Instruction: RenameGroup");
+                match accounts {
+                    [dweller_administrator, server_administrator, server, server_group, ..] => {
+                        Self::rename_group(
                             program_id,
-                            dweller_owner,
+                            dweller_administrator,
+                            server_administrator,
                             server,
-                            dweller_server,
-                            server_member,
+                            server_group,
                             &input,
+                            accounts,
                         )
                     }
                     _ => Err(ProgramError::NotEnoughAccountKeys),
                 }
             }
-            Instruction::SetDwellerName => {
-                msg!("Instruction: SetDwellerName");
-                match accounts {
-                    [dweller, ..] => {
-                        let input =
-                            super::instruction::SetNameInput::deserialize_const(&input[1..])?;
 
-                        Self::set_dweller_name(program_id, dweller, &input)
+            Instruction::MoveGroupChannel => {
+                msg!("Instruction: MoveGroupChannel");
+                match accounts {
+                    [dweller_administrator, server_administrator, server, server_group, group_channel, group_channel_other, ..] => {
+                        Self::move_group_channel(
+                            program_id,
+                            dweller_administrator,
+                            server_administrator,
+                            server,
+                            server_group,
+                            group_channel,
+                            group_channel_other,
+                            accounts,
+                        )
                     }
                     _ => Err(ProgramError::NotEnoughAccountKeys),
                 }
             }
-            Instruction::SetDwellerPhoto => {
-                msg!("Instruction: SetDwellerPhoto");
+
+            Instruction::UpdateDwellerProfile(input) => {
+                msg!("Instruction: UpdateDwellerProfile");
                 match accounts {
-                    [dweller, ..] => {
-                        let input =
-                            super::instruction::SetHashInput::deserialize_const(&input[1..])?;
+                    [dweller, payer, system_program, ..] => Self::update_dweller_profile(
+                        program_id,
+                        dweller,
+                        payer,
+                        system_program,
+                        &input,
+                        accounts,
+                    ),
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
 
-                        Self::set_dweller_photo(program_id, dweller, &input)
+            Instruction::MigrateDweller => {
+                msg!("Instruction: MigrateDweller");
+                match accounts {
+                    [dweller, payer, system_program, ..] => {
+                        Self::migrate_dweller(program_id, dweller, payer, system_program, accounts)
                     }
                     _ => Err(ProgramError::NotEnoughAccountKeys),
                 }
             }
-            Instruction::SetDwellerStatus => {
-                msg!("Instruction: SetDwellerStatus");
+
+            Instruction::SetDwellerAuthority(input) => {
+                msg!("Instruction: SetDwellerAuthority");
                 match accounts {
                     [dweller, ..] => {
-                        let input = super::instruction::SetDwellerStatusInput::deserialize_const(
-                            &input[1..],
-                        )?;
-
-                        Self::set_dweller_status(program_id, dweller, &input)
+                        Self::set_dweller_authority(program_id, dweller, &input, accounts)
                     }
                     _ => Err(ProgramError::NotEnoughAccountKeys),
                 }
             }
-            Instruction::AddChannel => {
-                msg!("Instruction: AddChannel");
+
+            Instruction::SetServerPhoto(input) => {
+                msg!("Instruction: SetServerPhoto");
                 match accounts {
-                    [dweller_administrator, server_administrator, server, server_channel, ..] => {
-                        let input =
-                            super::instruction::AddChannelInput::deserialize_const(&input[1..])?;
+                    [dweller_administrator, server_administrator, server, ..] => {
+                        Self::update_server_profile(
+                            program_id,
+                            dweller_administrator,
+                            server_administrator,
+                            server,
+                            &UpdateServerProfileInput {
+                                photo_hash: Some(input.hash),
+                                ..Default::default()
+                            },
+                            accounts,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
 
-                        Self::add_channel(
+            Instruction::SetServerDescription(input) => {
+                msg!("Instruction: SetServerDescription");
+                match accounts {
+                    [dweller_administrator, server_administrator, server, ..] => {
+                        Self::update_server_profile(
                             program_id,
                             dweller_administrator,
                             server_administrator,
                             server,
-                            server_channel,
-                            &input,
+                            &UpdateServerProfileInput {
+                                description_hash: Some(input.hash),
+                                ..Default::default()
+                            },
+                            accounts,
                         )
                     }
                     _ => Err(ProgramError::NotEnoughAccountKeys),
                 }
             }
-            Instruction::CreateGroup => {
-                msg!("Instruction: CreateGroup");
+
+            Instruction::SetServerBanner(input) => {
+                msg!("Instruction: SetServerBanner");
                 match accounts {
-                    [dweller, server_administrator, server, server_group, ..] => {
-                        let input =
-                            super::instruction::CreateGroupInput::deserialize_const(&input[1..])?;
+                    [dweller_administrator, server_administrator, server, ..] => {
+                        Self::update_server_profile(
+                            program_id,
+                            dweller_administrator,
+                            server_administrator,
+                            server,
+                            &UpdateServerProfileInput {
+                                banner_hash: Some(input.hash),
+                                ..Default::default()
+                            },
+                            accounts,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
 
-                        Self::create_group(
+            Instruction::UpdateServerProfile(input) => {
+                msg!("Instruction: UpdateServerProfile");
+                match accounts {
+                    [dweller_administrator, server_administrator, server, ..] => {
+                        Self::update_server_profile(
                             program_id,
-                            dweller,
+                            dweller_administrator,
                             server_administrator,
                             server,
-                            server_group,
                             &input,
+                            accounts,
                         )
                     }
                     _ => Err(ProgramError::NotEnoughAccountKeys),
                 }
             }
-            Instruction::AddAdmin => {
-                msg!("Instruction: AddAdmin");
+
+            Instruction::ListServer(input) => {
+                msg!("Instruction: ListServer");
                 match accounts {
-                    [owner, dweller, server, server_administrator, ..] => {
-                        Self::add_admin(program_id, owner, dweller, server, server_administrator)
+                    [dweller_owner, server, server_directory, directory_entry, ..] => {
+                        Self::list_server(
+                            program_id,
+                            dweller_owner,
+                            server,
+                            server_directory,
+                            directory_entry,
+                            &input,
+                            accounts,
+                        )
                     }
                     _ => Err(ProgramError::NotEnoughAccountKeys),
                 }
             }
-            Instruction::InviteToServer => {
-                msg!("Instruction: InviteToServer");
+
+            Instruction::UnlistServer => {
+                msg!("Instruction: UnlistServer");
                 match accounts {
-                    [server, dweller_administrator, server_administrator, dweller, member_status, ..] => {
-                        Self::invite_to_server(
+                    [dweller_owner, server, server_directory, directory_entry, directory_entry_last, ..] => {
+                        Self::unlist_server(
                             program_id,
+                            dweller_owner,
                             server,
+                            server_directory,
+                            directory_entry,
+                            directory_entry_last,
+                            accounts,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+
+            Instruction::SetJoinRequirement(input) => {
+                msg!("Instruction: SetJoinRequirement");
+                match accounts {
+                    [dweller_administrator, server_administrator, server, payer, system_program, ..] => {
+                        Self::set_join_requirement(
+                            program_id,
                             dweller_administrator,
                             server_administrator,
+                            server,
+                            payer,
+                            system_program,
+                            &input,
+                            accounts,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+            Instruction::RecheckMember => {
+                msg!("Instruction: RecheckMember");
+                match accounts {
+                    [server, server_member, server_member_last, dweller, dweller_server, dweller_server_last, token, member_marker, ..] => {
+                        Self::recheck_member(
+                            program_id,
+                            server,
+                            server_member,
+                            server_member_last,
                             dweller,
-                            member_status,
+                            dweller_server,
+                            dweller_server_last,
+                            token,
+                            member_marker,
+                            accounts,
                         )
                     }
                     _ => Err(ProgramError::NotEnoughAccountKeys),
                 }
             }
-
-            Instruction::RemoveAdmin => {
-                msg!("Instruction: RemoveAdmin");
+            Instruction::SetJoinFee(input) => {
+                msg!("Instruction: SetJoinFee");
+                match accounts {
+                    [dweller_owner, server, payer, system_program, treasury, mint, token_program, ..] => {
+                        Self::set_join_fee(
+                            program_id,
+                            dweller_owner,
+                            server,
+                            payer,
+                            system_program,
+                            treasury,
+                            mint,
+                            token_program,
+                            &input,
+                            accounts,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+            Instruction::RenewMembership => {
+                msg!("Instruction: RenewMembership");
                 match accounts {
-                    [owner, server, server_admin, server_admin_last, ..] => Self::remove_admin(
+                    [server, server_member, authority, ..] => Self::renew_membership(
                         program_id,
-                        owner,
                         server,
-                        server_admin,
-                        server_admin_last,
+                        server_member,
+                        authority,
+                        accounts,
                     ),
                     _ => Err(ProgramError::NotEnoughAccountKeys),
                 }
             }
-
-            Instruction::RevokeInviteServer => {
-                msg!("Instruction: RevokeInviteServer");
+            Instruction::WithdrawTreasury(input) => {
+                msg!("Instruction: WithdrawTreasury");
                 match accounts {
-                    [server, dweller_administrator, server_administrator, member_status, member_status_last, ..] => {
-                        Self::revoke_invite_server(
+                    [dweller_owner, server, treasury, destination, token_program, ..] => {
+                        Self::withdraw_treasury(
                             program_id,
+                            dweller_owner,
                             server,
-                            dweller_administrator,
-                            server_administrator,
-                            member_status,
-                            member_status_last,
+                            treasury,
+                            destination,
+                            token_program,
+                            &input,
+                            accounts,
                         )
                     }
                     _ => Err(ProgramError::NotEnoughAccountKeys),
                 }
             }
-
-            Instruction::SetServerName => {
-                msg!("Instruction: SetServerName");
+            Instruction::CreateServerEvent(input) => {
+                msg!("Instruction: CreateServerEvent");
                 match accounts {
-                    [dweller_administrator, server_administrator, server, ..] => {
-                        let input =
-                            super::instruction::SetNameInput::deserialize_const(&input[1..])?;
-
-                        Self::set_server_name(
+                    [dweller_administrator, server_administrator, server, server_events, server_event, server_channel, ..] => {
+                        Self::create_server_event(
                             program_id,
                             dweller_administrator,
                             server_administrator,
                             server,
+                            server_events,
+                            server_event,
+                            server_channel,
                             &input,
+                            accounts,
                         )
                     }
                     _ => Err(ProgramError::NotEnoughAccountKeys),
                 }
             }
-            Instruction::SetServerDb => {
-                msg!("Instruction: SetServerDb");
+            Instruction::CancelServerEvent => {
+                msg!("Instruction: CancelServerEvent");
                 match accounts {
-                    [dweller_administrator, server_administrator, server, ..] => {
-                        let input =
-                            super::instruction::SetHashInput::deserialize_const(&input[1..])?;
-
-                        Self::set_server_db(
+                    [dweller_administrator, server_administrator, server, server_event, ..] => {
+                        Self::cancel_server_event(
                             program_id,
                             dweller_administrator,
                             server_administrator,
                             server,
-                            &input,
+                            server_event,
+                            accounts,
                         )
                     }
                     _ => Err(ProgramError::NotEnoughAccountKeys),
                 }
             }
-            Instruction::JoinServer => {
-                msg!("Instruction: JoinServer");
+            Instruction::Rsvp => {
+                msg!("Instruction: Rsvp");
                 match accounts {
-                    [server, server_member, server_member_status, dweller, dweller_server, ..] => {
-                        Self::join_server(
+                    [dweller, server, server_member, server_event, event_rsvp, event_rsvp_marker, payer, system_program, ..] => {
+                        Self::rsvp(
                             program_id,
+                            dweller,
                             server,
                             server_member,
-                            server_member_status,
-                            dweller,
-                            dweller_server,
+                            server_event,
+                            event_rsvp,
+                            event_rsvp_marker,
+                            payer,
+                            system_program,
+                            accounts,
                         )
                     }
                     _ => Err(ProgramError::NotEnoughAccountKeys),
                 }
             }
-
-            Instruction::LeaveServer => {
-                msg!("Instruction: LeaveServer");
+            Instruction::CancelRsvp => {
+                msg!("Instruction: CancelRsvp");
                 match accounts {
-                    [server, server_member, server_member_last, dweller, dweller_server, dweller_server_last, ..] => {
-                        Self::leave_server(
+                    [dweller, server, server_event, event_rsvp, event_rsvp_last, event_rsvp_marker, ..] => {
+                        Self::cancel_rsvp(
                             program_id,
+                            dweller,
                             server,
-                            server_member,
-                            server_member_last,
+                            server_event,
+                            event_rsvp,
+                            event_rsvp_last,
+                            event_rsvp_marker,
+                            accounts,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+            Instruction::ClaimHandle(input) => {
+                msg!("Instruction: ClaimHandle");
+                match accounts {
+                    [dweller, dweller_handle, payer, system_program, ..] => Self::claim_handle(
+                        program_id,
+                        dweller,
+                        dweller_handle,
+                        payer,
+                        system_program,
+                        &input,
+                        accounts,
+                    ),
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+            Instruction::ReleaseHandle => {
+                msg!("Instruction: ReleaseHandle");
+                match accounts {
+                    [dweller, dweller_handle, destination, ..] => Self::release_handle(
+                        program_id,
+                        dweller,
+                        dweller_handle,
+                        destination,
+                        accounts,
+                    ),
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+            Instruction::TransferHandle => {
+                msg!("Instruction: TransferHandle");
+                match accounts {
+                    [dweller, dweller_handle, dweller_to, payer, system_program, ..] => {
+                        Self::transfer_handle(
+                            program_id,
                             dweller,
-                            dweller_server,
-                            dweller_server_last,
+                            dweller_handle,
+                            dweller_to,
+                            payer,
+                            system_program,
+                            accounts,
                         )
                     }
                     _ => Err(ProgramError::NotEnoughAccountKeys),
                 }
             }
-
-            Instruction::AddChannelToGroup => {
-                msg!("Instruction: AddChannelToGroup");
+            Instruction::SetDwellerNftAvatar => {
+                msg!("Instruction: SetDwellerNftAvatar");
                 match accounts {
-                    [server, dweller_administrator, server_administrator, server_channel, server_group, group_channel, ..] => {
-                        Self::add_channel_to_group(
+                    [dweller, token, token_data, payer, system_program, ..] => {
+                        Self::set_dweller_nft_avatar(
+                            program_id,
+                            dweller,
+                            token,
+                            token_data,
+                            payer,
+                            system_program,
+                            accounts,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+            Instruction::ClearStaleAvatar => {
+                msg!("Instruction: ClearStaleAvatar");
+                match accounts {
+                    [dweller, token, ..] => Self::clear_stale_avatar(program_id, dweller, token),
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+            Instruction::Reindex(input) => {
+                msg!("Instruction: Reindex");
+                match accounts {
+                    [dweller_administrator, server_administrator, server, container, ..] => {
+                        Self::reindex(
                             program_id,
+                            dweller_administrator,
+                            server_administrator,
                             server,
+                            container,
+                            list_entries(&accounts[4..], input.count)?,
+                            &input,
+                            accounts,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+            Instruction::ReindexDwellerServers(input) => {
+                msg!("Instruction: ReindexDwellerServers");
+                match accounts {
+                    [dweller, ..] => Self::reindex_dweller_servers(
+                        program_id,
+                        dweller,
+                        list_entries(&accounts[1..], input.count)?,
+                        &input,
+                        accounts,
+                    ),
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+            Instruction::SetChannelCommitter(input) => {
+                msg!("Instruction: SetChannelCommitter");
+                match accounts {
+                    [dweller_administrator, server_administrator, server, server_channel, channel_history, ..] => {
+                        Self::set_channel_committer(
+                            program_id,
                             dweller_administrator,
                             server_administrator,
+                            server,
                             server_channel,
-                            server_group,
-                            group_channel,
+                            channel_history,
+                            &input,
+                            accounts,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+            Instruction::CommitChannelCheckpoint(input) => {
+                msg!("Instruction: CommitChannelCheckpoint");
+                match accounts {
+                    [committer, server_administrator, server, server_channel, channel_history, channel_checkpoint, ..] => {
+                        Self::commit_channel_checkpoint(
+                            program_id,
+                            committer,
+                            server_administrator,
+                            server,
+                            server_channel,
+                            channel_history,
+                            channel_checkpoint,
+                            &input,
+                            accounts,
+                        )
+                    }
+                    _ => Err(ProgramError::NotEnoughAccountKeys),
+                }
+            }
+            Instruction::SetMemberNickname(input) => {
+                msg!("Instruction: SetMemberNickname");
+                match accounts {
+                    [dweller, server, server_member, server_administrator, ..] => {
+                        Self::set_member_nickname(
+                            program_id,
+                            dweller,
+                            server,
+                            server_member,
+                            server_administrator,
+                            &input,
+                            accounts,
                         )
                     }
                     _ => Err(ProgramError::NotEnoughAccountKeys),
                 }
             }
 
-            Instruction::RemoveChannelFromGroup => {
-                msg!("Instruction: RemoveChannelFromGroup");
+            Instruction::SetChannelOverwrite(input) => {
+                msg!("Instruction: SetChannelOverwrite");
                 match accounts {
-                    [server, dweller_administrator, server_administrator, server_group, group_channel, group_channel_last, ..] => {
-                        Self::remove_channel_from_group(
+                    [dweller_administrator, server_administrator, server, server_channel, channel_overwrite, ..] =>
+                    {
+                        let channel_overwrites = &accounts[5..];
+
+                        Self::set_channel_overwrite(
                             program_id,
-                            server,
                             dweller_administrator,
                             server_administrator,
-                            server_group,
-                            group_channel,
-                            group_channel_last,
+                            server,
+                            server_channel,
+                            channel_overwrite,
+                            channel_overwrites,
+                            &input,
+                            accounts,
                         )
                     }
                     _ => Err(ProgramError::NotEnoughAccountKeys),
                 }
             }
 
-            Instruction::DeleteChannel => {
-                msg!("Instruction: DeleteChannel");
-                // ISSUE: in original Solidity contract channels are not deleted from groups
+            Instruction::RemoveChannelOverwrite => {
+                msg!("Instruction: RemoveChannelOverwrite");
                 match accounts {
-                    [dweller, server_administrator, server, server_channel, server_channel_last, ..] => {
-                        Self::delete_channel(
+                    [dweller_administrator, server_administrator, server, server_channel, channel_overwrite, channel_overwrite_last, ..] =>
+                    {
+                        let channel_overwrites = &accounts[6..];
+
+                        Self::remove_channel_overwrite(
                             program_id,
-                            dweller,
+                            dweller_administrator,
                             server_administrator,
                             server,
                             server_channel,
-                            server_channel_last,
+                            channel_overwrite,
+                            channel_overwrite_last,
+                            channel_overwrites,
+                            accounts,
                         )
                     }
                     _ => Err(ProgramError::NotEnoughAccountKeys),
                 }
             }
 
-            Instruction::DeleteGroup => {
-                msg!("Instruction: DeleteGroup");
+            Instruction::DeclineInvite => {
+                msg!("Instruction: DeclineInvite");
                 match accounts {
-                    [dweller_administrator, server_administrator, server, server_group, server_group_last, ..] =>
-                    {
-                        let group_channels = &accounts[5..];
-                        Self::delete_group(
+                    [server, dweller, member_status, member_status_last, ..] => {
+                        Self::decline_invite(
                             program_id,
-                            dweller_administrator,
-                            server_administrator,
                             server,
-                            server_group,
-                            server_group_last,
-                            group_channels,
+                            dweller,
+                            member_status,
+                            member_status_last,
+                            accounts,
                         )
                     }
                     _ => Err(ProgramError::NotEnoughAccountKeys),
@@ -926,12 +3516,22 @@ impl Processor {
         server_group: &AccountInfo<'a>,
         server_group_last: &AccountInfo<'a>,
         group_channels: &[AccountInfo<'a>],
+        signers: &[AccountInfo<'a>],
     ) -> ProgramResult {
         require_admin(
             program_id,
             dweller_administrator,
             server,
             server_administrator,
+            signers,
+        )?;
+        append_audit_log(
+            program_id,
+            server,
+            signers,
+            dweller_administrator.key,
+            AuditAction::DeleteGroup,
+            server_group.key,
         )?;
 
         let (mut data, mut server_state) = server.read_data_with_borsh_mut::<Server>()?;
@@ -953,7 +3553,7 @@ impl Processor {
 
         if server_group_key == *server_group.key && server_group_last_key == *server_group_last.key
         {
-            for child in group_channels {
+            for child in trailing_accounts(program_id, server, group_channels)? {
                 let child_state = server_group.read_data_with_borsh::<GroupChannel>()?;
                 let child_key = create_index_with_seed(
                     program_id,
@@ -963,13 +3563,13 @@ impl Processor {
                 )?;
 
                 if child_key == *child.key {
-                    swap_accounts::<GroupChannel>(child, child)?;
+                    swap_accounts::<GroupChannel>(child, child, signers)?;
                 } else {
                     return Err(Error::InvalidDerivedGroupChannelAddress.into());
                 }
             }
 
-            swap_accounts::<ServerGroup>(server_group, server_group_last)?;
+            swap_accounts::<ServerGroup>(server_group, server_group_last, signers)?;
 
             server_state.groups = server_state.groups.error_decrement()?;
             server_state.serialize_const(&mut data)?;
@@ -987,8 +3587,17 @@ impl Processor {
         server: &AccountInfo<'a>,
         server_channel: &AccountInfo<'a>,
         server_channel_last: &AccountInfo<'a>,
+        signers: &[AccountInfo<'a>],
     ) -> ProgramResult {
-        require_admin(program_id, dweller, server, server_administrator)?;
+        require_admin(program_id, dweller, server, server_administrator, signers)?;
+        append_audit_log(
+            program_id,
+            server,
+            signers,
+            dweller.key,
+            AuditAction::DeleteChannel,
+            server_channel.key,
+        )?;
 
         let (mut server_data, mut server_state) = server.read_data_with_borsh_mut::<Server>()?;
         let channel_state = server_channel.read_data_with_borsh::<ServerChannel>()?;
@@ -1010,255 +3619,1205 @@ impl Processor {
         if server_channel_key == *server_channel.key
             && server_channel_last_key == *server_channel_last.key
         {
-            swap_accounts::<ServerChannel>(server_channel, server_channel_last)?;
+            if channel_overwrites_count(server_channel)? != 0
+                || channel_overwrites_count(server_channel_last)? != 0
+            {
+                return Err(Error::ChannelHasOverwrites.into());
+            }
+
+            swap_accounts::<ServerChannel>(server_channel, server_channel_last, signers)?;
 
             server_state.channels = server_state.channels.error_decrement()?;
             server_state.serialize_const(&mut server_data)?;
 
-            Ok(())
+            Ok(())
+        } else {
+            Err(Error::InvalidDerivedServerChannelAddress.into())
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn remove_channel_from_group<'a>(
+        program_id: &Pubkey,
+        server: &AccountInfo<'a>,
+        dweller_administrator: &AccountInfo<'a>,
+        server_administrator: &AccountInfo<'a>,
+        server_group: &AccountInfo<'a>,
+        group_channel: &AccountInfo<'a>,
+        group_channel_last: &AccountInfo<'a>,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        require_admin(
+            program_id,
+            dweller_administrator,
+            server,
+            server_administrator,
+            signers,
+        )?;
+        append_audit_log(
+            program_id,
+            server,
+            signers,
+            dweller_administrator.key,
+            AuditAction::RemoveChannelFromGroup,
+            server_group.key,
+        )?;
+        let (mut group_data, mut group_state) =
+            server_group.read_data_with_borsh_mut::<ServerGroup>()?;
+
+        let group_channel_data: GroupChannel = group_channel.read_data_with_borsh()?;
+        let group_channel_key = create_index_with_seed(
+            program_id,
+            GroupChannel::SEED,
+            server_group.key,
+            group_channel_data.index,
+        )?;
+
+        let group_channel_last_key = create_index_with_seed(
+            program_id,
+            GroupChannel::SEED,
+            server_group.key,
+            group_state.channels.error_decrement()?,
+        )?;
+
+        if group_channel_key == *group_channel.key
+            && group_channel_last_key == *group_channel_last.key
+        {
+            swap_accounts::<GroupChannel>(group_channel, group_channel_last, signers)?;
+
+            group_state.channels = group_state.channels.error_decrement()?;
+            group_state.serialize_const(&mut group_data)?;
+
+            Ok(())
+        } else {
+            Err(Error::InvalidDerivedGroupChannelAddress.into())
+        }
+    }
+
+    fn add_channel_to_group<'a>(
+        program_id: &Pubkey,
+        server: &AccountInfo<'a>,
+        dweller_administrator: &AccountInfo<'a>,
+        server_administrator: &AccountInfo<'a>,
+        server_channel: &AccountInfo<'a>,
+        server_group: &AccountInfo<'a>,
+        group_channel: &AccountInfo<'a>,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        require_admin(
+            program_id,
+            dweller_administrator,
+            server,
+            server_administrator,
+            signers,
+        )?;
+        append_audit_log(
+            program_id,
+            server,
+            signers,
+            dweller_administrator.key,
+            AuditAction::AddChannelToGroup,
+            server_channel.key,
+        )?;
+
+        let (mut server_group_data, mut server_group_state) =
+            server_group.read_data_with_borsh_mut::<ServerGroup>()?;
+
+        let group_channel_key = create_index_with_seed(
+            program_id,
+            GroupChannel::SEED,
+            server_group.key,
+            server_group_state.channels,
+        )?;
+
+        if group_channel_key == *group_channel.key {
+            create_list_entry_if_missing::<GroupChannel>(
+                program_id,
+                GroupChannel::SEED,
+                server_group,
+                server_group_state.channels,
+                group_channel,
+                signers,
+            )?;
+            let (mut group_channel_data, group_channel_state) =
+                group_channel.read_data_with_borsh_mut::<GroupChannel>()?;
+
+            if group_channel_state.version == StateVersion::Uninitialized {
+                if server_group_state.version == StateVersion::V2 {
+                    let mut server_group_state =
+                        ServerGroupV2::deserialize_const(&server_group_data)?;
+                    GroupChannelV2 {
+                        version: StateVersion::V2,
+                        container: *server_group.key,
+                        index: server_group_state.channels,
+                        channel: *server_channel.key,
+                        position: server_group_state.next_position,
+                    }
+                    .serialize_const(&mut group_channel_data)?;
+
+                    server_group_state.next_position =
+                        server_group_state.next_position.error_increment()?;
+                    server_group_state.channels = server_group_state.channels.error_increment()?;
+                    server_group_state.serialize_const(&mut server_group_data)?;
+                } else {
+                    GroupChannel {
+                        version: StateVersion::V1,
+                        container: *server_group.key,
+                        index: server_group_state.channels,
+                        channel: *server_channel.key,
+                    }
+                    .serialize_const(&mut group_channel_data)?;
+
+                    server_group_state.channels = server_group_state.channels.error_increment()?;
+                    server_group_state.serialize_const(&mut server_group_data)?;
+                }
+
+                Ok(())
+            } else {
+                Err(ProgramError::AccountAlreadyInitialized)
+            }
+        } else {
+            Err(Error::InvalidDerivedServerChannelAddress.into())
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn leave_server<'a>(
+        program_id: &Pubkey,
+        server: &AccountInfo<'a>,
+        server_member: &AccountInfo<'a>,
+        server_member_last: &AccountInfo<'a>,
+        dweller: &AccountInfo<'a>,
+        dweller_server: &AccountInfo<'a>,
+        dweller_server_last: &AccountInfo<'a>,
+        member_marker: &AccountInfo<'a>,
+        administrator_marker: &AccountInfo<'a>,
+        server_administrator: Option<(&AccountInfo<'a>, &AccountInfo<'a>)>,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        if is_dweller_authority(program_id, dweller, signers)? {
+            let (administrator_marker_key, _) = DwellerMarker::find_address(
+                program_id,
+                ServerAdministrator::SEED,
+                server.key,
+                dweller.key,
+            );
+            if administrator_marker_key != *administrator_marker.key {
+                return Err(Error::InvalidDwellerMarkerAddress.into());
+            }
+            // administrators added before markers are not detected
+            if administrator_marker.owner == program_id && server_administrator.is_none() {
+                return Err(Error::AdministratorEntryRequired.into());
+            }
+            close_dweller_marker(
+                program_id,
+                ServerMember::SEED,
+                server.key,
+                dweller.key,
+                member_marker,
+                signers,
+            )?;
+            remove_dweller_server(
+                program_id,
+                dweller,
+                dweller_server,
+                dweller_server_last,
+                signers,
+            )?;
+            remove_server_member(
+                program_id,
+                server,
+                server_member,
+                server_member_last,
+                signers,
+            )?;
+            if let Some((server_administrator, server_administrator_last)) = server_administrator {
+                let server_administrator_state =
+                    server_administrator.read_data_with_borsh::<ServerAdministrator>()?;
+                server_administrator_state.is_initialized()?;
+                if server_administrator_state.dweller != *dweller.key {
+                    return Err(Error::InvalidDerivedServerAdministratorAddress.into());
+                }
+                close_dweller_marker(
+                    program_id,
+                    ServerAdministrator::SEED,
+                    server.key,
+                    dweller.key,
+                    administrator_marker,
+                    signers,
+                )?;
+                remove_server_administrator(
+                    program_id,
+                    server,
+                    server_administrator,
+                    server_administrator_last,
+                    signers,
+                )?;
+            }
+            Ok(())
+        } else {
+            Err(ProgramError::MissingRequiredSignature)
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn join_server<'a>(
+        program_id: &Pubkey,
+        server: &AccountInfo<'a>,
+        server_member: &AccountInfo<'a>,
+        server_member_status: &AccountInfo<'a>,
+        dweller: &AccountInfo<'a>,
+        dweller_server: &AccountInfo<'a>,
+        server_member_status_last: &AccountInfo<'a>,
+        member_marker: &AccountInfo<'a>,
+        payer: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        if is_dweller_authority(program_id, dweller, signers)? {
+            create_dweller_marker(
+                program_id,
+                ServerMember::SEED,
+                server,
+                dweller,
+                member_marker,
+                payer,
+                system_program,
+                Error::AlreadyMember,
+            )?;
+            if join_policy(server)? != JoinPolicy::Public as u8 {
+                let server_member_status_state: ServerMemberStatus =
+                    server_member_status.read_data_with_borsh()?;
+                if server_member_status_state.dweller != *dweller.key
+                    || server_member_status_state.container != *server.key
+                {
+                    return Err(Error::InvalidDerivedServerMemberStatusAddress.into());
+                }
+
+                remove_server_member_status(
+                    program_id,
+                    server,
+                    server_member_status,
+                    server_member_status_last,
+                    signers,
+                )?;
+            }
+
+            add_server_member(
+                program_id,
+                server,
+                server_member,
+                dweller,
+                dweller_server,
+                signers,
+            )?;
+
+            if let Some(join_fee) = join_fee(server)? {
+                let authority = dweller_authority(program_id, dweller)?;
+                let authority = signers
+                    .iter()
+                    .find(|x| *x.key == authority)
+                    .ok_or(ProgramError::MissingRequiredSignature)?;
+                pay_join_fee(program_id, server, authority, &join_fee, signers)?;
+                if join_fee.period != 0 {
+                    extend_membership(server_member, &join_fee)?;
+                }
+            }
+            Ok(())
+        } else {
+            Err(ProgramError::MissingRequiredSignature)
+        }
+    }
+
+    fn set_join_policy<'a>(
+        program_id: &Pubkey,
+        dweller_administrator: &AccountInfo<'a>,
+        server_administrator: &AccountInfo<'a>,
+        server: &AccountInfo<'a>,
+        input: &SetJoinPolicyInput,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        require_admin(
+            program_id,
+            dweller_administrator,
+            server,
+            server_administrator,
+            signers,
+        )?;
+        append_audit_log(
+            program_id,
+            server,
+            signers,
+            dweller_administrator.key,
+            AuditAction::SetJoinPolicy,
+            server.key,
+        )?;
+        if server.read_data_with_borsh::<Server>()?.version == StateVersion::V1 {
+            return Err(Error::UnsupportedStateVersion.into());
+        }
+        let (mut server_data, mut server_state) = server.read_data_with_borsh_mut::<ServerV2>()?;
+        server_state.join_policy = input.join_policy.clone();
+        server_state.serialize_const(&mut server_data)?;
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn request_join_server<'a>(
+        program_id: &Pubkey,
+        server: &AccountInfo<'a>,
+        dweller: &AccountInfo<'a>,
+        join_request: &AccountInfo<'a>,
+        member_marker: &AccountInfo<'a>,
+        payer: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        signers: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        if is_dweller_authority(program_id, dweller, signers)? {
+            if join_policy(server)? != JoinPolicy::ApprovalRequired as u8 {
+                return Err(Error::JoinRequestsNotAccepted.into());
+            }
+            create_dweller_marker(
+                program_id,
+                ServerMember::SEED,
+                server,
+                dweller,
+                member_marker,
+                payer,
+                system_program,
+                Error::JoinRequestPending,
+            )?;
+            let mut server_state = server.read_data_with_zero_copy_mut::<ServerV2Pod>()?;
+            let join_requests = server_state.join_requests.into();
+
+            let join_request_key =
+                create_index_with_seed(program_id, JoinRequest::SEED, server.key, join_requests)?;
+
+            if join_request_key == *join_request.key {
+                create_list_entry_if_missing::<JoinRequest>(
+                    program_id,
+                    JoinRequest::SEED,
+                    server,
+                    join_requests,
+                    join_request,
+                    signers,
+                )?;
+                let (mut join_request_data, mut join_request_state) =
+                    join_request.read_data_with_borsh_mut::<JoinRequest>()?;
+                if join_request_state.version == StateVersion::Uninitialized {
+                    join_request_state.version = StateVersion::V1;
+                    join_request_state.container = *server.key;
+                    join_request_state.index = join_requests;
+                    join_request_state.dweller = *dweller.key;
+                    join_request_state.serialize_const(&mut join_request_data)?;
+
+                    server_state.join_requests = join_requests.error_increment()?.into();
+
+                    Ok(())
+                } else {
+                    Err(ProgramError::AccountAlreadyInitialized)
+                }
+            } else {
+                Err(Error::InvalidDerivedJoinRequestAddress.into())
+            }
         } else {
-            Err(Error::InvalidDerivedServerChannelAddress.into())
+            Err(ProgramError::MissingRequiredSignature)
         }
     }
 
     #[allow(clippy::too_many_arguments)]
-    fn remove_channel_from_group<'a>(
+    fn approve_join_request<'a>(
         program_id: &Pubkey,
-        server: &AccountInfo<'a>,
         dweller_administrator: &AccountInfo<'a>,
         server_administrator: &AccountInfo<'a>,
-        server_group: &AccountInfo<'a>,
-        group_channel: &AccountInfo<'a>,
-        group_channel_last: &AccountInfo<'a>,
+        server: &AccountInfo<'a>,
+        join_request: &AccountInfo<'a>,
+        join_request_last: &AccountInfo<'a>,
+        server_member: &AccountInfo<'a>,
+        dweller: &AccountInfo<'a>,
+        dweller_server: &AccountInfo<'a>,
+        member_marker: &AccountInfo<'a>,
+        signers: &[AccountInfo<'a>],
     ) -> ProgramResult {
         require_admin(
             program_id,
             dweller_administrator,
             server,
             server_administrator,
+            signers,
         )?;
-        let (mut group_data, mut group_state) =
-            server_group.read_data_with_borsh_mut::<ServerGroup>()?;
-
-        let group_channel_data: GroupChannel = group_channel.read_data_with_borsh()?;
-        let group_channel_key = create_index_with_seed(
+        if join_policy(server)? != JoinPolicy::ApprovalRequired as u8 {
+            return Err(Error::JoinRequestsNotAccepted.into());
+        }
+        append_audit_log(
             program_id,
-            GroupChannel::SEED,
-            server_group.key,
-            group_channel_data.index,
+            server,
+            signers,
+            dweller_administrator.key,
+            AuditAction::ApproveJoinRequest,
+            dweller.key,
         )?;
 
-        let group_channel_last_key = create_index_with_seed(
-            program_id,
-            GroupChannel::SEED,
-            server_group.key,
-            group_state.channels.error_decrement()?,
-        )?;
+        let join_request_state = join_request.read_data_with_borsh::<JoinRequest>()?;
+        if join_request_state.dweller != *dweller.key {
+            return Err(Error::InvalidDerivedJoinRequestAddress.into());
+        }
 
-        if group_channel_key == *group_channel.key
-            && group_channel_last_key == *group_channel_last.key
+        // marker of request stays as marker of membership
+        if DwellerMarker::find_address(program_id, ServerMember::SEED, server.key, dweller.key).0
+            != *member_marker.key
         {
-            swap_accounts::<GroupChannel>(group_channel, group_channel_last)?;
-
-            group_state.channels = group_state.channels.error_decrement()?;
-            group_state.serialize_const(&mut group_data)?;
-
-            Ok(())
-        } else {
-            Err(Error::InvalidDerivedGroupChannelAddress.into())
+            return Err(Error::InvalidDwellerMarkerAddress.into());
         }
+        remove_join_request(program_id, server, join_request, join_request_last, signers)?;
+        add_server_member(
+            program_id,
+            server,
+            server_member,
+            dweller,
+            dweller_server,
+            signers,
+        )
     }
 
-    fn add_channel_to_group<'a>(
+    #[allow(clippy::too_many_arguments)]
+    fn reject_join_request<'a>(
         program_id: &Pubkey,
-        server: &AccountInfo<'a>,
         dweller_administrator: &AccountInfo<'a>,
         server_administrator: &AccountInfo<'a>,
-        server_channel: &AccountInfo<'a>,
-        server_group: &AccountInfo<'a>,
-        group_channel: &AccountInfo<'a>,
+        server: &AccountInfo<'a>,
+        join_request: &AccountInfo<'a>,
+        join_request_last: &AccountInfo<'a>,
+        member_marker: &AccountInfo<'a>,
+        signers: &[AccountInfo<'a>],
     ) -> ProgramResult {
         require_admin(
             program_id,
             dweller_administrator,
             server,
             server_administrator,
+            signers,
+        )?;
+        let dweller = join_request.read_data_with_borsh::<JoinRequest>()?.dweller;
+        append_audit_log(
+            program_id,
+            server,
+            signers,
+            dweller_administrator.key,
+            AuditAction::RejectJoinRequest,
+            &dweller,
         )?;
 
-        let (mut server_group_data, mut server_group_state) =
-            server_group.read_data_with_borsh_mut::<ServerGroup>()?;
-
-        let group_channel_key = create_index_with_seed(
+        close_dweller_marker(
             program_id,
-            GroupChannel::SEED,
-            server_group.key,
-            server_group_state.channels,
+            ServerMember::SEED,
+            server.key,
+            &dweller,
+            member_marker,
+            signers,
         )?;
+        remove_join_request(program_id, server, join_request, join_request_last, signers)
+    }
+}
 
-        if group_channel_key == *group_channel.key {
-            let (mut group_channel_data, mut group_channel_state) =
-                group_channel.read_data_with_borsh_mut::<GroupChannel>()?;
+/// [DwellerV2::authority] of dweller, or dweller itself if not rotated.
+/// Accounts not owned by program are their own authority.
+fn dweller_authority(program_id: &Pubkey, dweller: &AccountInfo) -> Result<Pubkey, ProgramError> {
+    if dweller.owner == program_id
+        && matches!(
+            dweller.read_data_with_borsh::<Dweller>()?.version,
+            StateVersion::V2 | StateVersion::V3 | StateVersion::V4
+        )
+    {
+        Ok(dweller.read_data_with_borsh::<DwellerV2>()?.authority)
+    } else {
+        Ok(*dweller.key)
+    }
+}
 
-            if group_channel_state.version == StateVersion::Uninitialized {
-                group_channel_state.version = StateVersion::V1;
-                group_channel_state.container = *server_group.key;
-                group_channel_state.index = server_group_state.channels;
-                group_channel_state.channel = *server_channel.key;
-                group_channel_state.serialize_const(&mut group_channel_data)?;
+/// True if [dweller_authority] of dweller is among signers.
+fn is_dweller_authority(
+    program_id: &Pubkey,
+    dweller: &AccountInfo,
+    signers: &[AccountInfo],
+) -> Result<bool, ProgramError> {
+    let authority = dweller_authority(program_id, dweller)?;
+    Ok(signers.iter().any(|x| x.is_signer && *x.key == authority))
+}
+
+/// [ServerV3::join_requirement], older servers have none
+fn join_requirement(server: &AccountInfo) -> Result<JoinRequirement, ProgramError> {
+    match server.read_data_with_borsh::<Server>()?.version {
+        StateVersion::V3 | StateVersion::V4 => {
+            Ok(server.read_data_with_borsh::<ServerV3>()?.join_requirement)
+        }
+        _ => Ok(JoinRequirement::None),
+    }
+}
+
+/// [ServerV4::join_fee], older servers have none
+fn join_fee(server: &AccountInfo) -> Result<Option<JoinFee>, ProgramError> {
+    if server.read_data_with_borsh::<Server>()?.version == StateVersion::V4 {
+        Ok(server.read_data_with_borsh::<ServerV4>()?.join_fee)
+    } else {
+        Ok(None)
+    }
+}
+
+/// [ServerMemberV4::paid_until], older members never paid
+fn member_paid_until(server_member: &AccountInfo) -> Result<i64, ProgramError> {
+    if server_member
+        .read_data_with_borsh::<ServerMember>()?
+        .version
+        == StateVersion::V4
+    {
+        Ok(server_member
+            .read_data_with_borsh::<ServerMemberV4>()?
+            .paid_until)
+    } else {
+        Ok(0)
+    }
+}
+
+/// Transfers [JoinFee::amount] from token account of signing `authority` into server treasury.
+/// Source, treasury and token program are looked up in `accounts`.
+fn pay_join_fee<'a>(
+    program_id: &Pubkey,
+    server: &AccountInfo<'a>,
+    authority: &AccountInfo<'a>,
+    join_fee: &JoinFee,
+    accounts: &[AccountInfo<'a>],
+) -> ProgramResult {
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let (treasury_key, _) = JoinFee::find_treasury_address(program_id, server.key, &join_fee.mint);
+    let treasury = accounts
+        .iter()
+        .find(|x| *x.key == treasury_key)
+        .ok_or(Error::InvalidTreasuryAddress)?;
+    let token_program = accounts
+        .iter()
+        .find(|x| *x.key == spl_token::id())
+        .ok_or(ProgramError::IncorrectProgramId)?;
+    let source = accounts
+        .iter()
+        .find(|x| {
+            *x.owner == spl_token::id()
+                && *x.key != treasury_key
+                && spl_token::state::Account::unpack(&x.data.borrow())
+                    .map(|x| x.mint == join_fee.mint && x.owner == *authority.key)
+                    .unwrap_or(false)
+        })
+        .ok_or(Error::JoinFeeNotPaid)?;
+
+    invoke(
+        &spl_token::instruction::transfer(
+            &spl_token::id(),
+            source.key,
+            treasury.key,
+            authority.key,
+            &[],
+            join_fee.amount,
+        )?,
+        &[
+            source.clone(),
+            treasury.clone(),
+            authority.clone(),
+            token_program.clone(),
+        ],
+    )
+}
+
+/// Moves [ServerMemberV4::paid_until] one [JoinFee::period] past now or its current value, whichever is later
+fn extend_membership(server_member: &AccountInfo, join_fee: &JoinFee) -> ProgramResult {
+    if server_member
+        .read_data_with_borsh::<ServerMember>()?
+        .version
+        != StateVersion::V4
+    {
+        return Err(Error::UnsupportedStateVersion.into());
+    }
+    let (mut member_data, mut member_state) =
+        server_member.read_data_with_borsh_mut::<ServerMemberV4>()?;
+    member_state.paid_until = member_state
+        .paid_until
+        .max(Clock::get()?.unix_timestamp)
+        .checked_add(join_fee.period)
+        .ok_or(Error::Overflow)?;
+    member_state.serialize_const(&mut member_data)?;
+    Ok(())
+}
+
+/// True if token account is owned by `owner` and satisfies requirement
+fn meets_join_requirement(
+    requirement: &JoinRequirement,
+    owner: &Pubkey,
+    token: &AccountInfo,
+) -> bool {
+    match requirement {
+        JoinRequirement::None => true,
+        JoinRequirement::TokenBalance { mint, amount } => {
+            *token.owner == spl_token::id()
+                && spl_token::state::Account::unpack(&token.data.borrow())
+                    .map(|x| x.mint == *mint && x.owner == *owner && x.amount >= *amount)
+                    .unwrap_or(false)
+        }
+        JoinRequirement::NftOwnership { mint } => {
+            *token.owner == spl_nft_erc_721::id()
+                && token
+                    .read_data_with_borsh::<spl_nft_erc_721::state::Token>()
+                    .map(|x| {
+                        x.version == spl_nft_erc_721::state::TokenStatus::Initialized
+                            && x.mint == *mint
+                            && x.owner == *owner
+                    })
+                    .unwrap_or(false)
+        }
+    }
+}
+
+fn require_owner<'a>(
+    program_id: &Pubkey,
+    server_state: &Server,
+    owner: &AccountInfo<'a>,
+    signers: &[AccountInfo<'a>],
+) -> ProgramResult {
+    if is_dweller_authority(program_id, owner, signers)? {
+        if server_state.owner == *owner.key {
+            Ok(())
+        } else {
+            Err(Error::ProvidedDwellerIsNotTheOwnerOfTheServer.into())
+        }
+    } else {
+        Err(ProgramError::MissingRequiredSignature)
+    }
+}
+
+fn require_admin(
+    program_id: &Pubkey,
+    dweller_administrator: &AccountInfo,
+    server: &AccountInfo,
+    server_administrator: &AccountInfo,
+    signers: &[AccountInfo],
+) -> ProgramResult {
+    if server_administrator.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if is_dweller_authority(program_id, dweller_administrator, signers)? {
+        let server_administrator_state: ServerAdministrator =
+            server_administrator.read_data_with_borsh()?;
+        server_administrator_state.is_initialized()?;
+
+        if server_administrator_state.container == *server.key {
+            if server_administrator_state.dweller == *dweller_administrator.key {
+                require_server_member(program_id, server, dweller_administrator, signers)
+            } else {
+                Err(Error::InvalidDerivedServerAdministratorAddress.into())
+            }
+        } else {
+            Err(Error::InvalidDerivedAddressWrongServer.into())
+        }
+    } else {
+        Err(ProgramError::MissingRequiredSignature)
+    }
+}
+
+/// finds server_member of dweller among accounts, vacated entries past [Server::members] do not count
+fn require_server_member(
+    program_id: &Pubkey,
+    server: &AccountInfo,
+    dweller: &AccountInfo,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let members = server.read_data_with_borsh::<Server>()?.members;
+    for account in accounts {
+        if let Some(state) = server_member_entry(program_id, server, account)? {
+            if state.dweller == *dweller.key && state.index < members {
+                return Ok(());
+            }
+        }
+    }
+
+    Err(Error::AdministratorIsNotMember.into())
+}
+
+/// requires account to be initialized entry of server list derived with seed
+fn require_list_entry(
+    program_id: &Pubkey,
+    seed: &str,
+    server: &AccountInfo,
+    account: &AccountInfo,
+) -> ProgramResult {
+    let state = account.read_data_with_borsh::<ListEntry>()?;
+    if account.owner == program_id
+        && state.version != StateVersion::Uninitialized
+        && state.container == *server.key
+        && create_index_with_seed(program_id, seed, server.key, state.index)? == *account.key
+    {
+        Ok(())
+    } else {
+        Err(ProgramError::InvalidSeeds)
+    }
+}
+
+/// reads account as initialized [ServerMember] of server if it is one
+fn server_member_entry(
+    program_id: &Pubkey,
+    server: &AccountInfo,
+    account: &AccountInfo,
+) -> Result<Option<ServerMember>, ProgramError> {
+    if account.owner != program_id {
+        return Ok(None);
+    }
+    match account.read_data_with_borsh::<ServerMember>() {
+        Ok(state)
+            if state.version != StateVersion::Uninitialized
+                && state.container == *server.key
+                && create_index_with_seed(
+                    program_id,
+                    ServerMember::SEED,
+                    server.key,
+                    state.index,
+                )? == *account.key =>
+        {
+            Ok(Some(state))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// resizes dweller to packed length of V2 or V3 state and writes it
+fn write_dweller<'a>(
+    dweller: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    state: &impl BorshSerialize,
+) -> ProgramResult {
+    if system_program::id() != *system_program.key {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let data = state.try_to_vec()?;
+    resize_rent_exempt_account(dweller, payer, system_program, &Rent::get()?, data.len())?;
+    dweller.try_borrow_mut_data()?.copy_from_slice(&data);
+
+    Ok(())
+}
+
+/// [DwellerV3::handle], older dwellers have none
+fn dweller_handle(dweller: &AccountInfo) -> Result<Pubkey, ProgramError> {
+    match dweller.read_data_with_borsh::<Dweller>()?.version {
+        StateVersion::V3 | StateVersion::V4 => {
+            Ok(dweller.read_data_with_borsh::<DwellerV3>()?.handle)
+        }
+        _ => Ok(Pubkey::default()),
+    }
+}
+
+/// [DwellerV4::nft_avatar], older dwellers have none
+fn dweller_nft_avatar(dweller: &AccountInfo) -> Result<Pubkey, ProgramError> {
+    if dweller.read_data_with_borsh::<Dweller>()?.version == StateVersion::V4 {
+        Ok(dweller.read_data_with_borsh::<DwellerV4>()?.nft_avatar)
+    } else {
+        Ok(Pubkey::default())
+    }
+}
+
+/// True if `token` is initialized `spl_nft_erc_721` token owned by `owner`
+fn is_nft_owner(token: &AccountInfo, owner: &Pubkey) -> bool {
+    *token.owner == spl_nft_erc_721::id()
+        && token
+            .read_data_with_borsh::<spl_nft_erc_721::state::Token>()
+            .map(|x| {
+                x.version == spl_nft_erc_721::state::TokenStatus::Initialized && x.owner == *owner
+            })
+            .unwrap_or(false)
+}
+
+/// sets [DwellerV3::handle], migrates [DwellerV2] to V3
+fn set_dweller_handle<'a>(
+    dweller: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    handle: &Pubkey,
+) -> ProgramResult {
+    match dweller.read_data_with_borsh::<Dweller>()?.version {
+        // handle is at same offset in V4
+        StateVersion::V3 | StateVersion::V4 => {
+            let (mut data, mut state) = dweller.read_data_with_borsh_mut::<DwellerV3>()?;
+            state.handle = *handle;
+            state.serialize_const(&mut data)?;
+            Ok(())
+        }
+        StateVersion::V2 => {
+            let state = dweller.read_data_with_borsh::<DwellerV2>()?;
+            write_dweller(
+                dweller,
+                payer,
+                system_program,
+                &DwellerV3::from_v2(state, *handle),
+            )
+        }
+        StateVersion::V1 => Err(Error::UnsupportedStateVersion.into()),
+        StateVersion::Uninitialized => Err(ProgramError::UninitializedAccount),
+    }
+}
+
+/// sets [DwellerV4::nft_avatar], migrates [DwellerV2] and V3 to V4
+fn set_dweller_nft_avatar<'a>(
+    dweller: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    nft_avatar: &Pubkey,
+) -> ProgramResult {
+    match dweller.read_data_with_borsh::<Dweller>()?.version {
+        StateVersion::V4 => {
+            let (mut data, mut state) = dweller.read_data_with_borsh_mut::<DwellerV4>()?;
+            state.nft_avatar = *nft_avatar;
+            state.serialize_const(&mut data)?;
+            Ok(())
+        }
+        StateVersion::V2 | StateVersion::V3 => {
+            let handle = dweller_handle(dweller)?;
+            let state = DwellerV3::from_v2(dweller.read_data_with_borsh::<DwellerV2>()?, handle);
+            write_dweller(
+                dweller,
+                payer,
+                system_program,
+                &DwellerV4::from_v3(state, *nft_avatar),
+            )
+        }
+        StateVersion::V1 => Err(Error::UnsupportedStateVersion.into()),
+        StateVersion::Uninitialized => Err(ProgramError::UninitializedAccount),
+    }
+}
+
+/// writes [ServerMemberV4] with empty nickname, or older layout if account is too small
+fn write_new_server_member(
+    data: &mut [u8],
+    server: &Pubkey,
+    index: u64,
+    dweller: &Pubkey,
+    token: &Pubkey,
+) -> ProgramResult {
+    let member = |version| ServerMember {
+        version,
+        container: *server,
+        index,
+        dweller: *dweller,
+    };
+    let member_v2 = |version| ServerMemberV2 {
+        base: member(version),
+        nickname: [0; 32],
+    };
+    let member_v3 = |version| ServerMemberV3 {
+        base: member_v2(version),
+        token: *token,
+    };
+    if data.len() >= ServerMemberV4::LEN as usize {
+        ServerMemberV4 {
+            base: member_v3(StateVersion::V4),
+            paid_until: 0,
+        }
+        .serialize_const(data)?;
+    } else if data.len() >= ServerMemberV3::LEN as usize {
+        member_v3(StateVersion::V3).serialize_const(data)?;
+    } else if *token != Pubkey::default() {
+        return Err(Error::UnsupportedStateVersion.into());
+    } else if data.len() >= ServerMemberV2::LEN as usize {
+        member_v2(StateVersion::V2).serialize_const(data)?;
+    } else {
+        member(StateVersion::V1).serialize_const(data)?;
+    }
+    Ok(())
+}
+
+fn require_server_directory(program_id: &Pubkey, server_directory: &AccountInfo) -> ProgramResult {
+    let server_directory_key =
+        create_index_with_seed(program_id, ServerDirectory::SEED, program_id, 0)?;
+    if server_directory_key == *server_directory.key && server_directory.owner == program_id {
+        Ok(())
+    } else {
+        Err(Error::InvalidDerivedServerDirectoryAddress.into())
+    }
+}
+
+fn require_group(
+    program_id: &Pubkey,
+    server: &AccountInfo,
+    server_group: &AccountInfo,
+) -> Result<ServerGroup, ProgramError> {
+    let group_state = server_group.read_data_with_borsh::<ServerGroup>()?;
+    if group_state.version == StateVersion::Uninitialized {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    let server_group_key =
+        create_index_with_seed(program_id, ServerGroup::SEED, server.key, group_state.index)?;
+
+    if server_group_key == *server_group.key && group_state.container == *server.key {
+        Ok(group_state)
+    } else {
+        Err(Error::InvalidDerivedServerGroupAddress.into())
+    }
+}
+
+/// number of [ChannelOverwrite] of channel, V1 channels have none
+fn channel_overwrites_count(server_channel: &AccountInfo) -> Result<u64, ProgramError> {
+    let channel_state = server_channel.read_data_with_borsh::<ServerChannel>()?;
+    match channel_state.version {
+        StateVersion::V2 => Ok(server_channel
+            .read_data_with_borsh::<ServerChannelV2>()?
+            .overwrites),
+        _ => Ok(0),
+    }
+}
+
+/// Trailing list accounts without rotated dweller authority signers, [ServerAuditLog] of server,
+/// [ServerMember] of administrator and rent receivers not owned by program.
+fn trailing_accounts<'b, 'a>(
+    program_id: &Pubkey,
+    server: &AccountInfo,
+    accounts: &'b [AccountInfo<'a>],
+) -> Result<Vec<&'b AccountInfo<'a>>, ProgramError> {
+    let server_audit_log_key =
+        create_index_with_seed(program_id, ServerAuditLog::SEED, server.key, 0)?;
+    let mut trailing = Vec::with_capacity(accounts.len());
+    for account in accounts {
+        if !account.is_signer
+            && account.owner == program_id
+            && *account.key != server_audit_log_key
+            && server_member_entry(program_id, server, account)?.is_none()
+        {
+            trailing.push(account);
+        }
+    }
+    Ok(trailing)
+}
+
+/// Appends entry to [ServerAuditLog] of server, which must be created and among accounts,
+/// initializing it on first use.
+/// Privileged handlers call it after authorization, so failed actions are not logged.
+fn append_audit_log(
+    program_id: &Pubkey,
+    server: &AccountInfo,
+    accounts: &[AccountInfo],
+    actor: &Pubkey,
+    action: AuditAction,
+    target: &Pubkey,
+) -> ProgramResult {
+    let server_audit_log_key =
+        create_index_with_seed(program_id, ServerAuditLog::SEED, server.key, 0)?;
+    let server_audit_log = match accounts.iter().find(|x| *x.key == server_audit_log_key) {
+        Some(x) if x.owner == program_id => x,
+        _ => return Err(Error::InvalidDerivedServerAuditLogAddress.into()),
+    };
+
+    let (mut log_data, mut log_state) =
+        server_audit_log.read_data_with_borsh_mut::<ServerAuditLog>()?;
+    if log_state.version == StateVersion::Uninitialized {
+        log_state.version = StateVersion::V1;
+        log_state.container = *server.key;
+    }
+
+    let offset = log_state.next_offset();
+    AuditEntry {
+        actor: *actor,
+        action,
+        target: *target,
+        unix_timestamp: Clock::get()?.unix_timestamp,
+    }
+    .serialize_const(&mut log_data[offset..])?;
+
+    log_state.appended = log_state.appended.error_increment()?;
+    log_state.serialize_const(&mut log_data)?;
+    Ok(())
+}
+
+fn require_channel(
+    program_id: &Pubkey,
+    server: &AccountInfo,
+    server_channel: &AccountInfo,
+) -> ProgramResult {
+    let channel_state = server_channel.read_data_with_borsh::<ServerChannel>()?;
+    if channel_state.version == StateVersion::Uninitialized {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    let server_channel_key = create_index_with_seed(
+        program_id,
+        ServerChannel::SEED,
+        server.key,
+        channel_state.index,
+    )?;
+
+    if server_channel_key == *server_channel.key && channel_state.container == *server.key {
+        Ok(())
+    } else {
+        Err(Error::InvalidDerivedServerChannelAddress.into())
+    }
+}
 
-                server_group_state.channels = server_group_state.channels.error_increment()?;
-                server_group_state.serialize_const(&mut server_group_data)?;
+/// reads history of channel, initializing it on first use
+fn read_channel_history<'a, 'b>(
+    program_id: &Pubkey,
+    server_channel: &AccountInfo<'a>,
+    channel_history: &'b AccountInfo<'a>,
+) -> Result<(std::cell::RefMut<'b, &'a mut [u8]>, ChannelHistory), ProgramError> {
+    let channel_history_key =
+        create_index_with_seed(program_id, ChannelHistory::SEED, server_channel.key, 0)?;
+    if channel_history_key != *channel_history.key || channel_history.owner != program_id {
+        return Err(Error::InvalidDerivedChannelHistoryAddress.into());
+    }
 
-                Ok(())
-            } else {
-                Err(ProgramError::AccountAlreadyInitialized)
-            }
-        } else {
-            Err(Error::InvalidDerivedServerChannelAddress.into())
-        }
+    let (history_data, mut history_state) =
+        channel_history.read_data_with_borsh_mut::<ChannelHistory>()?;
+    if history_state.version == StateVersion::Uninitialized {
+        history_state.version = StateVersion::V1;
+        history_state.container = *server_channel.key;
     }
+    Ok((history_data, history_state))
+}
 
-    fn leave_server<'a>(
-        program_id: &Pubkey,
-        server: &AccountInfo<'a>,
-        server_member: &AccountInfo<'a>,
-        server_member_last: &AccountInfo<'a>,
-        dweller: &AccountInfo<'a>,
-        dweller_server: &AccountInfo<'a>,
-        dweller_server_last: &AccountInfo<'a>,
-    ) -> ProgramResult {
-        if dweller.is_signer {
-            remove_dweller_server(program_id, dweller, dweller_server, dweller_server_last)?;
-            remove_server_member(program_id, server, server_member, server_member_last)?;
-            Ok(())
-        } else {
-            Err(ProgramError::MissingRequiredSignature)
-        }
+/// checks handle is claimed by dweller and dweller points back to it
+fn require_dweller_handle(
+    program_id: &Pubkey,
+    dweller: &AccountInfo,
+    dweller_handle_account: &AccountInfo,
+) -> ProgramResult {
+    if dweller_handle_account.owner != program_id {
+        return Err(Error::InvalidHandleAddress.into());
+    }
+    let handle_state = dweller_handle_account.read_data_with_borsh::<DwellerHandle>()?;
+    let (handle_key, _) = DwellerHandle::find_normalized_address(program_id, &handle_state.handle);
+    if handle_key != *dweller_handle_account.key
+        || handle_state.dweller != *dweller.key
+        || dweller_handle(dweller)? != *dweller_handle_account.key
+    {
+        return Err(Error::InvalidHandleAddress.into());
     }
+    Ok(())
+}
 
-    fn join_server<'a>(
-        program_id: &Pubkey,
-        server: &AccountInfo<'a>,
-        server_member: &AccountInfo<'a>,
-        server_member_status: &AccountInfo<'a>,
-        dweller: &AccountInfo<'a>,
-        dweller_server: &AccountInfo<'a>,
-    ) -> ProgramResult {
-        if dweller.is_signer {
-            let mut dweller_data = dweller.try_borrow_mut_data()?;
-            let mut dweller_state = Dweller::deserialize_const(&dweller_data)?;
+/// reads event counter of server, initializing it on first use
+fn read_server_events<'a, 'b>(
+    program_id: &Pubkey,
+    server: &AccountInfo<'a>,
+    server_events: &'b AccountInfo<'a>,
+) -> Result<(std::cell::RefMut<'b, &'a mut [u8]>, ServerEvents), ProgramError> {
+    let server_events_key = create_index_with_seed(program_id, ServerEvents::SEED, server.key, 0)?;
+    if server_events_key != *server_events.key || server_events.owner != program_id {
+        return Err(Error::InvalidDerivedServerEventAddress.into());
+    }
 
-            let dweller_server_key = create_index_with_seed(
-                program_id,
-                DwellerServer::SEED,
-                dweller.key,
-                dweller_state.servers,
-            )?;
+    let (events_data, mut events_state) =
+        server_events.read_data_with_borsh_mut::<ServerEvents>()?;
+    if events_state.version == StateVersion::Uninitialized {
+        events_state.version = StateVersion::V1;
+        events_state.container = *server.key;
+    }
+    Ok((events_data, events_state))
+}
 
-            if dweller_server_key == *dweller_server.key {
-                let (mut dweller_server_data, mut dweller_server_state) =
-                    dweller_server.read_data_with_borsh_mut::<DwellerServer>()?;
+fn read_server_event<'a, 'b>(
+    program_id: &Pubkey,
+    server: &AccountInfo<'a>,
+    server_event: &'b AccountInfo<'a>,
+) -> Result<(std::cell::RefMut<'b, &'a mut [u8]>, ServerEvent), ProgramError> {
+    let (event_data, event_state) = server_event.read_data_with_borsh_mut::<ServerEvent>()?;
+    if event_state.version == StateVersion::Uninitialized {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    let server_event_key =
+        create_index_with_seed(program_id, ServerEvent::SEED, server.key, event_state.index)?;
+    if server_event_key != *server_event.key
+        || event_state.container != *server.key
+        || server_event.owner != program_id
+    {
+        return Err(Error::InvalidDerivedServerEventAddress.into());
+    }
+    Ok((event_data, event_state))
+}
 
-                if dweller_server_state.version == StateVersion::Uninitialized {
-                    let server_member_status_state: ServerMemberStatus =
-                        server_member_status.read_data_with_borsh()?;
-                    if server_member.owner == program_id
-                        && server_member_status_state.dweller == *dweller.key
-                        && server_member_status_state.container == *server.key
-                    {
-                        let (mut server_data, mut server_state) =
-                            server.read_data_with_borsh_mut::<Server>()?;
-                        let (mut server_member_data, mut server_member_state) =
-                            server_member.read_data_with_borsh_mut::<ServerMember>()?;
+fn require_channel_v2(
+    program_id: &Pubkey,
+    server: &AccountInfo,
+    server_channel: &AccountInfo,
+) -> ProgramResult {
+    let channel_state = server_channel.read_data_with_borsh::<ServerChannel>()?;
+    let server_channel_key = create_index_with_seed(
+        program_id,
+        ServerChannel::SEED,
+        server.key,
+        channel_state.index,
+    )?;
 
-                        let server_member_key = create_index_with_seed(
-                            program_id,
-                            ServerMember::SEED,
-                            server.key,
-                            server_state.members,
-                        )?;
-
-                        if server_member_key == *server_member.key {
-                            if server_member_state.version == StateVersion::Uninitialized {
-                                server_member_state.version = StateVersion::V1;
-                                server_member_state.container = *server.key;
-                                server_member_state.index = server_state.members;
-                                server_member_state.dweller = *dweller.key;
-                                server_member_state.serialize_const(&mut server_member_data)?;
-
-                                dweller_server_state.container = *dweller.key;
-                                dweller_server_state.index = dweller_state.servers;
-                                dweller_server_state.version = StateVersion::V1;
-                                dweller_server_state.server = *server.key;
-                                dweller_server_state.serialize_const(&mut dweller_server_data)?;
-
-                                dweller_state.servers = dweller_state.servers.error_increment()?;
-                                dweller_state.serialize_const(&mut dweller_data)?;
-
-                                server_state.members = server_state.members.error_increment()?;
-                                server_state.serialize_const(&mut server_data)?;
-
-                                Ok(())
-                            } else {
-                                Err(ProgramError::AccountAlreadyInitialized)
-                            }
-                        } else {
-                            Err(Error::InvalidDerivedServerMemberAddress.into())
-                        }
-                    } else {
-                        Err(Error::InvalidDerivedServerMemberStatusAddress.into())
-                    }
-                } else {
-                    Err(ProgramError::AccountAlreadyInitialized)
-                }
-            } else {
-                Err(Error::InvalidDerivedDwellerServerAddress.into())
-            }
-        } else {
-            Err(ProgramError::MissingRequiredSignature)
-        }
+    if server_channel_key != *server_channel.key || channel_state.container != *server.key {
+        return Err(Error::InvalidDerivedServerChannelAddress.into());
     }
-}
 
-fn require_owner<'a>(server_state: &Server, owner: &AccountInfo<'a>) -> ProgramResult {
-    if owner.is_signer {
-        if server_state.owner == *owner.key {
-            Ok(())
-        } else {
-            Err(Error::ProvidedDwellerIsNotTheOwnerOfTheServer.into())
+    match channel_state.version {
+        StateVersion::V2 => Ok(()),
+        StateVersion::V1 | StateVersion::V3 | StateVersion::V4 => {
+            Err(Error::UnsupportedStateVersion.into())
         }
-    } else {
-        Err(ProgramError::MissingRequiredSignature)
+        StateVersion::Uninitialized => Err(ProgramError::UninitializedAccount),
     }
 }
 
-fn require_admin(
+/// Checks administrator has permission in channel, given all channel overwrites in index order.
+/// Returns the overwrites read.
+fn require_channel_permission(
     program_id: &Pubkey,
     dweller_administrator: &AccountInfo,
     server: &AccountInfo,
-    server_administrator: &AccountInfo,
-) -> ProgramResult {
-    if server_administrator.owner != program_id {
-        return Err(ProgramError::IncorrectProgramId);
+    server_channel: &AccountInfo,
+    channel_overwrites: &[AccountInfo],
+    permission: u64,
+) -> Result<Vec<ChannelOverwrite>, ProgramError> {
+    let channel_overwrites = trailing_accounts(program_id, server, channel_overwrites)?;
+    if channel_overwrites.len() as u64 != channel_overwrites_count(server_channel)? {
+        return Err(Error::InvalidDerivedChannelOverwriteAddress.into());
     }
 
-    if dweller_administrator.is_signer {
-        let server_administrator_state: ServerAdministrator =
-            server_administrator.read_data_with_borsh()?;
-        server_administrator_state.is_initialized()?;
+    let mut overwrites = Vec::with_capacity(channel_overwrites.len());
+    for (index, channel_overwrite) in channel_overwrites.into_iter().enumerate() {
+        let channel_overwrite_key = create_index_with_seed(
+            program_id,
+            ChannelOverwrite::SEED,
+            server_channel.key,
+            index as u64,
+        )?;
 
-        if server_administrator_state.container == *server.key {
-            if server_administrator_state.dweller == *dweller_administrator.key {
-                Ok(())
-            } else {
-                Err(Error::InvalidDerivedServerAdministratorAddress.into())
-            }
-        } else {
-            Err(Error::InvalidDerivedAddressWrongServer.into())
+        if channel_overwrite_key != *channel_overwrite.key {
+            return Err(Error::InvalidDerivedChannelOverwriteAddress.into());
         }
+
+        overwrites.push(channel_overwrite.read_data_with_borsh::<ChannelOverwrite>()?);
+    }
+
+    let server_state = server.read_data_with_borsh::<Server>()?;
+    let permissions = resolve_channel_permissions(
+        dweller_administrator.key,
+        server_state.owner == *dweller_administrator.key,
+        true,
+        &overwrites,
+    );
+
+    if permissions & permission == permission {
+        Ok(overwrites)
     } else {
-        Err(ProgramError::MissingRequiredSignature)
+        Err(Error::MissingChannelPermission.into())
+    }
+}
+
+fn validate_channel(type_id: u8, voice: &VoiceChannelConfig) -> Result<ChannelKind, ProgramError> {
+    let kind = ChannelKind::from_u8(type_id).ok_or(Error::InvalidChannelKind)?;
+    if voice.bitrate > VoiceChannelConfig::MAX_BITRATE
+        || (!kind.is_voice() && *voice != VoiceChannelConfig::default())
+    {
+        return Err(Error::InvalidVoiceChannelConfig.into());
     }
+    Ok(kind)
 }
 
 fn remove_server_member<'a>(
@@ -1266,8 +4825,10 @@ fn remove_server_member<'a>(
     server: &AccountInfo<'a>,
     server_member: &AccountInfo<'a>,
     server_member_last: &AccountInfo<'a>,
+    accounts: &[AccountInfo<'a>],
 ) -> ProgramResult {
-    let (mut server_data, mut server_state) = server.read_data_with_borsh_mut::<Server>()?;
+    let mut server_state = server.read_data_with_zero_copy_mut::<ServerPod>()?;
+    let members = u64::from(server_state.members).error_decrement()?;
 
     let server_member_data: GroupChannel = server_member.read_data_with_borsh()?;
     let server_member_key = create_index_with_seed(
@@ -1277,23 +4838,61 @@ fn remove_server_member<'a>(
         server_member_data.index,
     )?;
 
-    let server_member_last_key = create_index_with_seed(
+    let server_member_last_key =
+        create_index_with_seed(program_id, ServerMember::SEED, server.key, members)?;
+
+    if server_member_last_key == *server_member_last.key && server_member_key == *server_member.key
+    {
+        crate::program::swap_accounts::<ServerMember>(server_member, server_member_last, accounts)?;
+
+        server_state.members = members.into();
+
+        Ok(())
+    } else {
+        Err(Error::InvalidDerivedServerMemberAddress.into())
+    }
+}
+
+fn remove_server_administrator<'a>(
+    program_id: &Pubkey,
+    server: &AccountInfo<'a>,
+    server_administrator: &AccountInfo<'a>,
+    server_administrator_last: &AccountInfo<'a>,
+    accounts: &[AccountInfo<'a>],
+) -> ProgramResult {
+    let mut server_state = server.read_data_with_zero_copy_mut::<ServerPod>()?;
+    let administrators = u64::from(server_state.administrators).error_decrement()?;
+
+    let server_administrator_data: ServerAdministrator =
+        server_administrator.read_data_with_borsh()?;
+    let server_administrator_key = create_index_with_seed(
+        program_id,
+        ServerAdministrator::SEED,
+        server.key,
+        server_administrator_data.index,
+    )?;
+
+    let server_administrator_last_key = create_index_with_seed(
         program_id,
-        ServerMember::SEED,
+        ServerAdministrator::SEED,
         server.key,
-        server_state.members.error_decrement()?,
+        administrators,
     )?;
 
-    if server_member_last_key == *server_member_last.key && server_member_key == *server_member.key
+    if server_administrator_last_key == *server_administrator_last.key
+        && server_administrator_key == *server_administrator.key
     {
-        crate::program::swap_accounts::<ServerMember>(server_member, server_member_last)?;
+        crate::program::swap_accounts::<ServerAdministrator>(
+            server_administrator,
+            server_administrator_last,
+            accounts,
+        )?;
 
-        server_state.members = server_state.members.error_decrement()?;
-        server_state.serialize_const(&mut server_data)?;
+        server_state.administrators = administrators.into();
 
         Ok(())
     } else {
-        Err(Error::InvalidDerivedServerMemberAddress.into())
+        Err(Error::InvalidDerivedServerAdministratorAddress.into())
     }
 }
 
@@ -1302,8 +4901,10 @@ fn remove_dweller_server<'a>(
     dweller: &AccountInfo<'a>,
     dweller_server: &AccountInfo<'a>,
     dweller_server_last: &AccountInfo<'a>,
+    accounts: &[AccountInfo<'a>],
 ) -> ProgramResult {
-    let (mut dweller_data, mut dweller_state) = dweller.read_data_with_borsh_mut::<Dweller>()?;
+    let mut dweller_state = dweller.read_data_with_zero_copy_mut::<DwellerPod>()?;
+    let servers = u64::from(dweller_state.servers).error_decrement()?;
 
     let dweller_server_data: DwellerServer = dweller_server.read_data_with_borsh()?;
     let dweller_server_key = create_index_with_seed(
@@ -1313,23 +4914,346 @@ fn remove_dweller_server<'a>(
         dweller_server_data.index,
     )?;
 
-    let dweller_server_last_key = create_index_with_seed(
-        program_id,
-        DwellerServer::SEED,
-        dweller.key,
-        dweller_state.servers.error_decrement()?,
-    )?;
+    let dweller_server_last_key =
+        create_index_with_seed(program_id, DwellerServer::SEED, dweller.key, servers)?;
 
     if dweller_server_key == *dweller_server.key
         && dweller_server_last_key == *dweller_server_last.key
     {
-        crate::program::swap_accounts::<DwellerServer>(dweller_server, dweller_server_last)?;
+        crate::program::swap_accounts::<DwellerServer>(
+            dweller_server,
+            dweller_server_last,
+            accounts,
+        )?;
 
-        dweller_state.servers = dweller_state.servers.error_decrement()?;
-        dweller_state.serialize_const(&mut dweller_data)?;
+        dweller_state.servers = servers.into();
 
         Ok(())
     } else {
         Err(Error::InvalidDerivedDwellerServerAddress.into())
     }
 }
+
+/// [JoinPolicy] of server, [StateVersion::V1] servers are invite only
+fn join_policy(server: &AccountInfo) -> Result<u8, ProgramError> {
+    let version = server.read_data_with_zero_copy::<ServerPod>()?.version;
+    if version == StateVersion::Uninitialized as u8 {
+        Err(ProgramError::UninitializedAccount)
+    } else if version == StateVersion::V1 as u8 {
+        Ok(JoinPolicy::InviteOnly as u8)
+    } else {
+        Ok(server
+            .read_data_with_zero_copy::<ServerV2Pod>()?
+            .join_policy)
+    }
+}
+
+fn add_server_member<'a>(
+    program_id: &Pubkey,
+    server: &AccountInfo<'a>,
+    server_member: &AccountInfo<'a>,
+    dweller: &AccountInfo<'a>,
+    dweller_server: &AccountInfo<'a>,
+    tokens: &[AccountInfo<'a>],
+) -> ProgramResult {
+    let requirement = join_requirement(server)?;
+    let token = match requirement {
+        JoinRequirement::None => Pubkey::default(),
+        _ => {
+            let owner = dweller_authority(program_id, dweller)?;
+            *tokens
+                .iter()
+                .find(|x| meets_join_requirement(&requirement, &owner, x))
+                .ok_or(Error::JoinRequirementNotMet)?
+                .key
+        }
+    };
+
+    let mut dweller_state = dweller.read_data_with_zero_copy_mut::<DwellerPod>()?;
+    let servers = dweller_state.servers.into();
+
+    let dweller_server_key =
+        create_index_with_seed(program_id, DwellerServer::SEED, dweller.key, servers)?;
+
+    if dweller_server_key != *dweller_server.key {
+        return Err(Error::InvalidDerivedDwellerServerAddress.into());
+    }
+
+    create_list_entry_if_missing::<DwellerServer>(
+        program_id,
+        DwellerServer::SEED,
+        dweller,
+        servers,
+        dweller_server,
+        tokens,
+    )?;
+    let (mut dweller_server_data, mut dweller_server_state) =
+        dweller_server.read_data_with_borsh_mut::<DwellerServer>()?;
+
+    if dweller_server_state.version != StateVersion::Uninitialized {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let mut server_state = server.read_data_with_zero_copy_mut::<ServerPod>()?;
+    let members = server_state.members.into();
+
+    let server_member_key =
+        create_index_with_seed(program_id, ServerMember::SEED, server.key, members)?;
+
+    if server_member_key != *server_member.key {
+        return Err(Error::InvalidDerivedServerMemberAddress.into());
+    }
+
+    create_list_entry_if_missing::<ServerMember>(
+        program_id,
+        ServerMember::SEED,
+        server,
+        members,
+        server_member,
+        tokens,
+    )?;
+    if server_member.owner != program_id {
+        return Err(Error::InvalidDerivedServerMemberAddress.into());
+    }
+    let (mut server_member_data, server_member_state) =
+        server_member.read_data_with_borsh_mut::<ServerMember>()?;
+
+    if server_member_state.version != StateVersion::Uninitialized {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    write_new_server_member(
+        &mut server_member_data,
+        server.key,
+        members,
+        dweller.key,
+        &token,
+    )?;
+
+    dweller_server_state.container = *dweller.key;
+    dweller_server_state.index = servers;
+    dweller_server_state.version = StateVersion::V1;
+    dweller_server_state.server = *server.key;
+    dweller_server_state.serialize_const(&mut dweller_server_data)?;
+
+    dweller_state.servers = servers.error_increment()?.into();
+    server_state.members = members.error_increment()?.into();
+
+    Ok(())
+}
+
+/// creates [DwellerMarker] of dweller in list of entries with `seed` in container,
+/// failing with `listed` if dweller already has one
+#[allow(clippy::too_many_arguments)]
+fn create_dweller_marker<'a>(
+    program_id: &Pubkey,
+    seed: &str,
+    container: &AccountInfo<'a>,
+    dweller: &AccountInfo<'a>,
+    marker: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    listed: Error,
+) -> ProgramResult {
+    let (marker_key, bump) =
+        DwellerMarker::find_address(program_id, seed, container.key, dweller.key);
+    if marker_key != *marker.key {
+        return Err(Error::InvalidDwellerMarkerAddress.into());
+    }
+    if marker.owner == program_id {
+        return Err(listed.into());
+    }
+
+    create_program_derived_account(
+        payer,
+        marker,
+        system_program,
+        &Rent::get()?,
+        DwellerMarker::LEN as usize,
+        program_id,
+        &[
+            DwellerMarker::SEED,
+            seed.as_bytes(),
+            &container.key.to_bytes(),
+            &dweller.key.to_bytes(),
+            &[bump],
+        ],
+    )?;
+    DwellerMarker {
+        version: StateVersion::V1,
+        payer: *payer.key,
+    }
+    .serialize_const(&mut marker.try_borrow_mut_data()?)?;
+    Ok(())
+}
+
+/// closes [DwellerMarker] of dweller into its payer, entries listed before markers have none
+fn close_dweller_marker<'a>(
+    program_id: &Pubkey,
+    seed: &str,
+    container: &Pubkey,
+    dweller: &Pubkey,
+    marker: &AccountInfo<'a>,
+    accounts: &[AccountInfo<'a>],
+) -> ProgramResult {
+    let (marker_key, _) = DwellerMarker::find_address(program_id, seed, container, dweller);
+    if marker_key != *marker.key {
+        return Err(Error::InvalidDwellerMarkerAddress.into());
+    }
+    if marker.owner != program_id {
+        return Ok(());
+    }
+
+    let payer = marker.read_data_with_borsh::<DwellerMarker>()?.payer;
+    close_account(marker, &payer, accounts)
+}
+
+/// first `count` of `accounts`, entries of list passed before trailing optional accounts
+fn list_entries<'a, 'b>(
+    accounts: &'b [AccountInfo<'a>],
+    count: u64,
+) -> Result<&'b [AccountInfo<'a>], ProgramError> {
+    accounts
+        .get(..count as usize)
+        .ok_or(ProgramError::NotEnoughAccountKeys)
+}
+
+/// rewrites [ListEntry::index] of `entries` addressed from `first` onward
+fn reindex_entries<'a>(
+    program_id: &Pubkey,
+    seed: &str,
+    container: &AccountInfo<'a>,
+    entries: &[AccountInfo<'a>],
+    first: u64,
+) -> ProgramResult {
+    if entries.is_empty() {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    let mut index = first;
+    for entry in entries {
+        if *entry.key != create_index_with_seed(program_id, seed, container.key, index)? {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if entry.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let (mut entry_data, mut entry_state) = entry.read_data_with_borsh_mut::<ListEntry>()?;
+        if entry_state.version == StateVersion::Uninitialized {
+            return Err(ProgramError::UninitializedAccount);
+        }
+        if entry_state.container != *container.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        entry_state.index = index;
+        entry_state.serialize_const(&mut entry_data)?;
+        index = index.error_increment()?;
+    }
+
+    Ok(())
+}
+
+/// creates not yet existing list entry at `index` of `container`,
+/// paid by the account passed right before system program among `accounts`
+fn create_list_entry_if_missing<'a, T: IndexedEntry>(
+    program_id: &Pubkey,
+    seed: &str,
+    container: &AccountInfo<'a>,
+    index: u64,
+    account: &AccountInfo<'a>,
+    accounts: &[AccountInfo<'a>],
+) -> ProgramResult {
+    if account.owner == program_id {
+        return Ok(());
+    }
+
+    let (payer, _) = find_payer(accounts)?;
+    let (_, base_key, ..) = create_base_index_with_seed(program_id, seed, container.key, index)?;
+    let base = accounts
+        .iter()
+        .find(|x| *x.key == base_key)
+        .ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    create_seeded_list_entry_account::<T>(
+        seed,
+        container,
+        &index,
+        base,
+        account,
+        payer,
+        &Rent::get()?,
+        program_id,
+    )
+}
+
+fn remove_join_request<'a>(
+    program_id: &Pubkey,
+    server: &AccountInfo<'a>,
+    join_request: &AccountInfo<'a>,
+    join_request_last: &AccountInfo<'a>,
+    accounts: &[AccountInfo<'a>],
+) -> ProgramResult {
+    let (mut server_data, mut server_state) = server.read_data_with_borsh_mut::<ServerV2>()?;
+
+    let join_request_state: JoinRequest = join_request.read_data_with_borsh()?;
+    let join_request_key = create_index_with_seed(
+        program_id,
+        JoinRequest::SEED,
+        server.key,
+        join_request_state.index,
+    )?;
+
+    let join_request_last_key = create_index_with_seed(
+        program_id,
+        JoinRequest::SEED,
+        server.key,
+        server_state.join_requests.error_decrement()?,
+    )?;
+
+    if join_request_key == *join_request.key && join_request_last_key == *join_request_last.key {
+        swap_accounts::<JoinRequest>(join_request, join_request_last, accounts)?;
+
+        server_state.join_requests = server_state.join_requests.error_decrement()?;
+        server_state.serialize_const(&mut server_data)?;
+
+        Ok(())
+    } else {
+        Err(Error::InvalidDerivedJoinRequestAddress.into())
+    }
+}
+
+fn remove_server_member_status<'a>(
+    program_id: &Pubkey,
+    server: &AccountInfo<'a>,
+    member_status: &AccountInfo<'a>,
+    member_status_last: &AccountInfo<'a>,
+    accounts: &[AccountInfo<'a>],
+) -> ProgramResult {
+    let mut server_state = server.read_data_with_zero_copy_mut::<ServerPod>()?;
+    let member_statuses = u64::from(server_state.member_statuses).error_decrement()?;
+    let member_status_state = member_status.read_data_with_borsh::<ServerMemberStatus>()?;
+
+    let member_status_last_key = create_index_with_seed(
+        program_id,
+        ServerMemberStatus::SEED,
+        server.key,
+        member_statuses,
+    )?;
+
+    let member_status_key = create_index_with_seed(
+        program_id,
+        ServerMemberStatus::SEED,
+        server.key,
+        member_status_state.index,
+    )?;
+
+    if *member_status.key == member_status_key && *member_status_last.key == member_status_last_key
+    {
+        swap_accounts::<ServerMemberStatus>(member_status, member_status_last, accounts)?;
+
+        server_state.member_statuses = member_statuses.into();
+        Ok(())
+    } else {
+        Err(Error::InvalidDerivedServerMemberStatusAddress.into())
+    }
+}
@@ -1,15 +1,21 @@
 //! In program helpers
 
-use std::mem;
-
 use borsh::BorshSerialize;
 use solana_program::{
     account_info::AccountInfo,
     entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
     program_error::ProgramError,
     pubkey::{Pubkey, PubkeyError},
     rent::Rent,
-    system_instruction,
+    system_instruction, system_program,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    borsh::{BorshDeserialiseConst, BorshSerializeConst},
+    error::Error,
+    state::{IndexedEntry, ListEntry, RentPayer, StateVersion},
 };
 
 /// implements program seed public key address as indexed list pattern
@@ -41,6 +47,51 @@ pub fn create_index_with_seed(
     Ok(create)
 }
 
+/// Creates program derived account of `len` owned by `owner`.
+/// Works even if someone sent lamports to address beforehand.
+pub fn create_program_derived_account<'a>(
+    payer: &AccountInfo<'a>,
+    account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    rent: &Rent,
+    len: usize,
+    owner: &Pubkey,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    let required_lamports = rent.minimum_balance(len);
+    let lamports = account.lamports();
+    if lamports == 0 {
+        return invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                account.key,
+                required_lamports,
+                len as u64,
+                owner,
+            ),
+            &[payer.clone(), account.clone(), system_program.clone()],
+            &[signer_seeds],
+        );
+    }
+
+    if required_lamports > lamports {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, required_lamports - lamports),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+    invoke_signed(
+        &system_instruction::allocate(account.key, len as u64),
+        &[account.clone(), system_program.clone()],
+        &[signer_seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(account.key, owner),
+        &[account.clone(), system_program.clone()],
+        &[signer_seeds],
+    )
+}
+
 /// in program invoke to create program signed seeded account
 #[allow(clippy::too_many_arguments)]
 pub fn create_derived_account<'a>(
@@ -68,18 +119,90 @@ pub fn create_derived_account<'a>(
     )
 }
 
-/// swaps two accounts data
-/// panics if accounts are borrowedy
-pub fn swap_accounts<'a, T: Default + BorshSerialize>(
+/// moves last list entry into removed current one, keeping [ListEntry::index] and [RentPayer] of current,
+/// and clears last, closing it into its [RentPayer]
+/// grows older smaller current to size of last, paid by [find_payer]
+/// fails if accounts are already borrowed
+pub fn swap_accounts<'a, T: IndexedEntry>(
     current: &AccountInfo<'a>,
     last: &AccountInfo<'a>,
+    accounts: &[AccountInfo<'a>],
 ) -> Result<(), ProgramError> {
-    let mut last_data = last.data.try_borrow_mut().unwrap();
     if current.key != last.key {
-        let mut current_data = current.data.try_borrow_mut().unwrap();
-        mem::swap(&mut *current_data, &mut *last_data);
+        let last_len = entry_len::<T>(&last.try_borrow_data()?);
+        let current_len = entry_len::<T>(&current.try_borrow_data()?);
+        if current_len < last_len {
+            let (payer, system_program) = find_payer(accounts)?;
+            resize_rent_exempt_account(current, payer, system_program, &Rent::get()?, last_len)?;
+        }
+
+        let last_data = last.try_borrow_data()?;
+        let mut current_data = current.try_borrow_mut_data()?;
+        let current_len = entry_len::<T>(&current_data);
+        let index = ListEntry::deserialize_const(&current_data)?.index;
+        current_data[..last_len].copy_from_slice(&last_data[..last_len]);
+        current_data[last_len..current_len].fill(0);
+        let mut entry = ListEntry::deserialize_const(&current_data)?;
+        entry.index = index;
+        entry.serialize_const(&mut current_data)?;
+    }
+    T::default().serialize_const(&mut last.try_borrow_mut_data()?)?;
+    close_list_entry::<T>(last, accounts)
+}
+
+/// payer passed by [crate::instruction::with_payer], the account right before system program,
+/// and system program
+pub fn find_payer<'a, 'b>(
+    accounts: &'b [AccountInfo<'a>],
+) -> Result<(&'b AccountInfo<'a>, &'b AccountInfo<'a>), ProgramError> {
+    accounts
+        .iter()
+        .rposition(|x| *x.key == system_program::id())
+        .filter(|x| *x > 0)
+        .map(|x| (&accounts[x - 1], &accounts[x]))
+        .ok_or(ProgramError::NotEnoughAccountKeys)
+}
+
+/// data size of entry without [RentPayer]
+fn entry_len<T: IndexedEntry>(data: &[u8]) -> usize {
+    match RentPayer::read::<T>(data) {
+        Some(_) => data.len() - RentPayer::LEN as usize,
+        None => data.len(),
     }
-    T::default().serialize(&mut *last_data)?;
+}
+
+/// closes cleared entry refunding its [RentPayer], which must be among accounts,
+/// so [crate::instruction::Instruction::CreateDerivedAccount] can create address again
+fn close_list_entry<'a, T: IndexedEntry>(
+    entry: &AccountInfo<'a>,
+    accounts: &[AccountInfo<'a>],
+) -> ProgramResult {
+    let payer = match RentPayer::read::<T>(&entry.try_borrow_data()?) {
+        Some(payer) => payer,
+        None => return Ok(()),
+    };
+    close_account(entry, &payer, accounts)
+}
+
+/// moves lamports of program owned account to `payer`, which must be writable among accounts,
+/// and hands account back to system program
+pub fn close_account<'a>(
+    account: &AccountInfo<'a>,
+    payer: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+) -> ProgramResult {
+    let receiver = accounts
+        .iter()
+        .find(|x| x.key == payer && x.is_writable)
+        .ok_or(Error::RentPayerNotProvided)?;
+
+    **receiver.try_borrow_mut_lamports()? = receiver
+        .lamports()
+        .checked_add(account.lamports())
+        .ok_or(ProgramError::InvalidArgument)?;
+    **account.try_borrow_mut_lamports()? = 0;
+    account.realloc(0, false)?;
+    account.assign(&system_program::id());
     Ok(())
 }
 
@@ -117,3 +240,63 @@ pub fn create_seeded_rent_except_account<'a>(
     )?;
     Ok(())
 }
+
+/// [create_seeded_rent_except_account] for [IndexedEntry], recording payer as [RentPayer]
+#[allow(clippy::too_many_arguments)]
+pub fn create_seeded_list_entry_account<'a, T: IndexedEntry>(
+    seed: &str,
+    owner_account_info: &AccountInfo<'a>,
+    index: &u64,
+    base_account_info: &AccountInfo<'a>,
+    account_to_create_info: &AccountInfo<'a>,
+    payer_account_info: &AccountInfo<'a>,
+    rent: &Rent,
+    program_id: &Pubkey,
+) -> Result<(), ProgramError> {
+    create_seeded_rent_except_account(
+        seed,
+        owner_account_info,
+        index,
+        base_account_info,
+        account_to_create_info,
+        payer_account_info,
+        rent,
+        T::ALLOCATED_LEN + RentPayer::LEN,
+        program_id,
+    )?;
+    let mut data = account_to_create_info.try_borrow_mut_data()?;
+    RentPayer {
+        version: StateVersion::V1,
+        payer: *payer_account_info.key,
+    }
+    .serialize_const(&mut data[T::ALLOCATED_LEN as usize..])?;
+    Ok(())
+}
+
+/// resizes program owned account to `len` keeping it rent exempt,
+/// tops up from `payer` on grow and refunds excess to `payer` on shrink
+pub fn resize_rent_exempt_account<'a>(
+    account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    rent: &Rent,
+    len: usize,
+) -> ProgramResult {
+    let required_lamports = rent.minimum_balance(len);
+    let lamports = account.lamports();
+    if required_lamports > lamports {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, required_lamports - lamports),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    } else if lamports > required_lamports {
+        let excess = lamports - required_lamports;
+        **account.try_borrow_mut_lamports()? = required_lamports;
+        **payer.try_borrow_mut_lamports()? = payer
+            .lamports()
+            .checked_add(excess)
+            .ok_or(Error::Overflow)?;
+    }
+
+    account.realloc(len, false)
+}
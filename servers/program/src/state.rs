@@ -1,8 +1,11 @@
 ///! Registry types.
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use bytemuck::{Pod, Zeroable};
 use num_derive::{FromPrimitive, ToPrimitive};
 use solana_program::{entrypoint::ProgramResult, program_error::ProgramError, pubkey::Pubkey};
 
+use crate::error::Error;
+
 /// flag
 #[repr(C)]
 #[derive(
@@ -15,11 +18,18 @@ use solana_program::{entrypoint::ProgramResult, program_error::ProgramError, pub
     ToPrimitive,
     FromPrimitive,
 )]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 pub enum StateVersion {
     /// default
     Uninitialized,
     /// initial
     V1,
+    /// extended layout, see `*V2` states
+    V2,
+    /// extended layout, see `*V3` states
+    V3,
+    /// extended layout, see `*V4` states
+    V4,
 }
 
 impl Default for StateVersion {
@@ -28,11 +38,58 @@ impl Default for StateVersion {
     }
 }
 
+/// versioned state extending `base`, previous version it starts with, derefs to it
+macro_rules! extends {
+    ($state:ty, $base:ty) => {
+        impl std::ops::Deref for $state {
+            type Target = $base;
+            fn deref(&self) -> &$base {
+                &self.base
+            }
+        }
+
+        impl std::ops::DerefMut for $state {
+            fn deref_mut(&mut self) -> &mut $base {
+                &mut self.base
+            }
+        }
+    };
+}
+
+/// who may join a server
+#[repr(C)]
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    PartialEq,
+    Debug,
+    Clone,
+    BorshSchema,
+    ToPrimitive,
+    FromPrimitive,
+)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub enum JoinPolicy {
+    /// default, dweller must have [ServerMemberStatus]
+    InviteOnly,
+    /// any dweller can join
+    Public,
+    /// dweller files [JoinRequest] which administrator approves or rejects
+    ApprovalRequired,
+}
+
+impl Default for JoinPolicy {
+    fn default() -> Self {
+        JoinPolicy::InviteOnly
+    }
+}
+
 /// address of signer + separate program deployed
 /// https://github.com/Satellite-im/Satellite-Contracts/blob/main/contracts/DwellerID.sol
 /// state
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 pub struct Dweller {
     /// version
     pub version: StateVersion,
@@ -56,19 +113,325 @@ impl Dweller {
     pub const LEN: u64 = 137;
 }
 
+/// little endian `u64` of zero copy views, unaligned as in borsh layout
+#[repr(transparent)]
+#[derive(Clone, Copy, Default, Debug, PartialEq, Pod, Zeroable)]
+pub struct PodU64(pub [u8; 8]);
+
+impl From<PodU64> for u64 {
+    fn from(value: PodU64) -> Self {
+        u64::from_le_bytes(value.0)
+    }
+}
+
+impl From<u64> for PodU64 {
+    fn from(value: u64) -> Self {
+        PodU64(value.to_le_bytes())
+    }
+}
+
+/// zero copy view of [Dweller] layout, prefix of all dweller versions
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct DwellerPod {
+    /// [StateVersion]
+    pub version: u8,
+    /// [Dweller::servers]
+    pub servers: PodU64,
+    /// [Dweller::name]
+    pub name: [u8; 32],
+    /// [Dweller::photo_hash]
+    pub photo_hash: [u8; 64],
+    /// [Dweller::status]
+    pub status: [u8; 32],
+}
+
+/// Profile of [StateVersion::V2] dweller, starts with [Dweller] layout, prefix of [DwellerV3].
+/// Account is resized to fit the packed length.
+/// state
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct DwellerV2 {
+    /// [StateVersion::V1] fields
+    pub base: Dweller,
+
+    /// signs for dweller, rotated without changing dweller address
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub authority: Pubkey,
+
+    /// Multihash referencing IPFS hash of profile banner
+    pub banner_hash: [u8; 64],
+
+    /// UTF-8, up to [DwellerV2::MAX_BIO_LEN] bytes
+    pub bio: String,
+
+    /// UTF-8, up to [DwellerV2::MAX_PRONOUNS_LEN] bytes
+    pub pronouns: String,
+
+    /// up to [DwellerV2::MAX_LINKS] UTF-8 links of up to [DwellerV2::MAX_LINK_LEN] bytes
+    pub links: Vec<String>,
+}
+
+extends!(DwellerV2, Dweller);
+extends!(DwellerV3, DwellerV2);
+extends!(DwellerV4, DwellerV3);
+
+impl DwellerV2 {
+    /// data size with empty variable fields
+    pub const MIN_LEN: u64 = 245;
+    /// cap of [DwellerV2::bio]
+    pub const MAX_BIO_LEN: usize = 256;
+    /// cap of [DwellerV2::pronouns]
+    pub const MAX_PRONOUNS_LEN: usize = 32;
+    /// cap of [DwellerV2::links] count
+    pub const MAX_LINKS: usize = 4;
+    /// cap of each of [DwellerV2::links]
+    pub const MAX_LINK_LEN: usize = 128;
+
+    /// upgrades V1 state with empty profile
+    pub fn from_v1(dweller: Dweller, authority: Pubkey) -> Self {
+        Self {
+            base: Dweller {
+                version: StateVersion::V2,
+                ..dweller
+            },
+            authority,
+            banner_hash: [0; 64],
+            bio: String::new(),
+            pronouns: String::new(),
+            links: Vec::new(),
+        }
+    }
+
+    /// true if all variable fields fit caps
+    pub fn is_valid(&self) -> bool {
+        self.bio.len() <= Self::MAX_BIO_LEN
+            && self.pronouns.len() <= Self::MAX_PRONOUNS_LEN
+            && self.links.len() <= Self::MAX_LINKS
+            && self.links.iter().all(|x| x.len() <= Self::MAX_LINK_LEN)
+    }
+}
+
+/// [StateVersion::V3] dweller, [DwellerV2] with handle appended, prefix of [DwellerV4].
+/// Account is resized to fit the packed length.
+/// state
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct DwellerV3 {
+    /// [StateVersion::V2] fields
+    pub base: DwellerV2,
+
+    /// address of claimed [DwellerHandle], default key if none
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub handle: Pubkey,
+}
+
+impl DwellerV3 {
+    /// upgrades V2 state with handle
+    pub fn from_v2(mut dweller: DwellerV2, handle: Pubkey) -> Self {
+        dweller.version = StateVersion::V3;
+        Self {
+            base: dweller,
+            handle,
+        }
+    }
+}
+
+/// [StateVersion::V4] dweller, [DwellerV3] with NFT avatar appended.
+/// Account is resized to fit the packed length.
+/// state
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct DwellerV4 {
+    /// [StateVersion::V3] fields
+    pub base: DwellerV3,
+
+    /// `spl_nft_erc_721` token owned by [DwellerV2::authority] when set, default key if none.
+    /// Clients show verified badge only while token is still owned by authority.
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub nft_avatar: Pubkey,
+}
+
+impl DwellerV4 {
+    /// upgrades V3 state with NFT avatar
+    pub fn from_v3(mut dweller: DwellerV3, nft_avatar: Pubkey) -> Self {
+        dweller.version = StateVersion::V4;
+        Self {
+            base: dweller,
+            nft_avatar,
+        }
+    }
+}
+
+/// Unique name resolving to dweller, e.g. `alice` for "@alice".
+/// Has program derived address from normalized handle, see [DwellerHandle::find_address].
+/// state
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct DwellerHandle {
+    /// version
+    pub version: StateVersion,
+    /// owner of handle
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub dweller: Pubkey,
+    /// normalized, zero padded
+    pub handle: [u8; 32],
+}
+
+impl DwellerHandle {
+    /// data size
+    pub const LEN: u64 = 65;
+    /// seed of handle address
+    pub const SEED: &'static [u8] = b"DwellerHandle";
+
+    /// Lowercases handle and zero pads it.
+    /// Fails unless handle is 1 to 32 of `a-z`, `0-9` or `_` in any case.
+    pub fn normalize(handle: &str) -> Result<[u8; 32], ProgramError> {
+        let bytes = handle.as_bytes();
+        if bytes.is_empty() || bytes.len() > 32 {
+            return Err(Error::InvalidHandle.into());
+        }
+
+        let mut normalized = [0; 32];
+        for (to, from) in normalized.iter_mut().zip(bytes) {
+            *to = from.to_ascii_lowercase();
+            if !(to.is_ascii_lowercase() || to.is_ascii_digit() || *to == b'_') {
+                return Err(Error::InvalidHandle.into());
+            }
+        }
+        Ok(normalized)
+    }
+
+    /// address of normalized handle
+    pub fn find_normalized_address(program_id: &Pubkey, normalized: &[u8; 32]) -> (Pubkey, u8) {
+        let len = normalized.iter().position(|x| *x == 0).unwrap_or(32);
+        Pubkey::find_program_address(&[Self::SEED, &normalized[..len]], program_id)
+    }
+
+    /// address of handle, `Alice` and `alice` resolve to same
+    pub fn find_address(program_id: &Pubkey, handle: &str) -> Result<(Pubkey, u8), ProgramError> {
+        Ok(Self::find_normalized_address(
+            program_id,
+            &Self::normalize(handle)?,
+        ))
+    }
+}
+
+/// Marks dweller as already listed in container, one [ServerMember] or pending [JoinRequest] per server
+/// and one [EventRsvp] per event.
+/// Has program derived address from seed of entry, container and dweller, see [DwellerMarker::find_address].
+/// state
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct DwellerMarker {
+    /// version
+    pub version: StateVersion,
+    /// receives rent when entry is removed
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub payer: Pubkey,
+}
+
+impl DwellerMarker {
+    /// data size
+    pub const LEN: u64 = 33;
+    /// seed of marker address
+    pub const SEED: &'static [u8] = b"DwellerMarker";
+
+    /// address of marker of dweller in list of entries with `seed` in `container`
+    pub fn find_address(
+        program_id: &Pubkey,
+        seed: &str,
+        container: &Pubkey,
+        dweller: &Pubkey,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                Self::SEED,
+                seed.as_bytes(),
+                &container.to_bytes(),
+                &dweller.to_bytes(),
+            ],
+            program_id,
+        )
+    }
+}
+
 /// Mapping of `Dweller` to `Server`.
+/// Common prefix of every entry of indexed list, address is derived from `container` and `index`.
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct ListEntry {
+    /// version
+    pub version: StateVersion,
+    /// owner of list
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub container: Pubkey,
+    /// index used to derive address
+    pub index: u64,
+}
+
+impl ListEntry {
+    /// data size
+    pub const LEN: u64 = 41;
+}
+
+/// Entry of indexed list which starts with [ListEntry] layout.
+pub trait IndexedEntry: Default + BorshSerialize {
+    /// data size allocated for new entries, followed by [RentPayer]
+    const ALLOCATED_LEN: u64;
+}
+
+/// Payer of [crate::instruction::Instruction::CreateDerivedAccount] recorded after
+/// [IndexedEntry::ALLOCATED_LEN] data of entry, refunded when entry account is closed.
+/// Entries created before have no record and are left zeroed instead.
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct RentPayer {
+    /// version
+    pub version: StateVersion,
+    /// receiver of rent
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub payer: Pubkey,
+}
+
+impl RentPayer {
+    /// data size
+    pub const LEN: u64 = 33;
+
+    /// reads record of entry account data if it has one
+    pub fn read<T: IndexedEntry>(data: &[u8]) -> Option<Pubkey> {
+        if data.len() as u64 != T::ALLOCATED_LEN + Self::LEN {
+            return None;
+        }
+        match Self::try_from_slice(&data[T::ALLOCATED_LEN as usize..]) {
+            Ok(record) if record.version == StateVersion::V1 => Some(record.payer),
+            _ => None,
+        }
+    }
+}
+
 /// Account address is be derived from `Dweller`
 /// state
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 pub struct DwellerServer {
     /// version
     pub version: StateVersion,
     /// dweller
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
     pub container: Pubkey,
     /// [Dweller::servers] index used to derive address
     pub index: u64,
     /// to
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
     pub server: Pubkey,
 }
 
@@ -79,21 +442,28 @@ impl DwellerServer {
     pub const SEED: &'static str = "DwellerServer";
 }
 
+impl IndexedEntry for DwellerServer {
+    const ALLOCATED_LEN: u64 = DwellerServer::LEN;
+}
+
 /// Server members whom have joined
 /// Has program derived address from Server
 /// many to many map of `Server` to `DwellerID` (inverse of `DwellerServer`)
 /// Payed by dweller.
-/// state
+/// state, prefix of [ServerMemberV2]
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 pub struct ServerMember {
     /// version
     pub version: StateVersion,
     /// server
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
     pub container: Pubkey,
     /// [Server::members] index used to derive address
     pub index: u64,
     /// to
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
     pub dweller: Pubkey,
 }
 
@@ -104,18 +474,78 @@ impl ServerMember {
     pub const SEED: &'static str = "ServerMember";
 }
 
+impl IndexedEntry for ServerMember {
+    const ALLOCATED_LEN: u64 = ServerMemberV4::LEN;
+}
+
+/// state of [StateVersion::V2] member, starts with [ServerMember] layout, prefix of [ServerMemberV3]
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct ServerMemberV2 {
+    /// [StateVersion::V1] fields
+    pub base: ServerMember,
+    /// display name in server, empty uses [Dweller::name]
+    pub nickname: [u8; 32],
+}
+
+extends!(ServerMemberV2, ServerMember);
+extends!(ServerMemberV3, ServerMemberV2);
+extends!(ServerMemberV4, ServerMemberV3);
+
+impl ServerMemberV2 {
+    /// data size
+    pub const LEN: u64 = 105;
+}
+
+/// state of [StateVersion::V3] member, starts with [ServerMemberV2] layout, prefix of [ServerMemberV4]
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct ServerMemberV3 {
+    /// [StateVersion::V2] fields
+    pub base: ServerMemberV2,
+    /// token account which met [ServerV3::join_requirement], default key if none
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub token: Pubkey,
+}
+
+impl ServerMemberV3 {
+    /// data size
+    pub const LEN: u64 = 137;
+}
+
+/// state of [StateVersion::V4] member, starts with [ServerMemberV3] layout
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct ServerMemberV4 {
+    /// [StateVersion::V3] fields
+    pub base: ServerMemberV3,
+    /// unix timestamp subscription lasts until if [JoinFee::period] is not zero
+    pub paid_until: i64,
+}
+
+impl ServerMemberV4 {
+    /// data size
+    pub const LEN: u64 = 145;
+}
+
 /// Dwellers who were invited.
 /// state
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 pub struct ServerMemberStatus {
     /// version
     pub version: StateVersion,
     /// server
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
     pub container: Pubkey,
     /// index    
     pub index: u64,
     /// to
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
     pub dweller: Pubkey,
 }
 
@@ -126,17 +556,53 @@ impl ServerMemberStatus {
     pub const SEED: &'static str = "ServerMemberStatus";
 }
 
+impl IndexedEntry for ServerMemberStatus {
+    const ALLOCATED_LEN: u64 = ServerMemberStatus::LEN;
+}
+
+/// Dwellers who asked to join [JoinPolicy::ApprovalRequired] server.
+/// state
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct JoinRequest {
+    /// version
+    pub version: StateVersion,
+    /// server
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub container: Pubkey,
+    /// [ServerV2::join_requests] index used to derive address
+    pub index: u64,
+    /// from
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub dweller: Pubkey,
+}
+
+impl JoinRequest {
+    /// data size
+    pub const LEN: u64 = 73;
+    /// entity type used for seed
+    pub const SEED: &'static str = "JoinRequest";
+}
+
+impl IndexedEntry for JoinRequest {
+    const ALLOCATED_LEN: u64 = JoinRequest::LEN;
+}
+
 /// state
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 pub struct ServerAdministrator {
     /// version
     pub version: StateVersion,
     /// server
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
     pub container: Pubkey,
     /// [Server::administrators] index used to derive address
     pub index: u64,
     /// to
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
     pub dweller: Pubkey,
 }
 
@@ -156,13 +622,19 @@ impl ServerAdministrator {
     }
 }
 
+impl IndexedEntry for ServerAdministrator {
+    const ALLOCATED_LEN: u64 = ServerAdministrator::LEN;
+}
+
 /// state
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 pub struct Server {
     /// version
     pub version: StateVersion,
     /// must be dweller, can add and remove admins
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
     pub owner: Pubkey,
 
     /// name
@@ -193,84 +665,953 @@ impl Server {
     pub const LEN: u64 = 233;
 }
 
-/// state
+/// zero copy view of [Server] layout, prefix of all server versions
 #[repr(C)]
-#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
-pub struct ServerChannel {
-    /// version
-    pub version: StateVersion,
-
-    /// server
-    pub container: Pubkey,
-    /// [Server::channels] index used to derive address
-    pub index: u64,
-    /// type
-    pub type_id: u8,
-    /// name
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct ServerPod {
+    /// [StateVersion]
+    pub version: u8,
+    /// [Server::owner]
+    pub owner: Pubkey,
+    /// [Server::name]
     pub name: [u8; 32],
+    /// [Server::photo_hash]
+    pub photo_hash: [u8; 64],
+    /// [Server::db_hash]
+    pub db_hash: [u8; 64],
+    /// [Server::members]
+    pub members: PodU64,
+    /// [Server::member_statuses]
+    pub member_statuses: PodU64,
+    /// [Server::administrators]
+    pub administrators: PodU64,
+    /// [Server::channels]
+    pub channels: PodU64,
+    /// [Server::groups]
+    pub groups: PodU64,
 }
 
-impl ServerChannel {
-    /// data size
-    pub const LEN: u64 = 74;
-    /// entity type used for seed
-    pub const SEED: &'static str = "ServerChannel";
+/// zero copy view of [ServerV2] layout up to join fields,
+/// prefix of server versions since [StateVersion::V2]
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct ServerV2Pod {
+    /// [Server] layout
+    pub server: ServerPod,
+    /// [JoinPolicy]
+    pub join_policy: u8,
+    /// [ServerV2::join_requests]
+    pub join_requests: PodU64,
 }
 
-/// state
+/// state of [StateVersion::V2] server, starts with [Server] layout, prefix of [ServerV3].
+/// [StateVersion::V1] servers are [JoinPolicy::InviteOnly].
 #[repr(C)]
-#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
-pub struct ServerGroup {
-    /// version
-    pub version: StateVersion,
-    /// server
-    pub container: Pubkey,
-    /// [Server::groups] index used to derive address
-    pub index: u64,
-
-    /// name
-    pub name: [u8; 32],
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct ServerV2 {
+    /// [StateVersion::V1] fields
+    pub base: Server,
 
+    /// who may join
+    pub join_policy: JoinPolicy,
     /// index
-    pub channels: u64,
+    pub join_requests: u64,
+
+    /// Multihash referencing IPFS hash of description
+    pub description_hash: [u8; 64],
+    /// Multihash referencing IPFS hash of banner
+    pub banner_hash: [u8; 64],
+
+    /// has [DirectoryEntry]
+    pub listed: bool,
 }
 
-impl ServerGroup {
+extends!(ServerV2, Server);
+extends!(ServerV3, ServerV2);
+extends!(ServerV4, ServerV3);
+
+impl ServerV2 {
     /// data size
-    pub const LEN: u64 = 81;
-    /// entity type used for seed
-    pub const SEED: &'static str = "ServerGroup";
+    pub const LEN: u64 = 371;
 }
 
-/// state
+/// what joining dweller must hold, checked against token account owned by dweller authority
 #[repr(C)]
-#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
-pub struct GroupChannel {
-    /// version
-    pub version: StateVersion,
-    /// group
-    pub container: Pubkey,
-    /// [Group::channels] index used to derive address
-    pub index: u64,
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+pub enum JoinRequirement {
+    /// default
+    None,
+    /// spl-token account with at least amount of mint
+    TokenBalance {
+        /// spl-token mint
+        mint: Pubkey,
+        /// minimal balance
+        amount: u64,
+    },
+    /// any spl_nft_erc_721 token of mint
+    NftOwnership {
+        /// spl_nft_erc_721 mint
+        mint: Pubkey,
+    },
+}
 
-    /// to
-    pub channel: Pubkey,
+impl Default for JoinRequirement {
+    fn default() -> Self {
+        JoinRequirement::None
+    }
 }
 
-impl GroupChannel {
+/// state of [StateVersion::V3] server, starts with [ServerV2] layout, prefix of [ServerV4]
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct ServerV3 {
+    /// [StateVersion::V2] fields
+    pub base: ServerV2,
+
+    /// checked on join and by recheck
+    pub join_requirement: JoinRequirement,
+}
+
+impl ServerV3 {
     /// data size
-    pub const LEN: u64 = 73;
+    pub const LEN: u64 = 412;
+}
 
-    /// entity type used for seed
-    pub const SEED: &'static str = "GroupChannel";
+/// spl-token fee paid to join server
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct JoinFee {
+    /// spl-token mint
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub mint: Pubkey,
+    /// paid on join and on each renewal
+    pub amount: u64,
+    /// seconds membership lasts until renewal, zero for one time fee
+    pub period: i64,
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
+impl JoinFee {
+    /// seed of treasury token account
+    pub const TREASURY_SEED: &'static [u8] = b"ServerTreasury";
 
-    #[test]
+    /// program derived token account of server which receives fees,
+    /// it is its own spl-token authority
+    pub fn find_treasury_address(
+        program_id: &Pubkey,
+        server: &Pubkey,
+        mint: &Pubkey,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[&server.to_bytes(), Self::TREASURY_SEED, &mint.to_bytes()],
+            program_id,
+        )
+    }
+}
+
+/// state of [StateVersion::V4] server, starts with [ServerV3] layout
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct ServerV4 {
+    /// [StateVersion::V3] fields
+    pub base: ServerV3,
+
+    /// paid on join into treasury, see [JoinFee::find_treasury_address]
+    pub join_fee: Option<JoinFee>,
+}
+
+impl ServerV4 {
+    /// data size
+    pub const LEN: u64 = 461;
+}
+
+/// privileged action recorded in [ServerAuditLog]
+#[repr(C)]
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    PartialEq,
+    Debug,
+    Clone,
+    Copy,
+    BorshSchema,
+    ToPrimitive,
+    FromPrimitive,
+)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub enum AuditAction {
+    /// default, empty slot
+    None,
+    /// target is administrator dweller
+    AddAdministrator,
+    /// target is administrator dweller
+    RemoveAdministrator,
+    /// target is invited dweller
+    InviteToServer,
+    /// target is invited dweller
+    RevokeInvite,
+    /// target is channel
+    AddChannel,
+    /// target is channel
+    UpdateChannel,
+    /// target is channel
+    DeleteChannel,
+    /// target is channel
+    SetChannelOverwrite,
+    /// target is channel
+    RemoveChannelOverwrite,
+    /// target is group
+    CreateGroup,
+    /// target is group
+    RenameGroup,
+    /// target is group
+    DeleteGroup,
+    /// target is channel
+    AddChannelToGroup,
+    /// target is group
+    RemoveChannelFromGroup,
+    /// target is group
+    MoveGroupChannel,
+    /// target is server
+    UpdateServerProfile,
+    /// target is server
+    SetServerDb,
+    /// target is server
+    SetJoinPolicy,
+    /// target is requesting dweller
+    ApproveJoinRequest,
+    /// target is requesting dweller
+    RejectJoinRequest,
+    /// target is server
+    ListServer,
+    /// target is server
+    UnlistServer,
+    /// target is member dweller
+    SetMemberNickname,
+    /// target is channel
+    SetChannelCommitter,
+    /// target is checkpoint
+    CommitChannelCheckpoint,
+    /// target is server
+    SetJoinRequirement,
+    /// target is server
+    SetJoinFee,
+    /// target is destination token account
+    WithdrawTreasury,
+    /// target is event
+    CreateServerEvent,
+    /// target is event
+    CancelServerEvent,
+    /// target is container of list
+    Reindex,
+}
+
+impl Default for AuditAction {
+    fn default() -> Self {
+        AuditAction::None
+    }
+}
+
+/// entry of [ServerAuditLog]
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct AuditEntry {
+    /// dweller or delegated key which did action
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub actor: Pubkey,
+    /// action
+    pub action: AuditAction,
+    /// acted upon
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub target: Pubkey,
+    /// unix timestamp of action
+    pub unix_timestamp: i64,
+}
+
+impl AuditEntry {
+    /// data size
+    pub const LEN: u64 = 73;
+}
+
+/// Ring buffer of last [ServerAuditLog::CAPACITY] privileged actions in server.
+/// Has program derived address from [Server] with index 0.
+/// Header is followed by [AuditEntry] slots, use [decode_server_audit_log] to read them.
+/// state
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct ServerAuditLog {
+    /// version
+    pub version: StateVersion,
+    /// server
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub container: Pubkey,
+    /// total entries ever appended, next slot is this modulo [Self::CAPACITY]
+    pub appended: u64,
+}
+
+impl ServerAuditLog {
+    /// header size
+    pub const HEADER_LEN: u64 = 41;
+    /// entries kept
+    pub const CAPACITY: u64 = 64;
+    /// data size
+    pub const LEN: u64 = Self::HEADER_LEN + Self::CAPACITY * AuditEntry::LEN;
+    /// entity type used for seed
+    pub const SEED: &'static str = "ServerAuditLog";
+
+    /// data offset of slot to write next entry into
+    pub fn next_offset(&self) -> usize {
+        (Self::HEADER_LEN + (self.appended % Self::CAPACITY) * AuditEntry::LEN) as usize
+    }
+}
+
+/// Reads [ServerAuditLog] account data, returns kept entries oldest first.
+pub fn decode_server_audit_log(
+    data: &[u8],
+) -> Result<(ServerAuditLog, Vec<AuditEntry>), ProgramError> {
+    if data.len() < ServerAuditLog::LEN as usize {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let log = ServerAuditLog::deserialize(&mut &data[..])?;
+    let kept = log.appended.min(ServerAuditLog::CAPACITY);
+    let first = log.appended - kept;
+    let entries = (first..log.appended)
+        .map(|x| {
+            let offset =
+                ServerAuditLog::HEADER_LEN + (x % ServerAuditLog::CAPACITY) * AuditEntry::LEN;
+            AuditEntry::deserialize(&mut &data[offset as usize..])
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((log, entries))
+}
+
+/// Program wide list of public servers.
+/// Has program derived address from program id.
+/// state
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct ServerDirectory {
+    /// version
+    pub version: StateVersion,
+    /// index
+    pub entries: u64,
+}
+
+impl ServerDirectory {
+    /// data size
+    pub const LEN: u64 = 9;
+    /// entity type used for seed, singleton has index 0
+    pub const SEED: &'static str = "ServerDirectory";
+}
+
+/// category of [DirectoryEntry]
+#[repr(C)]
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    PartialEq,
+    Debug,
+    Clone,
+    Copy,
+    BorshSchema,
+    ToPrimitive,
+    FromPrimitive,
+)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub enum ServerCategory {
+    /// default
+    Other,
+    /// games
+    Gaming,
+    /// music
+    Music,
+    /// learning
+    Education,
+    /// science and technology
+    Technology,
+    /// art and design
+    Art,
+    /// local or interest groups
+    Community,
+    /// crypto and finance
+    Finance,
+}
+
+impl Default for ServerCategory {
+    fn default() -> Self {
+        ServerCategory::Other
+    }
+}
+
+/// Listing of public server in [ServerDirectory].
+/// Has program derived address from [ServerDirectory].
+/// state
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct DirectoryEntry {
+    /// version
+    pub version: StateVersion,
+    /// directory
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub container: Pubkey,
+    /// [ServerDirectory::entries] index used to derive address
+    pub index: u64,
+    /// listed
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub server: Pubkey,
+    /// category
+    pub category: ServerCategory,
+    /// ASCII language code, like `en` or `pt-BR`, zero padded
+    pub language: [u8; 8],
+    /// zero padded UTF-8 tags
+    pub tags: [[u8; 16]; 4],
+}
+
+impl DirectoryEntry {
+    /// data size
+    pub const LEN: u64 = 146;
+    /// entity type used for seed
+    pub const SEED: &'static str = "DirectoryEntry";
+}
+
+impl IndexedEntry for DirectoryEntry {
+    const ALLOCATED_LEN: u64 = DirectoryEntry::LEN;
+}
+
+/// kind of [ServerChannel]
+#[repr(C)]
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    PartialEq,
+    Debug,
+    Clone,
+    Copy,
+    BorshSchema,
+    ToPrimitive,
+    FromPrimitive,
+)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub enum ChannelKind {
+    /// default
+    Text,
+    /// uses [VoiceChannelConfig]
+    Voice,
+    /// only administrators post
+    Announcement,
+    /// uses [VoiceChannelConfig]
+    Stage,
+    /// threads
+    Forum,
+}
+
+impl Default for ChannelKind {
+    fn default() -> Self {
+        ChannelKind::Text
+    }
+}
+
+impl ChannelKind {
+    /// carries [VoiceChannelConfig]
+    pub fn is_voice(&self) -> bool {
+        matches!(self, ChannelKind::Voice | ChannelKind::Stage)
+    }
+}
+
+/// voice and stage channel settings
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct VoiceChannelConfig {
+    /// zero is unlimited
+    pub user_limit: u32,
+    /// bits per second, zero is client default
+    pub bitrate: u32,
+}
+
+impl VoiceChannelConfig {
+    /// upper bound of [VoiceChannelConfig::bitrate]
+    pub const MAX_BITRATE: u32 = 384_000;
+}
+
+/// [StateVersion::V1] layout, prefix of [ServerChannelV2].
+/// Enough to derive addresses and remove channel of any version.
+/// state
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct ServerChannel {
+    /// version
+    pub version: StateVersion,
+
+    /// server
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub container: Pubkey,
+    /// [Server::channels] index used to derive address
+    pub index: u64,
+    /// [ChannelKind] as byte
+    pub type_id: u8,
+    /// name
+    pub name: [u8; 32],
+}
+
+impl ServerChannel {
+    /// data size
+    pub const LEN: u64 = 74;
+    /// entity type used for seed
+    pub const SEED: &'static str = "ServerChannel";
+}
+
+impl IndexedEntry for ServerChannel {
+    const ALLOCATED_LEN: u64 = ServerChannelV2::LEN;
+}
+
+/// [StateVersion::V2] layout of [ServerChannel]
+/// state
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct ServerChannelV2 {
+    /// version
+    pub version: StateVersion,
+
+    /// server
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub container: Pubkey,
+    /// [Server::channels] index used to derive address
+    pub index: u64,
+    /// kind
+    pub kind: ChannelKind,
+    /// name
+    pub name: [u8; 32],
+
+    /// IPFS hash of topic
+    pub topic_hash: [u8; 64],
+    /// UI ordering
+    pub position: u64,
+    /// ignored unless [ChannelKind::is_voice]
+    pub voice: VoiceChannelConfig,
+    /// index
+    pub overwrites: u64,
+}
+
+impl ServerChannelV2 {
+    /// data size
+    pub const LEN: u64 = 162;
+}
+
+/// whom [ChannelOverwrite] applies to
+#[repr(C)]
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    PartialEq,
+    Debug,
+    Clone,
+    Copy,
+    BorshSchema,
+    ToPrimitive,
+    FromPrimitive,
+)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub enum OverwriteTarget {
+    /// all members
+    Everyone,
+    /// dwellers with [ServerAdministrator]
+    Administrators,
+    /// [ChannelOverwrite::dweller]
+    Dweller,
+}
+
+impl Default for OverwriteTarget {
+    fn default() -> Self {
+        OverwriteTarget::Everyone
+    }
+}
+
+/// Permission overwrite of [ServerChannelV2].
+/// Has program derived address from [ServerChannel].
+/// state
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct ChannelOverwrite {
+    /// version
+    pub version: StateVersion,
+    /// channel
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub container: Pubkey,
+    /// [ServerChannelV2::overwrites] index used to derive address
+    pub index: u64,
+    /// to
+    pub target: OverwriteTarget,
+    /// only for [OverwriteTarget::Dweller]
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub dweller: Pubkey,
+    /// permission bits granted
+    pub allow: u64,
+    /// permission bits revoked
+    pub deny: u64,
+}
+
+impl ChannelOverwrite {
+    /// data size
+    pub const LEN: u64 = 90;
+    /// entity type used for seed
+    pub const SEED: &'static str = "ChannelOverwrite";
+
+    /// see channel and its history
+    pub const READ: u64 = 1;
+    /// post into channel
+    pub const WRITE: u64 = 1 << 1;
+    /// change channel and its overwrites
+    pub const MANAGE: u64 = 1 << 2;
+    /// all permission bits
+    pub const ALL: u64 = Self::READ | Self::WRITE | Self::MANAGE;
+
+    /// true if overwrite applies to same target
+    pub fn same_target(&self, target: OverwriteTarget, dweller: &Pubkey) -> bool {
+        self.target == target && (target != OverwriteTarget::Dweller || self.dweller == *dweller)
+    }
+
+    fn apply(&self, permissions: u64) -> u64 {
+        (permissions & !self.deny) | self.allow
+    }
+}
+
+impl IndexedEntry for ChannelOverwrite {
+    const ALLOCATED_LEN: u64 = ChannelOverwrite::LEN;
+}
+
+/// Resolves [ChannelOverwrite] permission bits of dweller in channel.
+/// Server owner always has [ChannelOverwrite::ALL].
+/// Otherwise members start with read and write, administrators with all bits,
+/// then overwrites for everyone, administrators and the dweller apply in that order.
+pub fn resolve_channel_permissions(
+    dweller: &Pubkey,
+    is_owner: bool,
+    is_administrator: bool,
+    overwrites: &[ChannelOverwrite],
+) -> u64 {
+    if is_owner {
+        return ChannelOverwrite::ALL;
+    }
+
+    let mut permissions = if is_administrator {
+        ChannelOverwrite::ALL
+    } else {
+        ChannelOverwrite::READ | ChannelOverwrite::WRITE
+    };
+
+    let initialized = || {
+        overwrites
+            .iter()
+            .filter(|x| x.version != StateVersion::Uninitialized)
+    };
+    for overwrite in initialized().filter(|x| x.target == OverwriteTarget::Everyone) {
+        permissions = overwrite.apply(permissions);
+    }
+    if is_administrator {
+        for overwrite in initialized().filter(|x| x.target == OverwriteTarget::Administrators) {
+            permissions = overwrite.apply(permissions);
+        }
+    }
+    for overwrite in initialized().filter(|x| x.same_target(OverwriteTarget::Dweller, dweller)) {
+        permissions = overwrite.apply(permissions);
+    }
+
+    permissions
+}
+
+/// Checkpoint counter and delegated committer of [ServerChannel].
+/// Has program derived address from [ServerChannel] with index 0.
+/// state
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct ChannelHistory {
+    /// version
+    pub version: StateVersion,
+    /// channel
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub container: Pubkey,
+    /// index
+    pub checkpoints: u64,
+    /// [ChannelCheckpoint::messages] of last checkpoint
+    pub messages: u64,
+    /// bot allowed to commit checkpoints besides administrators, default key if none
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub committer: Pubkey,
+}
+
+impl ChannelHistory {
+    /// data size
+    pub const LEN: u64 = 81;
+    /// entity type used for seed
+    pub const SEED: &'static str = "ChannelHistory";
+}
+
+/// Merkle root of off chain message log of [ServerChannel], see [crate::merkle].
+/// Has program derived address from [ServerChannel].
+/// state
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct ChannelCheckpoint {
+    /// version
+    pub version: StateVersion,
+    /// channel
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub container: Pubkey,
+    /// [ChannelHistory::checkpoints] index used to derive address, sequence number
+    pub index: u64,
+    /// root of all messages up to checkpoint
+    pub root: [u8; 32],
+    /// messages in log
+    pub messages: u64,
+    /// slot of commit
+    pub slot: u64,
+    /// unix timestamp of commit
+    pub unix_timestamp: i64,
+}
+
+impl ChannelCheckpoint {
+    /// data size
+    pub const LEN: u64 = 97;
+    /// entity type used for seed
+    pub const SEED: &'static str = "ChannelCheckpoint";
+
+    /// true if message at `index` of log is included into [Self::root]
+    pub fn verify_inclusion(&self, message: &[u8], index: u64, proof: &[[u8; 32]]) -> bool {
+        index < self.messages && crate::merkle::verify_inclusion(&self.root, message, index, proof)
+    }
+}
+
+/// Event counter of [Server].
+/// Has program derived address from [Server] with index 0.
+/// state
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct ServerEvents {
+    /// version
+    pub version: StateVersion,
+    /// server
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub container: Pubkey,
+    /// index
+    pub events: u64,
+}
+
+impl ServerEvents {
+    /// data size
+    pub const LEN: u64 = 41;
+    /// entity type used for seed
+    pub const SEED: &'static str = "ServerEvents";
+}
+
+/// Scheduled voice session, AMA or similar announced in [Server].
+/// Has program derived address from [Server].
+/// state
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct ServerEvent {
+    /// version
+    pub version: StateVersion,
+    /// server
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub container: Pubkey,
+    /// [ServerEvents::events] index used to derive address
+    pub index: u64,
+    /// title
+    pub title: [u8; 32],
+    /// Multihash referencing IPFS hash of description
+    pub description_hash: [u8; 64],
+    /// unix timestamp
+    pub start: i64,
+    /// unix timestamp
+    pub end: i64,
+    /// [ServerChannel] event takes place in, default key if none
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub channel: Pubkey,
+    /// index of [EventRsvp]
+    pub attendees: u64,
+    /// cancelled events accept no more RSVPs
+    pub cancelled: bool,
+}
+
+impl ServerEvent {
+    /// data size
+    pub const LEN: u64 = 194;
+    /// entity type used for seed
+    pub const SEED: &'static str = "ServerEvent";
+}
+
+/// Attendance of dweller at [ServerEvent].
+/// Has program derived address from [ServerEvent].
+/// state
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct EventRsvp {
+    /// version
+    pub version: StateVersion,
+    /// event
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub container: Pubkey,
+    /// [ServerEvent::attendees] index used to derive address
+    pub index: u64,
+    /// attending
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub dweller: Pubkey,
+}
+
+impl EventRsvp {
+    /// data size
+    pub const LEN: u64 = 73;
+    /// entity type used for seed
+    pub const SEED: &'static str = "EventRsvp";
+}
+
+impl IndexedEntry for EventRsvp {
+    const ALLOCATED_LEN: u64 = EventRsvp::LEN;
+}
+
+/// state, prefix of [ServerGroupV2]
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct ServerGroup {
+    /// version
+    pub version: StateVersion,
+    /// server
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub container: Pubkey,
+    /// [Server::groups] index used to derive address
+    pub index: u64,
+
+    /// name
+    pub name: [u8; 32],
+
+    /// index
+    pub channels: u64,
+}
+
+impl ServerGroup {
+    /// data size
+    pub const LEN: u64 = 81;
+    /// entity type used for seed
+    pub const SEED: &'static str = "ServerGroup";
+}
+
+impl IndexedEntry for ServerGroup {
+    const ALLOCATED_LEN: u64 = ServerGroupV2::LEN;
+}
+
+/// state of [StateVersion::V2] group
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct ServerGroupV2 {
+    /// version
+    pub version: StateVersion,
+    /// server
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub container: Pubkey,
+    /// [Server::groups] index used to derive address
+    pub index: u64,
+
+    /// name
+    pub name: [u8; 32],
+
+    /// index
+    pub channels: u64,
+    /// [GroupChannelV2::position] given to next added channel
+    pub next_position: u64,
+}
+
+impl ServerGroupV2 {
+    /// data size
+    pub const LEN: u64 = 89;
+}
+
+/// state, prefix of [GroupChannelV2]
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct GroupChannel {
+    /// version
+    pub version: StateVersion,
+    /// group
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub container: Pubkey,
+    /// [Group::channels] index used to derive address
+    pub index: u64,
+
+    /// to
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub channel: Pubkey,
+}
+
+impl GroupChannel {
+    /// data size
+    pub const LEN: u64 = 73;
+
+    /// entity type used for seed
+    pub const SEED: &'static str = "GroupChannel";
+}
+
+impl IndexedEntry for GroupChannel {
+    const ALLOCATED_LEN: u64 = GroupChannelV2::LEN;
+}
+
+/// state of channel in [StateVersion::V2] group
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, BorshSchema, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct GroupChannelV2 {
+    /// version
+    pub version: StateVersion,
+    /// group
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub container: Pubkey,
+    /// [ServerGroupV2::channels] index used to derive address
+    pub index: u64,
+
+    /// to
+    #[cfg_attr(feature = "fuzz", arbitrary(with = crate::fuzz::pubkey))]
+    pub channel: Pubkey,
+    /// UI ordering, unique in group and kept on removals
+    pub position: u64,
+}
+
+impl GroupChannelV2 {
+    /// data size
+    pub const LEN: u64 = 81;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
     fn len() {
+        assert_eq!(
+            ListEntry::LEN,
+            solana_program::borsh::get_packed_len::<ListEntry>() as u64
+        );
+        assert_eq!(
+            RentPayer::LEN,
+            solana_program::borsh::get_packed_len::<RentPayer>() as u64
+        );
         assert_eq!(
             DwellerServer::LEN,
             solana_program::borsh::get_packed_len::<DwellerServer>() as u64
@@ -283,6 +1624,10 @@ mod test {
             Server::LEN,
             solana_program::borsh::get_packed_len::<Server>() as u64
         );
+        assert_eq!(
+            ServerV2::LEN,
+            solana_program::borsh::get_packed_len::<ServerV2>() as u64
+        );
         assert_eq!(
             ServerAdministrator::LEN,
             solana_program::borsh::get_packed_len::<ServerAdministrator>() as u64
@@ -291,6 +1636,10 @@ mod test {
             ServerChannel::LEN,
             solana_program::borsh::get_packed_len::<ServerChannel>() as u64
         );
+        assert_eq!(
+            ServerChannelV2::LEN,
+            solana_program::borsh::get_packed_len::<ServerChannelV2>() as u64
+        );
         assert_eq!(
             ServerGroup::LEN,
             solana_program::borsh::get_packed_len::<ServerGroup>() as u64
@@ -307,5 +1656,239 @@ mod test {
             GroupChannel::LEN,
             solana_program::borsh::get_packed_len::<GroupChannel>() as u64
         );
+        assert_eq!(
+            JoinRequest::LEN,
+            solana_program::borsh::get_packed_len::<JoinRequest>() as u64
+        );
+        assert_eq!(Dweller::LEN, std::mem::size_of::<DwellerPod>() as u64);
+        assert_eq!(Server::LEN, std::mem::size_of::<ServerPod>() as u64);
+        assert_eq!(Server::LEN + 9, std::mem::size_of::<ServerV2Pod>() as u64);
+        let dweller = Dweller {
+            version: StateVersion::V1,
+            servers: 0,
+            name: [0; 32],
+            photo_hash: [0; 64],
+            status: [0; 32],
+        };
+        assert_eq!(
+            DwellerV2::MIN_LEN,
+            DwellerV2::from_v1(dweller, Pubkey::default())
+                .try_to_vec()
+                .unwrap()
+                .len() as u64
+        );
+        assert_eq!(
+            ServerMemberV2::LEN,
+            solana_program::borsh::get_packed_len::<ServerMemberV2>() as u64
+        );
+        assert_eq!(
+            ChannelHistory::LEN,
+            solana_program::borsh::get_packed_len::<ChannelHistory>() as u64
+        );
+        assert_eq!(
+            ChannelCheckpoint::LEN,
+            solana_program::borsh::get_packed_len::<ChannelCheckpoint>() as u64
+        );
+        assert_eq!(
+            AuditEntry::LEN,
+            solana_program::borsh::get_packed_len::<AuditEntry>() as u64
+        );
+        assert_eq!(
+            ServerAuditLog::HEADER_LEN,
+            solana_program::borsh::get_packed_len::<ServerAuditLog>() as u64
+        );
+        assert_eq!(
+            ServerV3::LEN,
+            solana_program::borsh::get_packed_len::<ServerV3>() as u64
+        );
+        assert_eq!(
+            ServerMemberV3::LEN,
+            solana_program::borsh::get_packed_len::<ServerMemberV3>() as u64
+        );
+        assert_eq!(
+            ServerV4::LEN,
+            solana_program::borsh::get_packed_len::<ServerV4>() as u64
+        );
+        assert_eq!(
+            ServerMemberV4::LEN,
+            solana_program::borsh::get_packed_len::<ServerMemberV4>() as u64
+        );
+        assert_eq!(
+            ServerEvents::LEN,
+            solana_program::borsh::get_packed_len::<ServerEvents>() as u64
+        );
+        assert_eq!(
+            ServerEvent::LEN,
+            solana_program::borsh::get_packed_len::<ServerEvent>() as u64
+        );
+        assert_eq!(
+            EventRsvp::LEN,
+            solana_program::borsh::get_packed_len::<EventRsvp>() as u64
+        );
+        assert_eq!(
+            DwellerHandle::LEN,
+            solana_program::borsh::get_packed_len::<DwellerHandle>() as u64
+        );
+        assert_eq!(
+            DwellerMarker::LEN,
+            solana_program::borsh::get_packed_len::<DwellerMarker>() as u64
+        );
+        assert_eq!(
+            ServerDirectory::LEN,
+            solana_program::borsh::get_packed_len::<ServerDirectory>() as u64
+        );
+        assert_eq!(
+            DirectoryEntry::LEN,
+            solana_program::borsh::get_packed_len::<DirectoryEntry>() as u64
+        );
+        assert_eq!(
+            ServerGroupV2::LEN,
+            solana_program::borsh::get_packed_len::<ServerGroupV2>() as u64
+        );
+        assert_eq!(
+            GroupChannelV2::LEN,
+            solana_program::borsh::get_packed_len::<GroupChannelV2>() as u64
+        );
+        assert_eq!(
+            ChannelOverwrite::LEN,
+            solana_program::borsh::get_packed_len::<ChannelOverwrite>() as u64
+        );
+    }
+
+    #[test]
+    fn zero_copy() {
+        let owner = Pubkey::new_unique();
+        let server = ServerV2 {
+            base: Server {
+                version: StateVersion::V2,
+                owner,
+                name: [13; 32],
+                photo_hash: [0; 64],
+                db_hash: [0; 64],
+                members: 3,
+                member_statuses: 4,
+                administrators: 5,
+                channels: 6,
+                groups: 7,
+            },
+            join_policy: JoinPolicy::ApprovalRequired,
+            join_requests: 8,
+            description_hash: [0; 64],
+            banner_hash: [0; 64],
+            listed: true,
+        };
+        let mut data = server.try_to_vec().unwrap();
+
+        let view: &mut ServerV2Pod =
+            bytemuck::from_bytes_mut(&mut data[..std::mem::size_of::<ServerV2Pod>()]);
+        assert_eq!(view.server.version, StateVersion::V2 as u8);
+        assert_eq!(view.server.owner, owner);
+        assert_eq!(u64::from(view.server.members), 3);
+        assert_eq!(u64::from(view.server.groups), 7);
+        assert_eq!(view.join_policy, JoinPolicy::ApprovalRequired as u8);
+        assert_eq!(u64::from(view.join_requests), 8);
+        view.server.channels = 9.into();
+        view.join_requests = 10.into();
+
+        let server = ServerV2::try_from_slice(&data).unwrap();
+        assert_eq!(server.channels, 9);
+        assert_eq!(server.join_requests, 10);
+        assert!(server.listed);
+    }
+
+    #[test]
+    fn audit_log_order() {
+        let mut data = vec![0; ServerAuditLog::LEN as usize];
+        let mut log = ServerAuditLog {
+            version: StateVersion::V1,
+            ..Default::default()
+        };
+        let appended = ServerAuditLog::CAPACITY + 3;
+        for x in 0..appended {
+            let offset = log.next_offset();
+            let entry = AuditEntry {
+                unix_timestamp: x as i64,
+                ..Default::default()
+            };
+            data[offset..offset + AuditEntry::LEN as usize]
+                .copy_from_slice(&entry.try_to_vec().unwrap());
+            log.appended += 1;
+        }
+        data[..ServerAuditLog::HEADER_LEN as usize].copy_from_slice(&log.try_to_vec().unwrap());
+
+        let (log, entries) = decode_server_audit_log(&data).unwrap();
+        assert_eq!(log.appended, appended);
+        assert_eq!(entries.len() as u64, ServerAuditLog::CAPACITY);
+        assert_eq!(entries[0].unix_timestamp, 3);
+        assert_eq!(entries.last().unwrap().unix_timestamp, appended as i64 - 1);
+    }
+
+    #[test]
+    fn handle() {
+        let program_id = Pubkey::new_unique();
+        assert_eq!(
+            DwellerHandle::find_address(&program_id, "Alice_1").unwrap(),
+            DwellerHandle::find_address(&program_id, "alice_1").unwrap()
+        );
+        assert_ne!(
+            DwellerHandle::find_address(&program_id, "alice").unwrap(),
+            DwellerHandle::find_address(&program_id, "alice_").unwrap()
+        );
+        for invalid in ["", "al ice", "alice!", "älice", &"a".repeat(33)] {
+            assert!(DwellerHandle::normalize(invalid).is_err());
+        }
+        assert!(DwellerHandle::normalize(&"a".repeat(32)).is_ok());
+    }
+
+    #[test]
+    fn channel_permissions() {
+        let dweller = Pubkey::new_unique();
+        let overwrite = |target, dweller, allow, deny| ChannelOverwrite {
+            version: StateVersion::V1,
+            target,
+            dweller,
+            allow,
+            deny,
+            ..Default::default()
+        };
+
+        // read only #rules
+        let rules = [overwrite(
+            OverwriteTarget::Everyone,
+            Pubkey::default(),
+            0,
+            ChannelOverwrite::WRITE,
+        )];
+        assert_eq!(
+            resolve_channel_permissions(&dweller, false, false, &rules),
+            ChannelOverwrite::READ
+        );
+        assert_eq!(
+            resolve_channel_permissions(&dweller, false, true, &rules),
+            ChannelOverwrite::READ | ChannelOverwrite::MANAGE
+        );
+
+        // #mod-log visible to listed dweller only
+        let mod_log = [
+            overwrite(
+                OverwriteTarget::Everyone,
+                Pubkey::default(),
+                0,
+                ChannelOverwrite::ALL,
+            ),
+            overwrite(OverwriteTarget::Dweller, dweller, ChannelOverwrite::READ, 0),
+        ];
+        assert_eq!(
+            resolve_channel_permissions(&dweller, false, false, &mod_log),
+            ChannelOverwrite::READ
+        );
+        assert_eq!(
+            resolve_channel_permissions(&Pubkey::new_unique(), false, false, &mod_log),
+            0
+        );
+        assert_eq!(
+            resolve_channel_permissions(&Pubkey::new_unique(), true, false, &mod_log),
+            ChannelOverwrite::ALL
+        );
     }
 }
@@ -1,7 +1,7 @@
 #![cfg(feature = "test-bpf")]
 
 use borsh::BorshDeserialize;
-use solana_program::{pubkey::Pubkey, system_instruction};
+use solana_program::{program_pack::Pack, pubkey::Pubkey, system_instruction};
 use solana_program_test::*;
 use solana_sdk::{
     account::Account,
@@ -13,17 +13,30 @@ use solana_sdk::{
 use satellite_servers::{
     id,
     instruction::{
-        self, AddChannelInput, CreateGroupInput, InitializeDwellerInput, InitializeServerInput,
+        self, AddChannelInput, ClaimHandleInput, CommitChannelCheckpointInput, CreateGroupInput,
+        CreateServerEventInput, InitializeDwellerInput, InitializeServerInput, ListServerInput,
+        SetChannelCommitterInput, SetChannelOverwriteInput, SetHashInput, SetJoinFeeInput,
+        SetJoinPolicyInput, SetJoinRequirementInput, SetNameInput, UpdateChannelInput,
+        UpdateDwellerProfileInput, UpdateServerProfileInput, WithdrawTreasuryInput,
     },
-    processor,
+    merkle, processor,
     state::*,
 };
 
 use sdk::{
     add_channel_to_group_transaction, add_channel_transaction, add_invite_transaction,
-    create_group_transaction, delete_channel_transaction, delete_group_transaction,
-    join_server_transaction, leave_server_transaction, remove_admin_transaction,
-    remove_channel_from_group_transaction, revoke_invite_server_transaction,
+    approve_join_request_transaction, commit_channel_checkpoint_transaction,
+    create_group_transaction, create_server_event_transaction, decline_invite_transaction,
+    delete_channel_transaction, delete_group_transaction, join_server_transaction,
+    leave_server_transaction, list_server_transaction, migrate_dweller_transaction,
+    move_group_channel_transaction, recheck_member_transaction, remove_admin_transaction,
+    remove_channel_from_group_transaction, remove_channel_overwrite_transaction,
+    rename_group_transaction, request_join_server_transaction, revoke_invite_server_transaction,
+    rsvp_transaction, set_channel_committer_transaction, set_channel_overwrite_transaction,
+    set_dweller_authority_transaction, set_join_fee_transaction, set_join_policy_transaction,
+    set_join_requirement_transaction, set_member_nickname_transaction,
+    set_server_photo_transaction, unlist_server_transaction, update_channel_transaction,
+    update_dweller_profile_transaction, update_server_profile_transaction,
 };
 
 pub fn program_test() -> ProgramTest {
@@ -101,8 +114,286 @@ async fn dweller_flow() {
 
     assert_eq!(
         dweller_server_info_data.data.len(),
-        DwellerServer::LEN as usize
+        (DwellerServer::ALLOCATED_LEN + RentPayer::LEN) as usize
     );
+    assert_eq!(
+        RentPayer::read::<DwellerServer>(&dweller_server_info_data.data),
+        Some(program_context.payer.pubkey())
+    );
+
+    let trx = migrate_dweller_transaction(
+        &program_context.payer,
+        &dweller,
+        program_context.last_blockhash,
+    );
+    program_context
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let dweller_data = get_account(&mut program_context, &dweller.pubkey()).await;
+    assert_eq!(dweller_data.data.len(), DwellerV2::MIN_LEN as usize);
+    let dweller_state: DwellerV2 = get_account_data(&mut program_context, &dweller.pubkey()).await;
+    assert_eq!(dweller_state.version, StateVersion::V2);
+    assert_eq!(dweller_state.name, [42; 32]);
+
+    let input = UpdateDwellerProfileInput {
+        banner_hash: [7; 64],
+        bio: "hello from satellite".to_string(),
+        pronouns: "they/them".to_string(),
+        links: vec!["https://satellite.im".to_string()],
+    };
+    let trx = update_dweller_profile_transaction(
+        &program_context.payer,
+        &dweller,
+        &input,
+        program_context.last_blockhash,
+    );
+    program_context
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let dweller_data = get_account(&mut program_context, &dweller.pubkey()).await;
+    assert!(dweller_data.lamports >= rent.minimum_balance(dweller_data.data.len()));
+    let dweller_state: DwellerV2 = get_account_data(&mut program_context, &dweller.pubkey()).await;
+    assert_eq!(dweller_state.bio, input.bio);
+    assert_eq!(dweller_state.pronouns, input.pronouns);
+    assert_eq!(dweller_state.links, input.links);
+    assert_eq!(dweller_state.banner_hash, [7; 64]);
+
+    let trx = update_dweller_profile_transaction(
+        &program_context.payer,
+        &dweller,
+        &UpdateDwellerProfileInput {
+            bio: "x".repeat(DwellerV2::MAX_BIO_LEN + 1),
+            ..input.clone()
+        },
+        program_context.last_blockhash,
+    );
+    assert!(program_context
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .is_err());
+
+    let trx = update_dweller_profile_transaction(
+        &program_context.payer,
+        &dweller,
+        &UpdateDwellerProfileInput {
+            banner_hash: [0; 64],
+            bio: String::new(),
+            pronouns: String::new(),
+            links: Vec::new(),
+        },
+        program_context.last_blockhash,
+    );
+    program_context
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let dweller_data = get_account(&mut program_context, &dweller.pubkey()).await;
+    assert_eq!(dweller_data.data.len(), DwellerV2::MIN_LEN as usize);
+    assert_eq!(
+        dweller_data.lamports,
+        rent.minimum_balance(DwellerV2::MIN_LEN as usize)
+    );
+
+    let authority = Keypair::new();
+    let trx = set_dweller_authority_transaction(
+        &program_context.payer,
+        &dweller,
+        &dweller.pubkey(),
+        &authority.pubkey(),
+        program_context.last_blockhash,
+    );
+    program_context
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let dweller_state: DwellerV2 = get_account_data(&mut program_context, &dweller.pubkey()).await;
+    assert_eq!(dweller_state.authority, authority.pubkey());
+
+    let mut transaction = Transaction::new_with_payer(
+        &[
+            instruction::set_dweller_name(&dweller.pubkey(), &SetNameInput { name: [43; 32] })
+                .unwrap(),
+        ],
+        Some(&program_context.payer.pubkey()),
+    );
+    transaction.sign(
+        &[&program_context.payer, &dweller],
+        program_context.last_blockhash,
+    );
+    assert!(program_context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .is_err());
+
+    let mut transaction = Transaction::new_with_payer(
+        &[instruction::with_dweller_authority(
+            instruction::set_dweller_name(&dweller.pubkey(), &SetNameInput { name: [44; 32] })
+                .unwrap(),
+            &dweller.pubkey(),
+            &authority.pubkey(),
+        )],
+        Some(&program_context.payer.pubkey()),
+    );
+    transaction.sign(
+        &[&program_context.payer, &authority],
+        program_context.last_blockhash,
+    );
+    program_context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+
+    let dweller_state: DwellerV2 = get_account_data(&mut program_context, &dweller.pubkey()).await;
+    assert_eq!(dweller_state.name, [44; 32]);
+    assert_eq!(dweller_state.authority, authority.pubkey());
+
+    // handles
+    let (dweller_handle, _) = DwellerHandle::find_address(&id(), "alice").unwrap();
+    let mut transaction = Transaction::new_with_payer(
+        &[instruction::with_dweller_authority(
+            instruction::claim_handle(
+                &dweller.pubkey(),
+                &program_context.payer.pubkey(),
+                &ClaimHandleInput {
+                    handle: "Alice".to_string(),
+                },
+            )
+            .unwrap(),
+            &dweller.pubkey(),
+            &authority.pubkey(),
+        )],
+        Some(&program_context.payer.pubkey()),
+    );
+    transaction.sign(
+        &[&program_context.payer, &authority],
+        program_context.last_blockhash,
+    );
+    program_context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+
+    let dweller_state: DwellerV3 = get_account_data(&mut program_context, &dweller.pubkey()).await;
+    assert_eq!(dweller_state.version, StateVersion::V3);
+    assert_eq!(dweller_state.handle, dweller_handle);
+    assert_eq!(dweller_state.authority, authority.pubkey());
+    let handle_state: DwellerHandle = get_account_data(&mut program_context, &dweller_handle).await;
+    assert_eq!(handle_state.dweller, dweller.pubkey());
+    assert_eq!(&handle_state.handle[..6], b"alice\0");
+
+    let other = Keypair::new();
+    test_initialize_dweller(
+        &program_context.payer,
+        &other,
+        rent,
+        program_context.last_blockhash,
+        &mut program_context.banks_client,
+    )
+    .await;
+    let trx = migrate_dweller_transaction(
+        &program_context.payer,
+        &other,
+        program_context.last_blockhash,
+    );
+    program_context
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let mut transaction = Transaction::new_with_payer(
+        &[instruction::claim_handle(
+            &other.pubkey(),
+            &program_context.payer.pubkey(),
+            &ClaimHandleInput {
+                handle: "ALICE".to_string(),
+            },
+        )
+        .unwrap()],
+        Some(&program_context.payer.pubkey()),
+    );
+    transaction.sign(
+        &[&program_context.payer, &other],
+        program_context.last_blockhash,
+    );
+    assert!(program_context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .is_err());
+
+    let mut transaction = Transaction::new_with_payer(
+        &[instruction::with_dweller_authority(
+            instruction::transfer_handle(
+                &dweller.pubkey(),
+                &dweller_handle,
+                &other.pubkey(),
+                &program_context.payer.pubkey(),
+            )
+            .unwrap(),
+            &dweller.pubkey(),
+            &authority.pubkey(),
+        )],
+        Some(&program_context.payer.pubkey()),
+    );
+    transaction.sign(
+        &[&program_context.payer, &authority, &other],
+        program_context.last_blockhash,
+    );
+    program_context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+
+    let dweller_state: DwellerV3 = get_account_data(&mut program_context, &dweller.pubkey()).await;
+    assert_eq!(dweller_state.handle, Pubkey::default());
+    let dweller_state: DwellerV3 = get_account_data(&mut program_context, &other.pubkey()).await;
+    assert_eq!(dweller_state.handle, dweller_handle);
+    let handle_state: DwellerHandle = get_account_data(&mut program_context, &dweller_handle).await;
+    assert_eq!(handle_state.dweller, other.pubkey());
+
+    let mut transaction = Transaction::new_with_payer(
+        &[instruction::release_handle(
+            &other.pubkey(),
+            &dweller_handle,
+            &program_context.payer.pubkey(),
+        )
+        .unwrap()],
+        Some(&program_context.payer.pubkey()),
+    );
+    transaction.sign(
+        &[&program_context.payer, &other],
+        program_context.last_blockhash,
+    );
+    program_context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+
+    let dweller_state: DwellerV3 = get_account_data(&mut program_context, &other.pubkey()).await;
+    assert_eq!(dweller_state.handle, Pubkey::default());
+    assert!(program_context
+        .banks_client
+        .get_account(dweller_handle)
+        .await
+        .unwrap()
+        .is_none());
 }
 
 #[tokio::test]
@@ -153,8 +444,7 @@ async fn positive_add_remove_flow() {
         dweller_servers.push(address_to_create);
     }
 
-    let [dweller_owner, dweller_admin_1, _dweller_admin_2, _dweller_admin_3, dweller_1, _dweller_2, _dweller_3] =
-        dwellers;
+    let [dweller_owner, dweller_admin_1, _, _, dweller_1, dweller_2, dweller_3] = dwellers;
 
     // create server
     let server = Keypair::new();
@@ -250,11 +540,72 @@ async fn positive_add_remove_flow() {
         assert_eq!(account_state.container, Pubkey::default(),);
     }
 
+    // administrator which is not member is rejected
+    let trx = add_invite_transaction(
+        &blockchain.payer,
+        &server.pubkey(),
+        &dweller_admin_1,
+        &server_administrators[0],
+        &server_members[0],
+        &dweller_1.pubkey(),
+        &server_member_statuses[0],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap_err();
+
+    test_add_administrator(
+        &blockchain.payer,
+        &dweller_owner,
+        &dweller_owner.pubkey(),
+        &server.pubkey(),
+        &server_administrators[1],
+        blockchain.last_blockhash,
+        &mut blockchain.banks_client,
+    )
+    .await;
+
+    let trx = add_invite_transaction(
+        &blockchain.payer,
+        &server.pubkey(),
+        &dweller_owner,
+        &server_administrators[1],
+        &server_members[0],
+        &dweller_admin_1.pubkey(),
+        &server_member_statuses[0],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let trx = join_server_transaction(
+        &blockchain.payer,
+        &server.pubkey(),
+        &server_members[1],
+        &server_member_statuses[0],
+        &dweller_admin_1,
+        &dweller_servers[1],
+        &server_member_statuses[0],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
     let trx = add_invite_transaction(
         &blockchain.payer,
         &server.pubkey(),
         &dweller_admin_1,
         &server_administrators[0],
+        &server_members[1],
         &dweller_1.pubkey(),
         &server_member_statuses[0],
         blockchain.last_blockhash,
@@ -274,10 +625,11 @@ async fn positive_add_remove_flow() {
     let trx = join_server_transaction(
         &blockchain.payer,
         &server.pubkey(),
-        &server_members[1],
+        &server_members[2],
         &server_member_statuses[0],
         &dweller_1,
         &dweller_servers[4],
+        &server_member_statuses[0],
         blockchain.last_blockhash,
     );
 
@@ -288,9 +640,9 @@ async fn positive_add_remove_flow() {
         .unwrap();
 
     let account_state: ServerMemberStatus =
-        get_account_data(&mut blockchain, &server_members[1]).await;
+        get_account_data(&mut blockchain, &server_members[2]).await;
     assert_eq!(account_state.container, server.pubkey());
-    assert_eq!(account_state.index, 1);
+    assert_eq!(account_state.index, 2);
     assert_eq!(account_state.dweller, dweller_1.pubkey());
 
     let account_state: DwellerServer = get_account_data(&mut blockchain, &dweller_servers[4]).await;
@@ -299,7 +651,8 @@ async fn positive_add_remove_flow() {
     assert_eq!(account_state.index, 0);
 
     let account_state: Server = get_account_data(&mut blockchain, &server.pubkey()).await;
-    assert_eq!(account_state.members, 2);
+    assert_eq!(account_state.members, 3);
+    assert_eq!(account_state.member_statuses, 0);
 
     // groups and channels
 
@@ -363,6 +716,7 @@ async fn positive_add_remove_flow() {
         &blockchain.payer,
         &dweller_admin_1,
         &server_administrators[0],
+        &server_members[1],
         &server.pubkey(),
         &server_groups[0],
         &CreateGroupInput { name: [66; 32] },
@@ -378,11 +732,12 @@ async fn positive_add_remove_flow() {
         &blockchain.payer,
         &dweller_admin_1,
         &server_administrators[0],
+        &server_members[1],
         &server.pubkey(),
         &server_channels[0],
         &AddChannelInput {
             name: [66; 32],
-            type_id: 17,
+            type_id: ChannelKind::Voice as u8,
         },
         blockchain.last_blockhash,
     );
@@ -392,14 +747,24 @@ async fn positive_add_remove_flow() {
         .await
         .unwrap();
 
-    let trx = add_channel_to_group_transaction(
+    let trx = update_channel_transaction(
         &blockchain.payer,
-        &server.pubkey(),
         &dweller_admin_1,
         &server_administrators[0],
+        &server_members[1],
+        &server.pubkey(),
         &server_channels[0],
-        &server_groups[0],
-        &group_channels[0],
+        &[],
+        &UpdateChannelInput {
+            type_id: ChannelKind::Voice as u8,
+            name: [67; 32],
+            topic_hash: [1; 64],
+            position: 3,
+            voice: VoiceChannelConfig {
+                user_limit: 10,
+                bitrate: 96_000,
+            },
+        },
         blockchain.last_blockhash,
     );
     blockchain
@@ -408,21 +773,38 @@ async fn positive_add_remove_flow() {
         .await
         .unwrap();
 
-    let account_state: ServerGroup = get_account_data(&mut blockchain, &server_groups[0]).await;
-    assert_eq!(account_state.channels, 1);
-
-    // removing/deleting
+    let account_state: ServerChannelV2 =
+        get_account_data(&mut blockchain, &server_channels[0]).await;
+    assert_eq!(account_state.version, StateVersion::V2);
+    assert_eq!(account_state.kind, ChannelKind::Voice);
+    assert_eq!(account_state.name, [67; 32]);
+    assert_eq!(account_state.position, 3);
+    assert_eq!(account_state.voice.user_limit, 10);
 
-    // groups/channels
+    let channel_overwrite = create_derived_account_index(
+        &mut blockchain,
+        &server_channels[0],
+        ChannelOverwrite::SEED,
+        0,
+        instruction::AddressTypeInput::ChannelOverwrite(0),
+    )
+    .await;
 
-    let trx = remove_channel_from_group_transaction(
+    let trx = set_channel_overwrite_transaction(
         &blockchain.payer,
-        &server.pubkey(),
         &dweller_admin_1,
         &server_administrators[0],
-        &server_groups[0],
-        &group_channels[0],
-        &group_channels[0],
+        &server_members[1],
+        &server.pubkey(),
+        &server_channels[0],
+        &channel_overwrite,
+        &[],
+        &SetChannelOverwriteInput {
+            target: OverwriteTarget::Everyone,
+            dweller: Pubkey::default(),
+            allow: 0,
+            deny: ChannelOverwrite::WRITE,
+        },
         blockchain.last_blockhash,
     );
     blockchain
@@ -431,17 +813,291 @@ async fn positive_add_remove_flow() {
         .await
         .unwrap();
 
-    let trx = delete_group_transaction(
+    let account_state: ServerChannelV2 =
+        get_account_data(&mut blockchain, &server_channels[0]).await;
+    assert_eq!(account_state.overwrites, 1);
+    let account_state: ChannelOverwrite =
+        get_account_data(&mut blockchain, &channel_overwrite).await;
+    assert_eq!(account_state.container, server_channels[0]);
+    assert_eq!(account_state.deny, ChannelOverwrite::WRITE);
+
+    let trx = update_channel_transaction(
         &blockchain.payer,
         &dweller_admin_1,
         &server_administrators[0],
+        &server_members[1],
         &server.pubkey(),
-        &server_groups[0],
-        &server_groups[0],
-        &group_channels[0],
+        &server_channels[0],
+        &[&channel_overwrite],
+        &UpdateChannelInput {
+            type_id: ChannelKind::Voice as u8,
+            name: [67; 32],
+            topic_hash: [1; 64],
+            position: 4,
+            voice: VoiceChannelConfig {
+                user_limit: 10,
+                bitrate: 96_000,
+            },
+        },
         blockchain.last_blockhash,
     );
-
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let trx = remove_channel_overwrite_transaction(
+        &blockchain.payer,
+        &dweller_admin_1,
+        &server_administrators[0],
+        &server_members[1],
+        &server.pubkey(),
+        &server_channels[0],
+        &channel_overwrite,
+        &channel_overwrite,
+        &[&channel_overwrite],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account_state: ServerChannelV2 =
+        get_account_data(&mut blockchain, &server_channels[0]).await;
+    assert_eq!(account_state.overwrites, 0);
+    assert_eq!(account_state.position, 4);
+
+    let channel_history = create_derived_account_index(
+        &mut blockchain,
+        &server_channels[0],
+        ChannelHistory::SEED,
+        0,
+        instruction::AddressTypeInput::ChannelHistory,
+    )
+    .await;
+    let mut channel_checkpoints = Vec::new();
+    for index in 0..2 {
+        let address_to_create = create_derived_account_index(
+            &mut blockchain,
+            &server_channels[0],
+            ChannelCheckpoint::SEED,
+            index,
+            instruction::AddressTypeInput::ChannelCheckpoint(index),
+        )
+        .await;
+        channel_checkpoints.push(address_to_create);
+    }
+
+    let messages: Vec<Vec<u8>> = (0..3u8).map(|x| vec![x; 10]).collect();
+    let leaves: Vec<_> = messages.iter().map(|x| merkle::leaf_hash(x)).collect();
+
+    let trx = commit_channel_checkpoint_transaction(
+        &blockchain.payer,
+        &dweller_admin_1,
+        &server_administrators[0],
+        &server_members[1],
+        &server.pubkey(),
+        &server_channels[0],
+        &channel_history,
+        &channel_checkpoints[0],
+        &CommitChannelCheckpointInput {
+            root: merkle::root(&leaves[..2]),
+            messages: 2,
+        },
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let bot = Keypair::new();
+    let trx = set_channel_committer_transaction(
+        &blockchain.payer,
+        &dweller_admin_1,
+        &server_administrators[0],
+        &server_members[1],
+        &server.pubkey(),
+        &server_channels[0],
+        &channel_history,
+        &SetChannelCommitterInput {
+            committer: bot.pubkey(),
+        },
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let trx = commit_channel_checkpoint_transaction(
+        &blockchain.payer,
+        &bot,
+        &server.pubkey(),
+        &server_members[0],
+        &server.pubkey(),
+        &server_channels[0],
+        &channel_history,
+        &channel_checkpoints[1],
+        &CommitChannelCheckpointInput {
+            root: merkle::root(&leaves),
+            messages: 3,
+        },
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account_state: ChannelHistory = get_account_data(&mut blockchain, &channel_history).await;
+    assert_eq!(account_state.checkpoints, 2);
+    assert_eq!(account_state.committer, bot.pubkey());
+    let account_state: ChannelCheckpoint =
+        get_account_data(&mut blockchain, &channel_checkpoints[1]).await;
+    assert_eq!(account_state.index, 1);
+    let proof = merkle::proof(&leaves, 2).unwrap();
+    assert!(account_state.verify_inclusion(&messages[2], 2, &proof));
+    assert!(!account_state.verify_inclusion(&messages[1], 2, &proof));
+
+    let trx = add_channel_to_group_transaction(
+        &blockchain.payer,
+        &server.pubkey(),
+        &dweller_admin_1,
+        &server_administrators[0],
+        &server_members[1],
+        &server_channels[0],
+        &server_groups[0],
+        &group_channels[0],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account_state: ServerGroup = get_account_data(&mut blockchain, &server_groups[0]).await;
+    assert_eq!(account_state.channels, 1);
+
+    let trx = add_channel_to_group_transaction(
+        &blockchain.payer,
+        &server.pubkey(),
+        &dweller_admin_1,
+        &server_administrators[0],
+        &server_members[1],
+        &server_channels[1],
+        &server_groups[0],
+        &group_channels[1],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let trx = move_group_channel_transaction(
+        &blockchain.payer,
+        &dweller_admin_1,
+        &server_administrators[0],
+        &server_members[1],
+        &server.pubkey(),
+        &server_groups[0],
+        &group_channels[0],
+        &group_channels[1],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account_state: GroupChannelV2 = get_account_data(&mut blockchain, &group_channels[0]).await;
+    assert_eq!(account_state.position, 1);
+    let account_state: GroupChannelV2 = get_account_data(&mut blockchain, &group_channels[1]).await;
+    assert_eq!(account_state.position, 0);
+
+    let trx = remove_channel_from_group_transaction(
+        &blockchain.payer,
+        &server.pubkey(),
+        &dweller_admin_1,
+        &server_administrators[0],
+        &server_members[1],
+        &server_groups[0],
+        &group_channels[1],
+        &group_channels[1],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let trx = rename_group_transaction(
+        &blockchain.payer,
+        &dweller_admin_1,
+        &server_administrators[0],
+        &server_members[1],
+        &server.pubkey(),
+        &server_groups[0],
+        &SetNameInput { name: [68; 32] },
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account_state: ServerGroupV2 = get_account_data(&mut blockchain, &server_groups[0]).await;
+    assert_eq!(account_state.name, [68; 32]);
+    assert_eq!(account_state.channels, 1);
+    assert_eq!(account_state.next_position, 2);
+    let account_state: GroupChannelV2 = get_account_data(&mut blockchain, &group_channels[0]).await;
+    assert_eq!(account_state.position, 1);
+
+    // removing/deleting
+
+    // groups/channels
+
+    let trx = remove_channel_from_group_transaction(
+        &blockchain.payer,
+        &server.pubkey(),
+        &dweller_admin_1,
+        &server_administrators[0],
+        &server_members[1],
+        &server_groups[0],
+        &group_channels[0],
+        &group_channels[0],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let trx = delete_group_transaction(
+        &blockchain.payer,
+        &dweller_admin_1,
+        &server_administrators[0],
+        &server_members[1],
+        &server.pubkey(),
+        &server_groups[0],
+        &server_groups[0],
+        &group_channels[0],
+        blockchain.last_blockhash,
+    );
+
     blockchain
         .banks_client
         .process_transaction(trx)
@@ -455,6 +1111,7 @@ async fn positive_add_remove_flow() {
         &blockchain.payer,
         &dweller_admin_1,
         &server_administrators[0],
+        &server_members[1],
         &server.pubkey(),
         &server_channels[0],
         &server_channels[0],
@@ -475,8 +1132,8 @@ async fn positive_add_remove_flow() {
     let trx = leave_server_transaction(
         &blockchain.payer,
         &server.pubkey(),
-        &server_members[1],
-        &server_members[1],
+        &server_members[2],
+        &server_members[2],
         &dweller_1,
         &dweller_servers[4],
         &dweller_servers[4],
@@ -490,18 +1147,32 @@ async fn positive_add_remove_flow() {
         .unwrap();
 
     let account_state: Server = get_account_data(&mut blockchain, &server.pubkey()).await;
-    assert_eq!(account_state.members, 1);
+    assert_eq!(account_state.members, 2);
 
-    let trx = revoke_invite_server_transaction(
+    let trx = add_invite_transaction(
         &blockchain.payer,
         &server.pubkey(),
         &dweller_admin_1,
         &server_administrators[0],
-        &server_member_statuses[0],
+        &server_members[1],
+        &dweller_2.pubkey(),
         &server_member_statuses[0],
         blockchain.last_blockhash,
     );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
 
+    let trx = decline_invite_transaction(
+        &blockchain.payer,
+        &server.pubkey(),
+        &dweller_2,
+        &server_member_statuses[0],
+        &server_member_statuses[0],
+        blockchain.last_blockhash,
+    );
     blockchain
         .banks_client
         .process_transaction(trx)
@@ -511,12 +1182,30 @@ async fn positive_add_remove_flow() {
     let account_state: Server = get_account_data(&mut blockchain, &server.pubkey()).await;
     assert_eq!(account_state.member_statuses, 0);
 
-    let trx = remove_admin_transaction(
+    let trx = add_invite_transaction(
         &blockchain.payer,
-        &dweller_owner,
         &server.pubkey(),
+        &dweller_admin_1,
         &server_administrators[0],
+        &server_members[1],
+        &dweller_3.pubkey(),
+        &server_member_statuses[0],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let trx = revoke_invite_server_transaction(
+        &blockchain.payer,
+        &server.pubkey(),
+        &dweller_admin_1,
         &server_administrators[0],
+        &server_members[1],
+        &server_member_statuses[0],
+        &server_member_statuses[0],
         blockchain.last_blockhash,
     );
 
@@ -527,169 +1216,3398 @@ async fn positive_add_remove_flow() {
         .unwrap();
 
     let account_state: Server = get_account_data(&mut blockchain, &server.pubkey()).await;
-    assert_eq!(account_state.administrators, 0);
-}
+    assert_eq!(account_state.member_statuses, 0);
 
-pub async fn create_derived_account_index(
-    blockchain: &mut ProgramTestContext,
-    owner: &Pubkey,
-    seed: &str,
-    index: u64,
-    address_type: instruction::AddressTypeInput,
-) -> Pubkey {
-    let (address_to_create, base_program_address, ..) =
-        satellite_servers::program::create_base_index_with_seed(&id(), seed, owner, index).unwrap();
-    test_create_derived_account(
-        blockchain,
-        owner,
-        &base_program_address,
-        &address_to_create,
-        address_type,
-    )
-    .await
-    .unwrap();
-    address_to_create
-}
-
-pub async fn test_initialize_dweller(
-    payer: &Keypair,
-    dweller_owner: &Keypair,
-    rent: solana_program::rent::Rent,
-    recent_blockhash: solana_program::hash::Hash,
-    blockchain: &mut BanksClient,
-) {
-    let mut transaction = Transaction::new_with_payer(
-        &[
-            system_instruction::create_account(
-                &payer.pubkey(),
-                &dweller_owner.pubkey(),
-                rent.minimum_balance(Dweller::LEN as usize),
-                Dweller::LEN as u64,
-                &satellite_servers::id(),
-            ),
-            instruction::initialize_dweller(
-                &dweller_owner.pubkey(),
-                InitializeDwellerInput { name: [42; 32] },
+    // administrator cannot leave keeping its entry
+    let trx = Transaction::new_signed_with_payer(
+        &[instruction::with_rent_receiver(
+            instruction::leave_server(
+                &server.pubkey(),
+                &server_members[1],
+                &server_members[1],
+                &dweller_admin_1.pubkey(),
+                &dweller_servers[1],
+                &dweller_servers[1],
             )
             .unwrap(),
-        ],
-        Some(&payer.pubkey()),
+            &blockchain.payer.pubkey(),
+        )],
+        Some(&blockchain.payer.pubkey()),
+        &[&blockchain.payer, &dweller_admin_1],
+        blockchain.last_blockhash,
     );
-    transaction.sign(&[&payer, dweller_owner], recent_blockhash);
-    blockchain.process_transaction(transaction).await.unwrap();
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap_err();
+
+    // leaving drops administrator entry, moving last one into it
+    let trx = Transaction::new_signed_with_payer(
+        &[instruction::with_rent_receiver(
+            instruction::with_server_administrator(
+                instruction::leave_server(
+                    &server.pubkey(),
+                    &server_members[1],
+                    &server_members[1],
+                    &dweller_admin_1.pubkey(),
+                    &dweller_servers[1],
+                    &dweller_servers[1],
+                )
+                .unwrap(),
+                &server_administrators[0],
+                &server_administrators[1],
+            ),
+            &blockchain.payer.pubkey(),
+        )],
+        Some(&blockchain.payer.pubkey()),
+        &[&blockchain.payer, &dweller_admin_1],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account_state: Server = get_account_data(&mut blockchain, &server.pubkey()).await;
+    assert_eq!(account_state.members, 1);
+    assert_eq!(account_state.administrators, 1);
+    let account_state: ServerAdministrator =
+        get_account_data(&mut blockchain, &server_administrators[0]).await;
+    assert_eq!(account_state.dweller, dweller_owner.pubkey());
+
+    let trx = remove_admin_transaction(
+        &blockchain.payer,
+        &dweller_owner,
+        &server.pubkey(),
+        &server_administrators[0],
+        &server_administrators[0],
+        &dweller_owner.pubkey(),
+        blockchain.last_blockhash,
+    );
+
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account_state: Server = get_account_data(&mut blockchain, &server.pubkey()).await;
+    assert_eq!(account_state.administrators, 0);
 }
 
-pub async fn test_add_administrator(
-    payer: &Keypair,
-    dweller_owner: &Keypair,
-    dweller: &Pubkey,
-    server: &Pubkey,
-    server_administrator: &Pubkey,
-    recent_blockhash: solana_program::hash::Hash,
-    blockchain: &mut BanksClient,
-) {
-    let mut transaction = Transaction::new_with_payer(
-        &[instruction::add_admin(
-            &dweller_owner.pubkey(),
-            dweller,
-            server,
-            server_administrator,
+#[tokio::test]
+async fn join_policy_flow() {
+    let mut blockchain = program_test().start_with_context().await;
+    let rent = blockchain.banks_client.get_rent().await.unwrap();
+
+    let dwellers = [
+        Keypair::new(),
+        Keypair::new(),
+        Keypair::new(),
+        Keypair::new(),
+    ];
+    let mut dweller_servers = Vec::new();
+    for dweller in dwellers.iter() {
+        test_initialize_dweller(
+            &blockchain.payer,
+            &dweller,
+            rent,
+            blockchain.last_blockhash,
+            &mut blockchain.banks_client,
         )
-        .unwrap()],
-        Some(&payer.pubkey()),
+        .await;
+
+        let address_to_create = create_derived_account_index(
+            &mut blockchain,
+            &dweller.pubkey(),
+            DwellerServer::SEED,
+            0,
+            instruction::AddressTypeInput::DwellerServer(0),
+        )
+        .await;
+        dweller_servers.push(address_to_create);
+    }
+
+    let [dweller_owner, dweller_rejected, dweller_public, dweller_approved] = dwellers;
+
+    let server = Keypair::new();
+    let mut server_members = Vec::new();
+    for index in 0u64..3 {
+        let address_to_create = create_derived_account_index(
+            &mut blockchain,
+            &server.pubkey(),
+            ServerMember::SEED,
+            index,
+            instruction::AddressTypeInput::ServerMember(index),
+        )
+        .await;
+        server_members.push(address_to_create);
+    }
+
+    test_initialize_server(
+        &blockchain.payer,
+        &dweller_owner,
+        &server,
+        &dweller_servers[0],
+        &server_members[0],
+        rent,
+        blockchain.last_blockhash,
+        &mut blockchain.banks_client,
+    )
+    .await;
+
+    let server_administrator = create_derived_account_index(
+        &mut blockchain,
+        &server.pubkey(),
+        ServerAdministrator::SEED,
+        0,
+        instruction::AddressTypeInput::ServerAdministrator(0),
+    )
+    .await;
+
+    test_add_administrator(
+        &blockchain.payer,
+        &dweller_owner,
+        &dweller_owner.pubkey(),
+        &server.pubkey(),
+        &server_administrator,
+        blockchain.last_blockhash,
+        &mut blockchain.banks_client,
+    )
+    .await;
+
+    let account_state: ServerV2 = get_account_data(&mut blockchain, &server.pubkey()).await;
+    assert_eq!(account_state.join_policy, JoinPolicy::InviteOnly);
+
+    // profile
+    let trx = set_server_photo_transaction(
+        &blockchain.payer,
+        &dweller_owner,
+        &server_administrator,
+        &server_members[0],
+        &server.pubkey(),
+        &SetHashInput { hash: [3; 64] },
+        blockchain.last_blockhash,
     );
-    transaction.sign(&[&payer, dweller_owner], recent_blockhash);
-    blockchain.process_transaction(transaction).await.unwrap();
-}
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
 
-#[allow(clippy::too_many_arguments)]
-pub async fn test_initialize_server(
-    payer: &Keypair,
-    dweller_owner: &Keypair,
-    server: &Keypair,
-    dweller_server: &Pubkey,
-    server_member: &Pubkey,
-    rent: solana_program::rent::Rent,
-    recent_blockhash: solana_program::hash::Hash,
-    blockchain: &mut BanksClient,
-) {
-    let mut transaction = Transaction::new_with_payer(
-        &[
-            system_instruction::create_account(
-                &payer.pubkey(),
-                &server.pubkey(),
-                rent.minimum_balance(Server::LEN as usize),
-                Server::LEN as u64,
-                &satellite_servers::id(),
-            ),
-            instruction::initialize_server(
+    let trx = update_server_profile_transaction(
+        &blockchain.payer,
+        &dweller_owner,
+        &server_administrator,
+        &server_members[0],
+        &server.pubkey(),
+        &UpdateServerProfileInput {
+            name: Some([14; 32]),
+            description_hash: Some([4; 64]),
+            banner_hash: Some([5; 64]),
+            ..Default::default()
+        },
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account_state: ServerV2 = get_account_data(&mut blockchain, &server.pubkey()).await;
+    assert_eq!(account_state.version, StateVersion::V2);
+    assert_eq!(account_state.name, [14; 32]);
+    assert_eq!(account_state.photo_hash, [3; 64]);
+    assert_eq!(account_state.description_hash, [4; 64]);
+    assert_eq!(account_state.banner_hash, [5; 64]);
+
+    let audit_log = server_audit_log(&server.pubkey());
+
+    // privileged instruction without audit log fails
+    let trx = Transaction::new_signed_with_payer(
+        &[instruction::with_administrator_member(
+            instruction::set_join_policy(
                 &dweller_owner.pubkey(),
+                &server_administrator,
                 &server.pubkey(),
-                dweller_server,
-                server_member,
-                InitializeServerInput { name: [13; 32] },
+                &SetJoinPolicyInput {
+                    join_policy: JoinPolicy::Public,
+                },
             )
             .unwrap(),
-        ],
-        Some(&payer.pubkey()),
+            &server_members[0],
+        )],
+        Some(&blockchain.payer.pubkey()),
+        &[&blockchain.payer, &dweller_owner],
+        blockchain.last_blockhash,
     );
-    transaction.sign(&[&payer, server, dweller_owner], recent_blockhash);
-    blockchain.process_transaction(transaction).await.unwrap();
-}
-
-pub async fn get_account(program_context: &mut ProgramTestContext, pubkey: &Pubkey) -> Account {
-    program_context
+    blockchain
         .banks_client
-        .get_account(*pubkey)
+        .process_transaction(trx)
         .await
-        .expect("account not found")
-        .expect("account empty")
-}
+        .unwrap_err();
 
-pub async fn get_account_data<T: BorshDeserialize>(
-    program_context: &mut ProgramTestContext,
-    pubkey: &Pubkey,
-) -> T {
-    program_context
+    // public server does not need invite
+    let mut trx = Transaction::new_with_payer(
+        &[instruction::with_server_audit_log(
+            instruction::with_administrator_member(
+                instruction::set_join_policy(
+                    &dweller_owner.pubkey(),
+                    &server_administrator,
+                    &server.pubkey(),
+                    &SetJoinPolicyInput {
+                        join_policy: JoinPolicy::Public,
+                    },
+                )
+                .unwrap(),
+                &server_members[0],
+            ),
+            &audit_log,
+        )],
+        Some(&blockchain.payer.pubkey()),
+    );
+    trx.sign(
+        &[&blockchain.payer, &dweller_owner],
+        blockchain.last_blockhash,
+    );
+    blockchain
         .banks_client
-        .get_account_data_with_borsh(*pubkey)
+        .process_transaction(trx)
         .await
-        .expect("account not found")
-}
+        .unwrap();
 
-mod sdk {
-    //! Helpers for tests
-    #![cfg(feature = "test-bpf")]
+    let account = get_account(&mut blockchain, &audit_log).await;
+    let (account_state, entries) = decode_server_audit_log(&account.data).unwrap();
+    assert_eq!(account_state.container, server.pubkey());
+    assert_eq!(entries.len(), 4);
+    assert_eq!(entries[0].action, AuditAction::AddAdministrator);
+    assert_eq!(entries[1].action, AuditAction::UpdateServerProfile);
+    assert_eq!(entries[3].actor, dweller_owner.pubkey());
+    assert_eq!(entries[3].action, AuditAction::SetJoinPolicy);
+    assert_eq!(entries[3].target, server.pubkey());
 
-    use solana_program::pubkey::Pubkey;
-    use solana_sdk::{
-        signature::{Keypair, Signer},
-        transaction::Transaction,
-    };
+    // directory
+    let server_directory = create_derived_account_index(
+        &mut blockchain,
+        &id(),
+        ServerDirectory::SEED,
+        0,
+        instruction::AddressTypeInput::ServerDirectory,
+    )
+    .await;
+    let directory_entry = create_derived_account_index(
+        &mut blockchain,
+        &server_directory,
+        DirectoryEntry::SEED,
+        0,
+        instruction::AddressTypeInput::DirectoryEntry(0),
+    )
+    .await;
 
-    use crate::instruction::{
-        self, AddChannelInput, CreateGroupInput, SetDwellerStatusInput, SetHashInput, SetNameInput,
+    let input = ListServerInput {
+        category: ServerCategory::Community,
+        language: *b"en\0\0\0\0\0\0",
+        tags: [[1; 16], [2; 16], [0; 16], [0; 16]],
     };
+    let trx = list_server_transaction(
+        &blockchain.payer,
+        &dweller_owner,
+        &server.pubkey(),
+        &server_directory,
+        &directory_entry,
+        &input,
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
 
+    let account_state: ServerDirectory = get_account_data(&mut blockchain, &server_directory).await;
+    assert_eq!(account_state.entries, 1);
+    let account_state: DirectoryEntry = get_account_data(&mut blockchain, &directory_entry).await;
+    assert_eq!(account_state.server, server.pubkey());
+    assert_eq!(account_state.category, ServerCategory::Community);
+    let account_state: ServerV2 = get_account_data(&mut blockchain, &server.pubkey()).await;
+    assert!(account_state.listed);
+
+    let trx = unlist_server_transaction(
+        &blockchain.payer,
+        &dweller_owner,
+        &server.pubkey(),
+        &server_directory,
+        &directory_entry,
+        &directory_entry,
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account_state: ServerDirectory = get_account_data(&mut blockchain, &server_directory).await;
+    assert_eq!(account_state.entries, 0);
+    let account_state: ServerV2 = get_account_data(&mut blockchain, &server.pubkey()).await;
+    assert!(!account_state.listed);
+
+    let trx = join_server_transaction(
+        &blockchain.payer,
+        &server.pubkey(),
+        &server_members[1],
+        &server.pubkey(),
+        &dweller_public,
+        &dweller_servers[2],
+        &server.pubkey(),
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account_state: ServerMember = get_account_data(&mut blockchain, &server_members[1]).await;
+    assert_eq!(account_state.dweller, dweller_public.pubkey());
+
+    // member cannot join again
+    blockchain.last_blockhash = blockchain
+        .banks_client
+        .get_new_latest_blockhash(&blockchain.last_blockhash)
+        .await
+        .unwrap();
+    let trx = join_server_transaction(
+        &blockchain.payer,
+        &server.pubkey(),
+        &server_members[2],
+        &server.pubkey(),
+        &dweller_public,
+        &dweller_servers[3],
+        &server.pubkey(),
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap_err();
+    let account_state: ServerV2 = get_account_data(&mut blockchain, &server.pubkey()).await;
+    assert_eq!(account_state.members, 2);
+
+    let trx = set_member_nickname_transaction(
+        &blockchain.payer,
+        &dweller_public,
+        &server.pubkey(),
+        &server_members[1],
+        &server.pubkey(),
+        &server_members[0],
+        &SetNameInput { name: [71; 32] },
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account_state: ServerMemberV2 = get_account_data(&mut blockchain, &server_members[1]).await;
+    assert_eq!(account_state.nickname, [71; 32]);
+
+    let trx = set_member_nickname_transaction(
+        &blockchain.payer,
+        &dweller_approved,
+        &server.pubkey(),
+        &server_members[1],
+        &server.pubkey(),
+        &server_members[0],
+        &SetNameInput { name: [72; 32] },
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap_err();
+
+    let trx = set_member_nickname_transaction(
+        &blockchain.payer,
+        &dweller_owner,
+        &server.pubkey(),
+        &server_members[1],
+        &server_administrator,
+        &server_members[0],
+        &SetNameInput { name: [73; 32] },
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account_state: ServerMemberV2 = get_account_data(&mut blockchain, &server_members[1]).await;
+    assert_eq!(account_state.nickname, [73; 32]);
+
+    // member written before nicknames is grown, paid by payer
+    let legacy = ServerMember {
+        version: StateVersion::V1,
+        container: server.pubkey(),
+        index: 1,
+        dweller: dweller_public.pubkey(),
+    };
+    blockchain.set_account(
+        &server_members[1],
+        &Account {
+            lamports: rent.minimum_balance(ServerMember::LEN as usize),
+            data: borsh::BorshSerialize::try_to_vec(&legacy).unwrap(),
+            owner: id(),
+            executable: false,
+            rent_epoch: 0,
+        }
+        .into(),
+    );
+
+    let trx = set_member_nickname_transaction(
+        &blockchain.payer,
+        &dweller_public,
+        &server.pubkey(),
+        &server_members[1],
+        &server.pubkey(),
+        &server_members[0],
+        &SetNameInput { name: [74; 32] },
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap_err();
+
+    let trx = Transaction::new_signed_with_payer(
+        &[instruction::with_payer(
+            instruction::set_member_nickname(
+                &dweller_public.pubkey(),
+                &server.pubkey(),
+                &server_members[1],
+                &server.pubkey(),
+                &SetNameInput { name: [74; 32] },
+            )
+            .unwrap(),
+            &blockchain.payer.pubkey(),
+            &[],
+        )],
+        Some(&blockchain.payer.pubkey()),
+        &[&blockchain.payer, &dweller_public],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account = get_account(&mut blockchain, &server_members[1]).await;
+    assert_eq!(account.data.len() as u64, ServerMemberV2::LEN);
+    assert!(account.lamports >= rent.minimum_balance(account.data.len()));
+    let account_state: ServerMemberV2 = get_account_data(&mut blockchain, &server_members[1]).await;
+    assert_eq!(account_state.version, StateVersion::V2);
+    assert_eq!(account_state.dweller, dweller_public.pubkey());
+    assert_eq!(account_state.nickname, [74; 32]);
+
+    // approval required server
+    let trx = set_join_policy_transaction(
+        &blockchain.payer,
+        &dweller_owner,
+        &server_administrator,
+        &server_members[0],
+        &server.pubkey(),
+        &SetJoinPolicyInput {
+            join_policy: JoinPolicy::ApprovalRequired,
+        },
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let join_request = create_derived_account_index(
+        &mut blockchain,
+        &server.pubkey(),
+        JoinRequest::SEED,
+        0,
+        instruction::AddressTypeInput::JoinRequest(0),
+    )
+    .await;
+
+    let trx = request_join_server_transaction(
+        &blockchain.payer,
+        &server.pubkey(),
+        &dweller_approved,
+        &join_request,
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account_state: JoinRequest = get_account_data(&mut blockchain, &join_request).await;
+    assert_eq!(account_state.dweller, dweller_approved.pubkey());
+    let account_state: ServerV2 = get_account_data(&mut blockchain, &server.pubkey()).await;
+    assert_eq!(account_state.join_requests, 1);
+    let (member_marker, _) = DwellerMarker::find_address(
+        &id(),
+        ServerMember::SEED,
+        &server.pubkey(),
+        &dweller_approved.pubkey(),
+    );
+    let account = get_account(&mut blockchain, &member_marker).await;
+    assert_eq!(account.owner, id());
+
+    // one pending request per dweller
+    let join_request_other = create_derived_account_index(
+        &mut blockchain,
+        &server.pubkey(),
+        JoinRequest::SEED,
+        1,
+        instruction::AddressTypeInput::JoinRequest(1),
+    )
+    .await;
+    let trx = request_join_server_transaction(
+        &blockchain.payer,
+        &server.pubkey(),
+        &dweller_approved,
+        &join_request_other,
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap_err();
+
+    let trx = approve_join_request_transaction(
+        &blockchain.payer,
+        &dweller_owner,
+        &server_administrator,
+        &server_members[0],
+        &server.pubkey(),
+        &join_request,
+        &join_request,
+        &server_members[2],
+        &dweller_approved.pubkey(),
+        &dweller_servers[3],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account_state: ServerV2 = get_account_data(&mut blockchain, &server.pubkey()).await;
+    assert_eq!(account_state.join_requests, 0);
+    assert_eq!(account_state.members, 3);
+    let account_state: DwellerServer = get_account_data(&mut blockchain, &dweller_servers[3]).await;
+    assert_eq!(account_state.server, server.pubkey());
+    // marker of request stays as marker of membership
+    let account = get_account(&mut blockchain, &member_marker).await;
+    assert_eq!(account.owner, id());
+
+    // requests are not approved once server stops requiring approval
+    blockchain.last_blockhash = blockchain
+        .banks_client
+        .get_new_latest_blockhash(&blockchain.last_blockhash)
+        .await
+        .unwrap();
+    create_derived_account_index(
+        &mut blockchain,
+        &server.pubkey(),
+        JoinRequest::SEED,
+        0,
+        instruction::AddressTypeInput::JoinRequest(0),
+    )
+    .await;
+    let trx = request_join_server_transaction(
+        &blockchain.payer,
+        &server.pubkey(),
+        &dweller_rejected,
+        &join_request,
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let trx = set_join_policy_transaction(
+        &blockchain.payer,
+        &dweller_owner,
+        &server_administrator,
+        &server_members[0],
+        &server.pubkey(),
+        &SetJoinPolicyInput {
+            join_policy: JoinPolicy::Public,
+        },
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let server_member = satellite_servers::program::create_index_with_seed(
+        &id(),
+        ServerMember::SEED,
+        &server.pubkey(),
+        3,
+    )
+    .unwrap();
+    let trx = Transaction::new_signed_with_payer(
+        &[instruction::with_server_audit_log(
+            instruction::with_payer(
+                instruction::with_rent_receiver(
+                    instruction::with_administrator_member(
+                        instruction::approve_join_request(
+                            &dweller_owner.pubkey(),
+                            &server_administrator,
+                            &server.pubkey(),
+                            &join_request,
+                            &join_request,
+                            &server_member,
+                            &dweller_rejected.pubkey(),
+                            &dweller_servers[1],
+                        )
+                        .unwrap(),
+                        &server_members[0],
+                    ),
+                    &blockchain.payer.pubkey(),
+                ),
+                &blockchain.payer.pubkey(),
+                &[&server.pubkey()],
+            ),
+            &server_audit_log(&server.pubkey()),
+        )],
+        Some(&blockchain.payer.pubkey()),
+        &[&blockchain.payer, &dweller_owner],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap_err();
+
+    let trx = Transaction::new_signed_with_payer(
+        &[instruction::with_server_audit_log(
+            instruction::with_rent_receiver(
+                instruction::with_administrator_member(
+                    instruction::reject_join_request(
+                        &dweller_owner.pubkey(),
+                        &server_administrator,
+                        &server.pubkey(),
+                        &join_request,
+                        &join_request,
+                        &dweller_rejected.pubkey(),
+                    )
+                    .unwrap(),
+                    &server_members[0],
+                ),
+                &blockchain.payer.pubkey(),
+            ),
+            &server_audit_log(&server.pubkey()),
+        )],
+        Some(&blockchain.payer.pubkey()),
+        &[&blockchain.payer, &dweller_owner],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account_state: ServerV2 = get_account_data(&mut blockchain, &server.pubkey()).await;
+    assert_eq!(account_state.join_requests, 0);
+    assert_eq!(account_state.members, 3);
+    let (member_marker, _) = DwellerMarker::find_address(
+        &id(),
+        ServerMember::SEED,
+        &server.pubkey(),
+        &dweller_rejected.pubkey(),
+    );
+    assert_eq!(
+        blockchain
+            .banks_client
+            .get_account(member_marker)
+            .await
+            .unwrap(),
+        None
+    );
+
+    // events
+    let server_events = create_derived_account_index(
+        &mut blockchain,
+        &server.pubkey(),
+        ServerEvents::SEED,
+        0,
+        instruction::AddressTypeInput::ServerEvents,
+    )
+    .await;
+    let server_event = create_derived_account_index(
+        &mut blockchain,
+        &server.pubkey(),
+        ServerEvent::SEED,
+        0,
+        instruction::AddressTypeInput::ServerEvent(0),
+    )
+    .await;
+
+    let trx = create_server_event_transaction(
+        &blockchain.payer,
+        &dweller_owner,
+        &server_administrator,
+        &server_members[0],
+        &server.pubkey(),
+        &server_events,
+        &server_event,
+        &server.pubkey(),
+        &CreateServerEventInput {
+            title: [1; 32],
+            description_hash: [2; 64],
+            start: 4_000_000_000,
+            end: 4_000_007_200,
+            channel: Pubkey::default(),
+        },
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account_state: ServerEvents = get_account_data(&mut blockchain, &server_events).await;
+    assert_eq!(account_state.events, 1);
+
+    let mut event_rsvps = Vec::new();
+    for index in 0u64..2 {
+        let address_to_create = create_derived_account_index(
+            &mut blockchain,
+            &server_event,
+            EventRsvp::SEED,
+            index,
+            instruction::AddressTypeInput::EventRsvp(index),
+        )
+        .await;
+        event_rsvps.push(address_to_create);
+    }
+
+    for (index, (dweller, server_member)) in [
+        (&dweller_public, &server_members[1]),
+        (&dweller_approved, &server_members[2]),
+    ]
+    .iter()
+    .enumerate()
+    {
+        let trx = rsvp_transaction(
+            &blockchain.payer,
+            dweller,
+            &server.pubkey(),
+            server_member,
+            &server_event,
+            &event_rsvps[index],
+            blockchain.last_blockhash,
+        );
+        blockchain
+            .banks_client
+            .process_transaction(trx)
+            .await
+            .unwrap();
+    }
+
+    let account_state: ServerEvent = get_account_data(&mut blockchain, &server_event).await;
+    assert_eq!(account_state.attendees, 2);
+    let account_state: EventRsvp = get_account_data(&mut blockchain, &event_rsvps[1]).await;
+    assert_eq!(account_state.dweller, dweller_approved.pubkey());
+
+    // one RSVP per dweller
+    let event_rsvp_other = create_derived_account_index(
+        &mut blockchain,
+        &server_event,
+        EventRsvp::SEED,
+        2,
+        instruction::AddressTypeInput::EventRsvp(2),
+    )
+    .await;
+    let trx = rsvp_transaction(
+        &blockchain.payer,
+        &dweller_public,
+        &server.pubkey(),
+        &server_members[1],
+        &server_event,
+        &event_rsvp_other,
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap_err();
+
+    let mut trx = Transaction::new_with_payer(
+        &[instruction::with_rent_receiver(
+            instruction::cancel_rsvp(
+                &dweller_approved.pubkey(),
+                &server.pubkey(),
+                &server_event,
+                &event_rsvps[1],
+                &event_rsvps[1],
+            )
+            .unwrap(),
+            &blockchain.payer.pubkey(),
+        )],
+        Some(&blockchain.payer.pubkey()),
+    );
+    trx.sign(
+        &[&blockchain.payer, &dweller_approved],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let (event_rsvp_marker, _) = DwellerMarker::find_address(
+        &id(),
+        EventRsvp::SEED,
+        &server_event,
+        &dweller_approved.pubkey(),
+    );
+    assert_eq!(
+        blockchain
+            .banks_client
+            .get_account(event_rsvp_marker)
+            .await
+            .unwrap(),
+        None
+    );
+
+    let mut trx = Transaction::new_with_payer(
+        &[instruction::with_server_audit_log(
+            instruction::with_administrator_member(
+                instruction::cancel_server_event(
+                    &dweller_owner.pubkey(),
+                    &server_administrator,
+                    &server.pubkey(),
+                    &server_event,
+                )
+                .unwrap(),
+                &server_members[0],
+            ),
+            &server_audit_log(&server.pubkey()),
+        )],
+        Some(&blockchain.payer.pubkey()),
+    );
+    trx.sign(
+        &[&blockchain.payer, &dweller_owner],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account_state: ServerEvent = get_account_data(&mut blockchain, &server_event).await;
+    assert_eq!(account_state.attendees, 1);
+    assert!(account_state.cancelled);
+
+    // cancelled event takes no RSVPs
+    let trx = rsvp_transaction(
+        &blockchain.payer,
+        &dweller_public,
+        &server.pubkey(),
+        &server_members[1],
+        &server_event,
+        &event_rsvps[1],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap_err();
+
+    // ended event takes no RSVPs
+    let server_event = create_derived_account_index(
+        &mut blockchain,
+        &server.pubkey(),
+        ServerEvent::SEED,
+        1,
+        instruction::AddressTypeInput::ServerEvent(1),
+    )
+    .await;
+    let event_rsvp = create_derived_account_index(
+        &mut blockchain,
+        &server_event,
+        EventRsvp::SEED,
+        0,
+        instruction::AddressTypeInput::EventRsvp(0),
+    )
+    .await;
+
+    let trx = create_server_event_transaction(
+        &blockchain.payer,
+        &dweller_owner,
+        &server_administrator,
+        &server_members[0],
+        &server.pubkey(),
+        &server_events,
+        &server_event,
+        &server.pubkey(),
+        &CreateServerEventInput {
+            title: [3; 32],
+            description_hash: [4; 64],
+            start: 100,
+            end: 200,
+            channel: Pubkey::default(),
+        },
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let trx = rsvp_transaction(
+        &blockchain.payer,
+        &dweller_approved,
+        &server.pubkey(),
+        &server_members[2],
+        &server_event,
+        &event_rsvp,
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap_err();
+}
+
+#[tokio::test]
+async fn join_requirement_flow() {
+    let mut blockchain = program_test().start_with_context().await;
+    let rent = blockchain.banks_client.get_rent().await.unwrap();
+
+    let dwellers = [Keypair::new(), Keypair::new(), Keypair::new()];
+    let mut dweller_servers = Vec::new();
+    for dweller in dwellers.iter() {
+        test_initialize_dweller(
+            &blockchain.payer,
+            &dweller,
+            rent,
+            blockchain.last_blockhash,
+            &mut blockchain.banks_client,
+        )
+        .await;
+
+        let address_to_create = create_derived_account_index(
+            &mut blockchain,
+            &dweller.pubkey(),
+            DwellerServer::SEED,
+            0,
+            instruction::AddressTypeInput::DwellerServer(0),
+        )
+        .await;
+        dweller_servers.push(address_to_create);
+    }
+
+    let [dweller_owner, dweller_holder, dweller_other] = dwellers;
+
+    let server = Keypair::new();
+    let mut server_members = Vec::new();
+    for index in 0u64..2 {
+        let address_to_create = create_derived_account_index(
+            &mut blockchain,
+            &server.pubkey(),
+            ServerMember::SEED,
+            index,
+            instruction::AddressTypeInput::ServerMember(index),
+        )
+        .await;
+        server_members.push(address_to_create);
+    }
+
+    test_initialize_server(
+        &blockchain.payer,
+        &dweller_owner,
+        &server,
+        &dweller_servers[0],
+        &server_members[0],
+        rent,
+        blockchain.last_blockhash,
+        &mut blockchain.banks_client,
+    )
+    .await;
+
+    let server_administrator = create_derived_account_index(
+        &mut blockchain,
+        &server.pubkey(),
+        ServerAdministrator::SEED,
+        0,
+        instruction::AddressTypeInput::ServerAdministrator(0),
+    )
+    .await;
+
+    test_add_administrator(
+        &blockchain.payer,
+        &dweller_owner,
+        &dweller_owner.pubkey(),
+        &server.pubkey(),
+        &server_administrator,
+        blockchain.last_blockhash,
+        &mut blockchain.banks_client,
+    )
+    .await;
+
+    let trx = set_join_policy_transaction(
+        &blockchain.payer,
+        &dweller_owner,
+        &server_administrator,
+        &server_members[0],
+        &server.pubkey(),
+        &SetJoinPolicyInput {
+            join_policy: JoinPolicy::Public,
+        },
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    // holder gets tokens
+    let mint = Keypair::new();
+    let token = Keypair::new();
+    let mut trx = Transaction::new_with_payer(
+        &[
+            system_instruction::create_account(
+                &blockchain.payer.pubkey(),
+                &mint.pubkey(),
+                rent.minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_mint(
+                &spl_token::id(),
+                &mint.pubkey(),
+                &blockchain.payer.pubkey(),
+                None,
+                0,
+            )
+            .unwrap(),
+            system_instruction::create_account(
+                &blockchain.payer.pubkey(),
+                &token.pubkey(),
+                rent.minimum_balance(spl_token::state::Account::LEN),
+                spl_token::state::Account::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_account(
+                &spl_token::id(),
+                &token.pubkey(),
+                &mint.pubkey(),
+                &dweller_holder.pubkey(),
+            )
+            .unwrap(),
+            spl_token::instruction::mint_to(
+                &spl_token::id(),
+                &mint.pubkey(),
+                &token.pubkey(),
+                &blockchain.payer.pubkey(),
+                &[],
+                10,
+            )
+            .unwrap(),
+        ],
+        Some(&blockchain.payer.pubkey()),
+    );
+    trx.sign(
+        &[&blockchain.payer, &mint, &token],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let trx = set_join_requirement_transaction(
+        &blockchain.payer,
+        &dweller_owner,
+        &server_administrator,
+        &server_members[0],
+        &server.pubkey(),
+        &SetJoinRequirementInput {
+            join_requirement: JoinRequirement::TokenBalance {
+                mint: mint.pubkey(),
+                amount: 5,
+            },
+        },
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account_state: ServerV3 = get_account_data(&mut blockchain, &server.pubkey()).await;
+    assert_eq!(account_state.version, StateVersion::V3);
+    assert_eq!(account_state.members, 1);
+
+    // token of other owner does not qualify
+    let mut trx = Transaction::new_with_payer(
+        &[instruction::with_join_requirement_token(
+            instruction::join_server(
+                &server.pubkey(),
+                &server_members[1],
+                &server.pubkey(),
+                &dweller_other.pubkey(),
+                &dweller_servers[2],
+                &server.pubkey(),
+                &blockchain.payer.pubkey(),
+            )
+            .unwrap(),
+            &token.pubkey(),
+        )],
+        Some(&blockchain.payer.pubkey()),
+    );
+    trx.sign(
+        &[&blockchain.payer, &dweller_other],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap_err();
+
+    let mut trx = Transaction::new_with_payer(
+        &[instruction::with_join_requirement_token(
+            instruction::join_server(
+                &server.pubkey(),
+                &server_members[1],
+                &server.pubkey(),
+                &dweller_holder.pubkey(),
+                &dweller_servers[1],
+                &server.pubkey(),
+                &blockchain.payer.pubkey(),
+            )
+            .unwrap(),
+            &token.pubkey(),
+        )],
+        Some(&blockchain.payer.pubkey()),
+    );
+    trx.sign(
+        &[&blockchain.payer, &dweller_holder],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account_state: ServerMemberV3 = get_account_data(&mut blockchain, &server_members[1]).await;
+    assert_eq!(account_state.dweller, dweller_holder.pubkey());
+    assert_eq!(account_state.token, token.pubkey());
+
+    let trx = recheck_member_transaction(
+        &blockchain.payer,
+        &server.pubkey(),
+        &server_members[1],
+        &server_members[1],
+        &dweller_holder.pubkey(),
+        &dweller_servers[1],
+        &dweller_servers[1],
+        &token.pubkey(),
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap_err();
+
+    let mut trx = Transaction::new_with_payer(
+        &[spl_token::instruction::burn(
+            &spl_token::id(),
+            &token.pubkey(),
+            &mint.pubkey(),
+            &dweller_holder.pubkey(),
+            &[],
+            6,
+        )
+        .unwrap()],
+        Some(&blockchain.payer.pubkey()),
+    );
+    trx.sign(
+        &[&blockchain.payer, &dweller_holder],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    // owner is never evicted
+    let trx = recheck_member_transaction(
+        &blockchain.payer,
+        &server.pubkey(),
+        &server_members[0],
+        &server_members[1],
+        &dweller_owner.pubkey(),
+        &dweller_servers[0],
+        &dweller_servers[0],
+        &token.pubkey(),
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap_err();
+    let account_state: Server = get_account_data(&mut blockchain, &server.pubkey()).await;
+    assert_eq!(account_state.members, 2);
+
+    // anyone can evict
+    let evictor = Keypair::new();
+    let mut trx = Transaction::new_with_payer(
+        &[system_instruction::transfer(
+            &blockchain.payer.pubkey(),
+            &evictor.pubkey(),
+            1_000_000_000,
+        )],
+        Some(&blockchain.payer.pubkey()),
+    );
+    trx.sign(&[&blockchain.payer], blockchain.last_blockhash);
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let trx = recheck_member_transaction(
+        &evictor,
+        &server.pubkey(),
+        &server_members[1],
+        &server_members[1],
+        &dweller_holder.pubkey(),
+        &dweller_servers[1],
+        &dweller_servers[1],
+        &token.pubkey(),
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account_state: Server = get_account_data(&mut blockchain, &server.pubkey()).await;
+    assert_eq!(account_state.members, 1);
+    let account_state: Dweller = get_account_data(&mut blockchain, &dweller_holder.pubkey()).await;
+    assert_eq!(account_state.servers, 0);
+}
+
+#[tokio::test]
+async fn join_fee_flow() {
+    let mut blockchain = program_test().start_with_context().await;
+    let rent = blockchain.banks_client.get_rent().await.unwrap();
+
+    let dwellers = [Keypair::new(), Keypair::new()];
+    let mut dweller_servers = Vec::new();
+    for dweller in dwellers.iter() {
+        test_initialize_dweller(
+            &blockchain.payer,
+            &dweller,
+            rent,
+            blockchain.last_blockhash,
+            &mut blockchain.banks_client,
+        )
+        .await;
+
+        let address_to_create = create_derived_account_index(
+            &mut blockchain,
+            &dweller.pubkey(),
+            DwellerServer::SEED,
+            0,
+            instruction::AddressTypeInput::DwellerServer(0),
+        )
+        .await;
+        dweller_servers.push(address_to_create);
+    }
+
+    let [dweller_owner, dweller_payer] = dwellers;
+
+    let server = Keypair::new();
+    let mut server_members = Vec::new();
+    for index in 0u64..2 {
+        let address_to_create = create_derived_account_index(
+            &mut blockchain,
+            &server.pubkey(),
+            ServerMember::SEED,
+            index,
+            instruction::AddressTypeInput::ServerMember(index),
+        )
+        .await;
+        server_members.push(address_to_create);
+    }
+
+    test_initialize_server(
+        &blockchain.payer,
+        &dweller_owner,
+        &server,
+        &dweller_servers[0],
+        &server_members[0],
+        rent,
+        blockchain.last_blockhash,
+        &mut blockchain.banks_client,
+    )
+    .await;
+
+    let server_administrator = create_derived_account_index(
+        &mut blockchain,
+        &server.pubkey(),
+        ServerAdministrator::SEED,
+        0,
+        instruction::AddressTypeInput::ServerAdministrator(0),
+    )
+    .await;
+
+    test_add_administrator(
+        &blockchain.payer,
+        &dweller_owner,
+        &dweller_owner.pubkey(),
+        &server.pubkey(),
+        &server_administrator,
+        blockchain.last_blockhash,
+        &mut blockchain.banks_client,
+    )
+    .await;
+
+    let trx = set_join_policy_transaction(
+        &blockchain.payer,
+        &dweller_owner,
+        &server_administrator,
+        &server_members[0],
+        &server.pubkey(),
+        &SetJoinPolicyInput {
+            join_policy: JoinPolicy::Public,
+        },
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    // payer gets tokens, owner gets account to withdraw into
+    let mint = Keypair::new();
+    let token = Keypair::new();
+    let destination = Keypair::new();
+    let mut trx = Transaction::new_with_payer(
+        &[
+            system_instruction::create_account(
+                &blockchain.payer.pubkey(),
+                &mint.pubkey(),
+                rent.minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_mint(
+                &spl_token::id(),
+                &mint.pubkey(),
+                &blockchain.payer.pubkey(),
+                None,
+                0,
+            )
+            .unwrap(),
+            system_instruction::create_account(
+                &blockchain.payer.pubkey(),
+                &token.pubkey(),
+                rent.minimum_balance(spl_token::state::Account::LEN),
+                spl_token::state::Account::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_account(
+                &spl_token::id(),
+                &token.pubkey(),
+                &mint.pubkey(),
+                &dweller_payer.pubkey(),
+            )
+            .unwrap(),
+            system_instruction::create_account(
+                &blockchain.payer.pubkey(),
+                &destination.pubkey(),
+                rent.minimum_balance(spl_token::state::Account::LEN),
+                spl_token::state::Account::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_account(
+                &spl_token::id(),
+                &destination.pubkey(),
+                &mint.pubkey(),
+                &dweller_owner.pubkey(),
+            )
+            .unwrap(),
+            spl_token::instruction::mint_to(
+                &spl_token::id(),
+                &mint.pubkey(),
+                &token.pubkey(),
+                &blockchain.payer.pubkey(),
+                &[],
+                10,
+            )
+            .unwrap(),
+        ],
+        Some(&blockchain.payer.pubkey()),
+    );
+    trx.sign(
+        &[&blockchain.payer, &mint, &token, &destination],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let join_fee = JoinFee {
+        mint: mint.pubkey(),
+        amount: 3,
+        period: 100,
+    };
+    let trx = set_join_fee_transaction(
+        &blockchain.payer,
+        &dweller_owner,
+        &server.pubkey(),
+        &mint.pubkey(),
+        &SetJoinFeeInput {
+            join_fee: Some(join_fee.clone()),
+        },
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account_state: ServerV4 = get_account_data(&mut blockchain, &server.pubkey()).await;
+    assert_eq!(account_state.version, StateVersion::V4);
+    assert_eq!(account_state.join_fee, Some(join_fee));
+    let (treasury, _) = JoinFee::find_treasury_address(&id(), &server.pubkey(), &mint.pubkey());
+
+    // fee must be paid
+    let mut trx = Transaction::new_with_payer(
+        &[instruction::join_server(
+            &server.pubkey(),
+            &server_members[1],
+            &server.pubkey(),
+            &dweller_payer.pubkey(),
+            &dweller_servers[1],
+            &server.pubkey(),
+            &blockchain.payer.pubkey(),
+        )
+        .unwrap()],
+        Some(&blockchain.payer.pubkey()),
+    );
+    trx.sign(
+        &[&blockchain.payer, &dweller_payer],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap_err();
+
+    let mut trx = Transaction::new_with_payer(
+        &[instruction::with_join_fee(
+            instruction::join_server(
+                &server.pubkey(),
+                &server_members[1],
+                &server.pubkey(),
+                &dweller_payer.pubkey(),
+                &dweller_servers[1],
+                &server.pubkey(),
+                &blockchain.payer.pubkey(),
+            )
+            .unwrap(),
+            &server.pubkey(),
+            &token.pubkey(),
+            &mint.pubkey(),
+        )],
+        Some(&blockchain.payer.pubkey()),
+    );
+    trx.sign(
+        &[&blockchain.payer, &dweller_payer],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account = get_account(&mut blockchain, &treasury).await;
+    let treasury_state = spl_token::state::Account::unpack(&account.data).unwrap();
+    assert_eq!(treasury_state.amount, 3);
+    let account_state: ServerMemberV4 = get_account_data(&mut blockchain, &server_members[1]).await;
+    assert_eq!(account_state.version, StateVersion::V4);
+    let paid_until = account_state.paid_until;
+    assert!(paid_until > 0);
+
+    let mut trx = Transaction::new_with_payer(
+        &[instruction::renew_membership(
+            &server.pubkey(),
+            &server_members[1],
+            &dweller_payer.pubkey(),
+            &token.pubkey(),
+            &mint.pubkey(),
+        )
+        .unwrap()],
+        Some(&blockchain.payer.pubkey()),
+    );
+    trx.sign(
+        &[&blockchain.payer, &dweller_payer],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account_state: ServerMemberV4 = get_account_data(&mut blockchain, &server_members[1]).await;
+    assert_eq!(account_state.paid_until, paid_until + 100);
+
+    let mut trx = Transaction::new_with_payer(
+        &[instruction::with_server_audit_log(
+            instruction::withdraw_treasury(
+                &dweller_owner.pubkey(),
+                &server.pubkey(),
+                &destination.pubkey(),
+                &WithdrawTreasuryInput {
+                    mint: mint.pubkey(),
+                    amount: 6,
+                },
+            )
+            .unwrap(),
+            &server_audit_log(&server.pubkey()),
+        )],
+        Some(&blockchain.payer.pubkey()),
+    );
+    trx.sign(
+        &[&blockchain.payer, &dweller_owner],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account = get_account(&mut blockchain, &destination.pubkey()).await;
+    let destination_state = spl_token::state::Account::unpack(&account.data).unwrap();
+    assert_eq!(destination_state.amount, 6);
+}
+
+#[tokio::test]
+async fn admin_membership_flow() {
+    let mut blockchain = program_test().start_with_context().await;
+    let rent = blockchain.banks_client.get_rent().await.unwrap();
+
+    let dwellers = [Keypair::new(), Keypair::new()];
+    let mut dweller_servers = Vec::new();
+    for dweller in dwellers.iter() {
+        test_initialize_dweller(
+            &blockchain.payer,
+            &dweller,
+            rent,
+            blockchain.last_blockhash,
+            &mut blockchain.banks_client,
+        )
+        .await;
+
+        let address_to_create = create_derived_account_index(
+            &mut blockchain,
+            &dweller.pubkey(),
+            DwellerServer::SEED,
+            0,
+            instruction::AddressTypeInput::DwellerServer(0),
+        )
+        .await;
+        dweller_servers.push(address_to_create);
+    }
+
+    let [dweller_owner, dweller_admin] = dwellers;
+
+    let server = Keypair::new();
+    let mut server_members = Vec::new();
+    for index in 0u64..2 {
+        let address_to_create = create_derived_account_index(
+            &mut blockchain,
+            &server.pubkey(),
+            ServerMember::SEED,
+            index,
+            instruction::AddressTypeInput::ServerMember(index),
+        )
+        .await;
+        server_members.push(address_to_create);
+    }
+
+    test_initialize_server(
+        &blockchain.payer,
+        &dweller_owner,
+        &server,
+        &dweller_servers[0],
+        &server_members[0],
+        rent,
+        blockchain.last_blockhash,
+        &mut blockchain.banks_client,
+    )
+    .await;
+
+    let mut server_administrators = Vec::new();
+    for (index, dweller) in [&dweller_admin, &dweller_owner].iter().enumerate() {
+        let address_to_create = create_derived_account_index(
+            &mut blockchain,
+            &server.pubkey(),
+            ServerAdministrator::SEED,
+            index as u64,
+            instruction::AddressTypeInput::ServerAdministrator(index as u64),
+        )
+        .await;
+        test_add_administrator(
+            &blockchain.payer,
+            &dweller_owner,
+            &dweller.pubkey(),
+            &server.pubkey(),
+            &address_to_create,
+            blockchain.last_blockhash,
+            &mut blockchain.banks_client,
+        )
+        .await;
+        server_administrators.push(address_to_create);
+    }
+
+    let server_channel = create_derived_account_index(
+        &mut blockchain,
+        &server.pubkey(),
+        ServerChannel::SEED,
+        0,
+        instruction::AddressTypeInput::ServerChannel(0),
+    )
+    .await;
+
+    // administrator which is not member is rejected
+    let trx = set_join_policy_transaction(
+        &blockchain.payer,
+        &dweller_admin,
+        &server_administrators[0],
+        &server_members[0],
+        &server.pubkey(),
+        &SetJoinPolicyInput {
+            join_policy: JoinPolicy::Public,
+        },
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap_err();
+
+    let trx = set_join_policy_transaction(
+        &blockchain.payer,
+        &dweller_owner,
+        &server_administrators[1],
+        &server_members[0],
+        &server.pubkey(),
+        &SetJoinPolicyInput {
+            join_policy: JoinPolicy::Public,
+        },
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let trx = join_server_transaction(
+        &blockchain.payer,
+        &server.pubkey(),
+        &server_members[1],
+        &server.pubkey(),
+        &dweller_admin,
+        &dweller_servers[1],
+        &server.pubkey(),
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let trx = add_channel_transaction(
+        &blockchain.payer,
+        &dweller_admin,
+        &server_administrators[0],
+        &server_members[1],
+        &server.pubkey(),
+        &server_channel,
+        &AddChannelInput {
+            name: [66; 32],
+            type_id: ChannelKind::Text as u8,
+        },
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account_state: Server = get_account_data(&mut blockchain, &server.pubkey()).await;
+    assert_eq!(account_state.channels, 1);
+
+    // leaving with administrator entry of other dweller fails
+    let trx = Transaction::new_signed_with_payer(
+        &[instruction::with_server_administrator(
+            instruction::leave_server(
+                &server.pubkey(),
+                &server_members[1],
+                &server_members[1],
+                &dweller_admin.pubkey(),
+                &dweller_servers[1],
+                &dweller_servers[1],
+            )
+            .unwrap(),
+            &server_administrators[1],
+            &server_administrators[1],
+        )],
+        Some(&blockchain.payer.pubkey()),
+        &[&blockchain.payer, &dweller_admin],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap_err();
+
+    // leaving without rent payer of vacated entries fails
+    let trx = Transaction::new_signed_with_payer(
+        &[instruction::with_server_administrator(
+            instruction::leave_server(
+                &server.pubkey(),
+                &server_members[1],
+                &server_members[1],
+                &dweller_admin.pubkey(),
+                &dweller_servers[1],
+                &dweller_servers[1],
+            )
+            .unwrap(),
+            &server_administrators[0],
+            &server_administrators[1],
+        )],
+        Some(&blockchain.payer.pubkey()),
+        &[&blockchain.payer, &dweller_admin],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap_err();
+
+    let trx = Transaction::new_signed_with_payer(
+        &[instruction::with_rent_receiver(
+            instruction::with_server_administrator(
+                instruction::leave_server(
+                    &server.pubkey(),
+                    &server_members[1],
+                    &server_members[1],
+                    &dweller_admin.pubkey(),
+                    &dweller_servers[1],
+                    &dweller_servers[1],
+                )
+                .unwrap(),
+                &server_administrators[0],
+                &server_administrators[1],
+            ),
+            &blockchain.payer.pubkey(),
+        )],
+        Some(&blockchain.payer.pubkey()),
+        &[&blockchain.payer, &dweller_admin],
+        blockchain.last_blockhash,
+    );
+    let payer = blockchain.payer.pubkey();
+    let payer_lamports = get_account(&mut blockchain, &payer).await.lamports;
+    let refund = get_account(&mut blockchain, &server_members[1])
+        .await
+        .lamports
+        + get_account(&mut blockchain, &server_administrators[1])
+            .await
+            .lamports
+        + get_account(&mut blockchain, &dweller_servers[1])
+            .await
+            .lamports;
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account_state: Server = get_account_data(&mut blockchain, &server.pubkey()).await;
+    assert_eq!(account_state.members, 1);
+    assert_eq!(account_state.administrators, 1);
+
+    // vacated last entries are closed into payer who created them
+    for closed in [
+        &server_members[1],
+        &server_administrators[1],
+        &dweller_servers[1],
+    ] {
+        assert_eq!(
+            blockchain.banks_client.get_account(*closed).await.unwrap(),
+            None
+        );
+    }
+    assert!(
+        get_account(&mut blockchain, &payer).await.lamports >= payer_lamports + refund - 10_000
+    );
+
+    // and can be created again
+    blockchain.last_blockhash = blockchain
+        .banks_client
+        .get_new_latest_blockhash(&blockchain.last_blockhash)
+        .await
+        .unwrap();
+    create_derived_account_index(
+        &mut blockchain,
+        &server.pubkey(),
+        ServerMember::SEED,
+        1,
+        instruction::AddressTypeInput::ServerMember(1),
+    )
+    .await;
+    let account = get_account(&mut blockchain, &server_members[1]).await;
+    assert_eq!(account.owner, id());
+    assert_eq!(
+        RentPayer::read::<ServerMember>(&account.data),
+        Some(blockchain.payer.pubkey())
+    );
+
+    // former administrator keeps no rights
+    let trx = add_channel_transaction(
+        &blockchain.payer,
+        &dweller_admin,
+        &server_administrators[0],
+        &server_members[1],
+        &server.pubkey(),
+        &server_channel,
+        &AddChannelInput {
+            name: [67; 32],
+            type_id: ChannelKind::Text as u8,
+        },
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap_err();
+
+    let account_state: Server = get_account_data(&mut blockchain, &server.pubkey()).await;
+    assert_eq!(account_state.channels, 1);
+
+    // last administrator moved into vacated entry with index of its address
+    let mut account_state: ServerAdministrator =
+        get_account_data(&mut blockchain, &server_administrators[0]).await;
+    assert_eq!(account_state.dweller, dweller_owner.pubkey());
+    assert_eq!(account_state.index, 0);
+
+    // entry corrupted by older removal
+    account_state.index = 1;
+    let mut account = get_account(&mut blockchain, &server_administrators[0]).await;
+    account.data[..ServerAdministrator::LEN as usize]
+        .copy_from_slice(&borsh::BorshSerialize::try_to_vec(&account_state).unwrap());
+    blockchain.set_account(&server_administrators[0], &account.into());
+    blockchain.last_blockhash = blockchain
+        .banks_client
+        .get_new_latest_blockhash(&blockchain.last_blockhash)
+        .await
+        .unwrap();
+
+    let reindex = |container: &Pubkey, entry: &Pubkey| {
+        Transaction::new_signed_with_payer(
+            &[instruction::with_server_audit_log(
+                instruction::with_administrator_member(
+                    instruction::reindex(
+                        &dweller_owner.pubkey(),
+                        &server_administrators[0],
+                        &server.pubkey(),
+                        container,
+                        &[entry],
+                        &instruction::ReindexInput {
+                            first: instruction::AddressTypeInput::ServerAdministrator(0),
+                            count: 1,
+                        },
+                    )
+                    .unwrap(),
+                    &server_members[0],
+                ),
+                &server_audit_log(&server.pubkey()),
+            )],
+            Some(&blockchain.payer.pubkey()),
+            &[&blockchain.payer, &dweller_owner],
+            blockchain.last_blockhash,
+        )
+    };
+    let trx_wrong_container = reindex(&server_channel, &server_administrators[0]);
+    let trx_wrong_entry = reindex(&server.pubkey(), &server_members[0]);
+    let trx = reindex(&server.pubkey(), &server_administrators[0]);
+    for trx in [trx_wrong_container, trx_wrong_entry] {
+        blockchain
+            .banks_client
+            .process_transaction(trx)
+            .await
+            .unwrap_err();
+    }
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account_state: ServerAdministrator =
+        get_account_data(&mut blockchain, &server_administrators[0]).await;
+    assert_eq!(account_state.dweller, dweller_owner.pubkey());
+    assert_eq!(account_state.index, 0);
+
+    // dweller list is repaired by dweller authority only
+    let mut account_state: DwellerServer =
+        get_account_data(&mut blockchain, &dweller_servers[0]).await;
+    account_state.index = 1;
+    let mut account = get_account(&mut blockchain, &dweller_servers[0]).await;
+    account.data[..DwellerServer::LEN as usize]
+        .copy_from_slice(&borsh::BorshSerialize::try_to_vec(&account_state).unwrap());
+    blockchain.set_account(&dweller_servers[0], &account.into());
+
+    let reindex_dweller_servers = instruction::reindex_dweller_servers(
+        &dweller_owner.pubkey(),
+        &[&dweller_servers[0]],
+        &instruction::ReindexInput {
+            first: instruction::AddressTypeInput::DwellerServer(0),
+            count: 1,
+        },
+    )
+    .unwrap();
+    let trx_other = Transaction::new_signed_with_payer(
+        &[instruction::with_dweller_authority(
+            reindex_dweller_servers.clone(),
+            &dweller_owner.pubkey(),
+            &dweller_admin.pubkey(),
+        )],
+        Some(&blockchain.payer.pubkey()),
+        &[&blockchain.payer, &dweller_admin],
+        blockchain.last_blockhash,
+    );
+    let trx = Transaction::new_signed_with_payer(
+        &[reindex_dweller_servers],
+        Some(&blockchain.payer.pubkey()),
+        &[&blockchain.payer, &dweller_owner],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx_other)
+        .await
+        .unwrap_err();
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account_state: DwellerServer = get_account_data(&mut blockchain, &dweller_servers[0]).await;
+    assert_eq!(account_state.index, 0);
+}
+
+#[tokio::test]
+async fn legacy_entry_swap_flow() {
+    let mut blockchain = program_test().start_with_context().await;
+    let rent = blockchain.banks_client.get_rent().await.unwrap();
+    let payer = blockchain.payer.pubkey();
+
+    let dwellers = [Keypair::new(), Keypair::new(), Keypair::new()];
+    let mut dweller_servers = Vec::new();
+    for dweller in dwellers.iter() {
+        test_initialize_dweller(
+            &blockchain.payer,
+            &dweller,
+            rent,
+            blockchain.last_blockhash,
+            &mut blockchain.banks_client,
+        )
+        .await;
+
+        let address_to_create = create_derived_account_index(
+            &mut blockchain,
+            &dweller.pubkey(),
+            DwellerServer::SEED,
+            0,
+            instruction::AddressTypeInput::DwellerServer(0),
+        )
+        .await;
+        dweller_servers.push(address_to_create);
+    }
+
+    let [dweller_owner, dweller_legacy, dweller_last] = dwellers;
+
+    let server = Keypair::new();
+    let mut server_members = Vec::new();
+    for index in 0u64..3 {
+        let address_to_create = create_derived_account_index(
+            &mut blockchain,
+            &server.pubkey(),
+            ServerMember::SEED,
+            index,
+            instruction::AddressTypeInput::ServerMember(index),
+        )
+        .await;
+        server_members.push(address_to_create);
+    }
+
+    test_initialize_server(
+        &blockchain.payer,
+        &dweller_owner,
+        &server,
+        &dweller_servers[0],
+        &server_members[0],
+        rent,
+        blockchain.last_blockhash,
+        &mut blockchain.banks_client,
+    )
+    .await;
+
+    let server_administrator = create_derived_account_index(
+        &mut blockchain,
+        &server.pubkey(),
+        ServerAdministrator::SEED,
+        0,
+        instruction::AddressTypeInput::ServerAdministrator(0),
+    )
+    .await;
+    test_add_administrator(
+        &blockchain.payer,
+        &dweller_owner,
+        &dweller_owner.pubkey(),
+        &server.pubkey(),
+        &server_administrator,
+        blockchain.last_blockhash,
+        &mut blockchain.banks_client,
+    )
+    .await;
+
+    let trx = set_join_policy_transaction(
+        &blockchain.payer,
+        &dweller_owner,
+        &server_administrator,
+        &server_members[0],
+        &server.pubkey(),
+        &SetJoinPolicyInput {
+            join_policy: JoinPolicy::Public,
+        },
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    for (index, dweller) in [(1, &dweller_legacy), (2, &dweller_last)] {
+        let trx = join_server_transaction(
+            &blockchain.payer,
+            &server.pubkey(),
+            &server_members[index],
+            &server.pubkey(),
+            dweller,
+            &dweller_servers[index],
+            &server.pubkey(),
+            blockchain.last_blockhash,
+        );
+        blockchain
+            .banks_client
+            .process_transaction(trx)
+            .await
+            .unwrap();
+    }
+
+    // member written before entries were versioned and allocated with rent payer
+    let legacy = ServerMember {
+        version: StateVersion::V1,
+        container: server.pubkey(),
+        index: 1,
+        dweller: dweller_legacy.pubkey(),
+    };
+    blockchain.set_account(
+        &server_members[1],
+        &Account {
+            lamports: rent.minimum_balance(ServerMember::LEN as usize),
+            data: borsh::BorshSerialize::try_to_vec(&legacy).unwrap(),
+            owner: id(),
+            executable: false,
+            rent_epoch: 0,
+        }
+        .into(),
+    );
+    let account = get_account(&mut blockchain, &server_members[2]).await;
+    assert_eq!(
+        account.data.len() as u64,
+        ServerMember::ALLOCATED_LEN + RentPayer::LEN
+    );
+
+    let leave = instruction::with_rent_receiver(
+        instruction::leave_server(
+            &server.pubkey(),
+            &server_members[1],
+            &server_members[2],
+            &dweller_legacy.pubkey(),
+            &dweller_servers[1],
+            &dweller_servers[1],
+        )
+        .unwrap(),
+        &payer,
+    );
+
+    // growing legacy entry to fit last one needs payer
+    let trx = Transaction::new_signed_with_payer(
+        &[leave.clone()],
+        Some(&payer),
+        &[&blockchain.payer, &dweller_legacy],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap_err();
+
+    let trx = Transaction::new_signed_with_payer(
+        &[instruction::with_payer(leave, &payer, &[])],
+        Some(&payer),
+        &[&blockchain.payer, &dweller_legacy],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let account = get_account(&mut blockchain, &server_members[1]).await;
+    assert_eq!(account.data.len() as u64, ServerMemberV4::LEN);
+    assert!(account.lamports >= rent.minimum_balance(account.data.len()));
+    let account_state: ServerMember = get_account_data(&mut blockchain, &server_members[1]).await;
+    assert_eq!(account_state.dweller, dweller_last.pubkey());
+    assert_eq!(account_state.index, 1);
+    assert_eq!(
+        blockchain
+            .banks_client
+            .get_account(server_members[2])
+            .await
+            .unwrap(),
+        None
+    );
+
+    let account_state: Server = get_account_data(&mut blockchain, &server.pubkey()).await;
+    assert_eq!(account_state.members, 2);
+}
+
+#[tokio::test]
+async fn inline_create_flow() {
+    let mut blockchain = program_test().start_with_context().await;
+    let rent = blockchain.banks_client.get_rent().await.unwrap();
+    let payer = blockchain.payer.pubkey();
+
+    let dweller_owner = Keypair::new();
+    let dweller = Keypair::new();
+    for x in [&dweller_owner, &dweller] {
+        test_initialize_dweller(
+            &blockchain.payer,
+            x,
+            rent,
+            blockchain.last_blockhash,
+            &mut blockchain.banks_client,
+        )
+        .await;
+    }
+
+    let server = Keypair::new();
+    let index_address = |container: &Keypair, seed: &str, index: u64| {
+        satellite_servers::program::create_index_with_seed(&id(), seed, &container.pubkey(), index)
+            .unwrap()
+    };
+    let server_members = [
+        index_address(&server, ServerMember::SEED, 0),
+        index_address(&server, ServerMember::SEED, 1),
+    ];
+    let owner_server = index_address(&dweller_owner, DwellerServer::SEED, 0);
+    let dweller_server = index_address(&dweller, DwellerServer::SEED, 0);
+    let server_administrator = index_address(&server, ServerAdministrator::SEED, 0);
+    let server_channel = index_address(&server, ServerChannel::SEED, 0);
+    create_derived_account_index(
+        &mut blockchain,
+        &server.pubkey(),
+        ServerAuditLog::SEED,
+        0,
+        instruction::AddressTypeInput::ServerAuditLog,
+    )
+    .await;
+
+    let mut transaction = Transaction::new_with_payer(
+        &[
+            system_instruction::create_account(
+                &payer,
+                &server.pubkey(),
+                rent.minimum_balance(ServerV2::LEN as usize),
+                ServerV2::LEN,
+                &id(),
+            ),
+            instruction::with_payer(
+                instruction::initialize_server(
+                    &dweller_owner.pubkey(),
+                    &server.pubkey(),
+                    &owner_server,
+                    &server_members[0],
+                    &payer,
+                    InitializeServerInput { name: [13; 32] },
+                )
+                .unwrap(),
+                &payer,
+                &[&server.pubkey(), &dweller_owner.pubkey()],
+            ),
+            instruction::with_server_audit_log(
+                instruction::with_payer(
+                    instruction::add_admin(
+                        &dweller_owner.pubkey(),
+                        &dweller_owner.pubkey(),
+                        &server.pubkey(),
+                        &server_administrator,
+                        &payer,
+                    )
+                    .unwrap(),
+                    &payer,
+                    &[&server.pubkey()],
+                ),
+                &server_audit_log(&server.pubkey()),
+            ),
+            instruction::with_server_audit_log(
+                instruction::with_administrator_member(
+                    instruction::set_join_policy(
+                        &dweller_owner.pubkey(),
+                        &server_administrator,
+                        &server.pubkey(),
+                        &SetJoinPolicyInput {
+                            join_policy: JoinPolicy::Public,
+                        },
+                    )
+                    .unwrap(),
+                    &server_members[0],
+                ),
+                &server_audit_log(&server.pubkey()),
+            ),
+        ],
+        Some(&payer),
+    );
+    transaction.sign(
+        &[&blockchain.payer, &server, &dweller_owner],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+
+    let join = instruction::join_server(
+        &server.pubkey(),
+        &server_members[1],
+        &server.pubkey(),
+        &dweller.pubkey(),
+        &dweller_server,
+        &server.pubkey(),
+        &payer,
+    )
+    .unwrap();
+
+    // without payer not yet created entries are rejected
+    let mut transaction = Transaction::new_with_payer(std::slice::from_ref(&join), Some(&payer));
+    transaction.sign(&[&blockchain.payer, &dweller], blockchain.last_blockhash);
+    blockchain
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap_err();
+
+    let mut transaction = Transaction::new_with_payer(
+        &[
+            instruction::with_payer(join, &payer, &[&dweller.pubkey(), &server.pubkey()]),
+            instruction::with_server_audit_log(
+                instruction::with_payer(
+                    instruction::with_administrator_member(
+                        instruction::add_channel(
+                            &dweller_owner.pubkey(),
+                            &server_administrator,
+                            &server.pubkey(),
+                            &server_channel,
+                            &AddChannelInput {
+                                name: [66; 32],
+                                type_id: ChannelKind::Text as u8,
+                            },
+                        )
+                        .unwrap(),
+                        &server_members[0],
+                    ),
+                    &payer,
+                    &[&server.pubkey()],
+                ),
+                &server_audit_log(&server.pubkey()),
+            ),
+        ],
+        Some(&payer),
+    );
+    transaction.sign(
+        &[&blockchain.payer, &dweller, &dweller_owner],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+
+    let server_state: Server = get_account_data(&mut blockchain, &server.pubkey()).await;
+    assert_eq!(server_state.members, 2);
+    assert_eq!(server_state.administrators, 1);
+    assert_eq!(server_state.channels, 1);
+
+    let member: ServerMember = get_account_data(&mut blockchain, &server_members[1]).await;
+    assert_eq!(member.dweller, dweller.pubkey());
+    let channel: ServerChannel = get_account_data(&mut blockchain, &server_channel).await;
+    assert_eq!(channel.name, [66; 32]);
+
+    for (address, len) in [
+        (server_members[1], ServerMemberV4::LEN),
+        (dweller_server, DwellerServer::LEN),
+        (server_administrator, ServerAdministrator::LEN),
+        (server_channel, ServerChannelV2::LEN),
+    ] {
+        let account = get_account(&mut blockchain, &address).await;
+        assert_eq!(account.owner, id());
+        assert_eq!(account.data.len() as u64, len + RentPayer::LEN);
+        assert_eq!(
+            RentPayer::try_from_slice(&account.data[len as usize..])
+                .unwrap()
+                .payer,
+            payer
+        );
+    }
+}
+
+/// compute budget of one instruction, zero copy hot paths must stay under it
+const HOT_PATH_COMPUTE_UNITS: u64 = 200_000;
+
+/// Checks compute units of hot path instructions, measured on the BPF build when `BPF_OUT_DIR` points
+/// to it, builtin costs otherwise.
+#[tokio::test]
+async fn compute_units_flow() {
+    let mut blockchain = program_test().start_with_context().await;
+    let rent = blockchain.banks_client.get_rent().await.unwrap();
+    let payer = blockchain.payer.pubkey();
+
+    let dweller_owner = Keypair::new();
+    let dweller = Keypair::new();
+    for x in [&dweller_owner, &dweller] {
+        test_initialize_dweller(
+            &blockchain.payer,
+            x,
+            rent,
+            blockchain.last_blockhash,
+            &mut blockchain.banks_client,
+        )
+        .await;
+    }
+
+    let server = Keypair::new();
+    let index_address = |container: &Keypair, seed: &str, index: u64| {
+        satellite_servers::program::create_index_with_seed(&id(), seed, &container.pubkey(), index)
+            .unwrap()
+    };
+    let server_members = [
+        index_address(&server, ServerMember::SEED, 0),
+        index_address(&server, ServerMember::SEED, 1),
+    ];
+    let owner_server = index_address(&dweller_owner, DwellerServer::SEED, 0);
+    let dweller_server = index_address(&dweller, DwellerServer::SEED, 0);
+    let server_administrator = index_address(&server, ServerAdministrator::SEED, 0);
+    create_derived_account_index(
+        &mut blockchain,
+        &server.pubkey(),
+        ServerAuditLog::SEED,
+        0,
+        instruction::AddressTypeInput::ServerAuditLog,
+    )
+    .await;
+
+    let mut transaction = Transaction::new_with_payer(
+        &[
+            system_instruction::create_account(
+                &payer,
+                &server.pubkey(),
+                rent.minimum_balance(ServerV2::LEN as usize),
+                ServerV2::LEN,
+                &id(),
+            ),
+            instruction::with_payer(
+                instruction::initialize_server(
+                    &dweller_owner.pubkey(),
+                    &server.pubkey(),
+                    &owner_server,
+                    &server_members[0],
+                    &payer,
+                    InitializeServerInput { name: [13; 32] },
+                )
+                .unwrap(),
+                &payer,
+                &[&server.pubkey(), &dweller_owner.pubkey()],
+            ),
+            instruction::with_server_audit_log(
+                instruction::with_payer(
+                    instruction::add_admin(
+                        &dweller_owner.pubkey(),
+                        &dweller_owner.pubkey(),
+                        &server.pubkey(),
+                        &server_administrator,
+                        &payer,
+                    )
+                    .unwrap(),
+                    &payer,
+                    &[&server.pubkey()],
+                ),
+                &server_audit_log(&server.pubkey()),
+            ),
+            instruction::with_server_audit_log(
+                instruction::with_administrator_member(
+                    instruction::set_join_policy(
+                        &dweller_owner.pubkey(),
+                        &server_administrator,
+                        &server.pubkey(),
+                        &SetJoinPolicyInput {
+                            join_policy: JoinPolicy::Public,
+                        },
+                    )
+                    .unwrap(),
+                    &server_members[0],
+                ),
+                &server_audit_log(&server.pubkey()),
+            ),
+        ],
+        Some(&payer),
+    );
+    transaction.sign(
+        &[&blockchain.payer, &server, &dweller_owner],
+        blockchain.last_blockhash,
+    );
+    blockchain
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+
+    let join = instruction::with_payer(
+        instruction::join_server(
+            &server.pubkey(),
+            &server_members[1],
+            &server.pubkey(),
+            &dweller.pubkey(),
+            &dweller_server,
+            &server.pubkey(),
+            &payer,
+        )
+        .unwrap(),
+        &payer,
+        &[&dweller.pubkey(), &server.pubkey()],
+    );
+    let leave = instruction::with_rent_receiver(
+        instruction::leave_server(
+            &server.pubkey(),
+            &server_members[1],
+            &server_members[1],
+            &dweller.pubkey(),
+            &dweller_server,
+            &dweller_server,
+        )
+        .unwrap(),
+        &payer,
+    );
+
+    for (name, instruction) in [("JoinServer", join), ("LeaveServer", leave)] {
+        let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer));
+        transaction.sign(&[&blockchain.payer, &dweller], blockchain.last_blockhash);
+        let result = blockchain
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+            .unwrap();
+        result.result.unwrap();
+        let consumed = result.metadata.unwrap().compute_units_consumed;
+        assert!(
+            consumed <= HOT_PATH_COMPUTE_UNITS,
+            "{} consumed {} compute units",
+            name,
+            consumed
+        );
+    }
+
+    let server_state: Server = get_account_data(&mut blockchain, &server.pubkey()).await;
+    assert_eq!(server_state.members, 1);
+    let dweller_state: Dweller = get_account_data(&mut blockchain, &dweller.pubkey()).await;
+    assert_eq!(dweller_state.servers, 0);
+}
+
+#[tokio::test]
+async fn nft_avatar_flow() {
+    let dweller = Keypair::new();
+    let token = Pubkey::new_unique();
+    let token_data = Pubkey::create_with_seed(
+        &token,
+        spl_nft_erc_721::processor::Processor::TOKEN_DATA_SEED,
+        &spl_nft_erc_721::id(),
+    )
+    .unwrap();
+    let nft = |owner| Account {
+        lamports: 1_000_000_000,
+        data: borsh::BorshSerialize::try_to_vec(&spl_nft_erc_721::state::Token {
+            version: spl_nft_erc_721::state::TokenStatus::Initialized,
+            mint: Pubkey::new_unique(),
+            owner,
+            approval: None,
+        })
+        .unwrap(),
+        owner: spl_nft_erc_721::id(),
+        ..Account::default()
+    };
+
+    let mut program_test = program_test();
+    program_test.add_account(token, nft(dweller.pubkey()));
+    program_test.add_account(
+        token_data,
+        Account {
+            lamports: 1_000_000_000,
+            data: borsh::BorshSerialize::try_to_vec(&spl_nft_erc_721::state::TokenData {
+                version: spl_nft_erc_721::state::TokenDataStatus::Initialized,
+                token,
+                hash: Pubkey::new_unique(),
+                uri: [0; spl_nft_erc_721::state::URI_LEN],
+            })
+            .unwrap(),
+            owner: spl_nft_erc_721::id(),
+            ..Account::default()
+        },
+    );
+    let mut program_context = program_test.start_with_context().await;
+    let rent = program_context.banks_client.get_rent().await.unwrap();
+
+    test_initialize_dweller(
+        &program_context.payer,
+        &dweller,
+        rent,
+        program_context.last_blockhash,
+        &mut program_context.banks_client,
+    )
+    .await;
+    let trx = migrate_dweller_transaction(
+        &program_context.payer,
+        &dweller,
+        program_context.last_blockhash,
+    );
+    program_context
+        .banks_client
+        .process_transaction(trx)
+        .await
+        .unwrap();
+
+    let mut transaction = Transaction::new_with_payer(
+        &[instruction::set_dweller_nft_avatar(
+            &dweller.pubkey(),
+            &token,
+            &program_context.payer.pubkey(),
+        )
+        .unwrap()],
+        Some(&program_context.payer.pubkey()),
+    );
+    transaction.sign(
+        &[&program_context.payer, &dweller],
+        program_context.last_blockhash,
+    );
+    program_context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+
+    let dweller_state: DwellerV4 = get_account_data(&mut program_context, &dweller.pubkey()).await;
+    assert_eq!(dweller_state.version, StateVersion::V4);
+    assert_eq!(dweller_state.nft_avatar, token);
+    assert_eq!(dweller_state.name, [42; 32]);
+
+    let mut transaction = Transaction::new_with_payer(
+        &[instruction::clear_stale_avatar(&dweller.pubkey(), &token).unwrap()],
+        Some(&program_context.payer.pubkey()),
+    );
+    transaction.sign(&[&program_context.payer], program_context.last_blockhash);
+    assert!(program_context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .is_err());
+
+    // NFT is sold
+    program_context.set_account(&token, &nft(Pubkey::new_unique()).into());
+    let blockhash = program_context
+        .banks_client
+        .get_new_latest_blockhash(&program_context.last_blockhash)
+        .await
+        .unwrap();
+
+    let mut transaction = Transaction::new_with_payer(
+        &[instruction::clear_stale_avatar(&dweller.pubkey(), &token).unwrap()],
+        Some(&program_context.payer.pubkey()),
+    );
+    transaction.sign(&[&program_context.payer], blockhash);
+    program_context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+
+    let dweller_state: DwellerV4 = get_account_data(&mut program_context, &dweller.pubkey()).await;
+    assert_eq!(dweller_state.nft_avatar, Pubkey::default());
+}
+
+pub async fn create_derived_account_index(
+    blockchain: &mut ProgramTestContext,
+    owner: &Pubkey,
+    seed: &str,
+    index: u64,
+    address_type: instruction::AddressTypeInput,
+) -> Pubkey {
+    let (address_to_create, base_program_address, ..) =
+        satellite_servers::program::create_base_index_with_seed(&id(), seed, owner, index).unwrap();
+    test_create_derived_account(
+        blockchain,
+        owner,
+        &base_program_address,
+        &address_to_create,
+        address_type,
+    )
+    .await
+    .unwrap();
+    address_to_create
+}
+
+/// [ServerAuditLog] privileged instructions of server append to
+pub fn server_audit_log(server: &Pubkey) -> Pubkey {
+    satellite_servers::program::create_index_with_seed(&id(), ServerAuditLog::SEED, server, 0)
+        .unwrap()
+}
+
+pub async fn test_initialize_dweller(
+    payer: &Keypair,
+    dweller_owner: &Keypair,
+    rent: solana_program::rent::Rent,
+    recent_blockhash: solana_program::hash::Hash,
+    blockchain: &mut BanksClient,
+) {
+    let mut transaction = Transaction::new_with_payer(
+        &[
+            system_instruction::create_account(
+                &payer.pubkey(),
+                &dweller_owner.pubkey(),
+                rent.minimum_balance(Dweller::LEN as usize),
+                Dweller::LEN as u64,
+                &satellite_servers::id(),
+            ),
+            instruction::initialize_dweller(
+                &dweller_owner.pubkey(),
+                InitializeDwellerInput { name: [42; 32] },
+            )
+            .unwrap(),
+        ],
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, dweller_owner], recent_blockhash);
+    blockchain.process_transaction(transaction).await.unwrap();
+}
+
+pub async fn test_add_administrator(
+    payer: &Keypair,
+    dweller_owner: &Keypair,
+    dweller: &Pubkey,
+    server: &Pubkey,
+    server_administrator: &Pubkey,
+    recent_blockhash: solana_program::hash::Hash,
+    blockchain: &mut BanksClient,
+) {
+    let mut transaction = Transaction::new_with_payer(
+        &[instruction::with_server_audit_log(
+            instruction::add_admin(
+                &dweller_owner.pubkey(),
+                dweller,
+                server,
+                server_administrator,
+                &payer.pubkey(),
+            )
+            .unwrap(),
+            &server_audit_log(server),
+        )],
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, dweller_owner], recent_blockhash);
+    blockchain.process_transaction(transaction).await.unwrap();
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn test_initialize_server(
+    payer: &Keypair,
+    dweller_owner: &Keypair,
+    server: &Keypair,
+    dweller_server: &Pubkey,
+    server_member: &Pubkey,
+    rent: solana_program::rent::Rent,
+    recent_blockhash: solana_program::hash::Hash,
+    blockchain: &mut BanksClient,
+) {
+    let (server_audit_log, server_audit_log_base, ..) =
+        satellite_servers::program::create_base_index_with_seed(
+            &id(),
+            ServerAuditLog::SEED,
+            &server.pubkey(),
+            0,
+        )
+        .unwrap();
+    let mut transaction = Transaction::new_with_payer(
+        &[
+            system_instruction::create_account(
+                &payer.pubkey(),
+                &server.pubkey(),
+                rent.minimum_balance(ServerV2::LEN as usize),
+                ServerV2::LEN as u64,
+                &satellite_servers::id(),
+            ),
+            instruction::initialize_server(
+                &dweller_owner.pubkey(),
+                &server.pubkey(),
+                dweller_server,
+                server_member,
+                &payer.pubkey(),
+                InitializeServerInput { name: [13; 32] },
+            )
+            .unwrap(),
+            instruction::create_derived_account(
+                &id(),
+                &payer.pubkey(),
+                &server.pubkey(),
+                &server_audit_log_base,
+                &server_audit_log,
+                instruction::AddressTypeInput::ServerAuditLog,
+            )
+            .unwrap(),
+        ],
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, server, dweller_owner], recent_blockhash);
+    blockchain.process_transaction(transaction).await.unwrap();
+}
+
+pub async fn get_account(program_context: &mut ProgramTestContext, pubkey: &Pubkey) -> Account {
+    program_context
+        .banks_client
+        .get_account(*pubkey)
+        .await
+        .expect("account not found")
+        .expect("account empty")
+}
+
+pub async fn get_account_data<T: BorshDeserialize>(
+    program_context: &mut ProgramTestContext,
+    pubkey: &Pubkey,
+) -> T {
+    // versioned states are read by their prefix
+    let account = get_account(program_context, pubkey).await;
+    T::deserialize(&mut &account.data[..]).expect("account not deserialized")
+}
+
+mod sdk {
+    //! Helpers for tests
+    #![cfg(feature = "test-bpf")]
+
+    use solana_program::pubkey::Pubkey;
+    use solana_sdk::{
+        signature::{Keypair, Signer},
+        transaction::Transaction,
+    };
+
+    use crate::server_audit_log;
+
+    use crate::instruction::{
+        self, AddChannelInput, CommitChannelCheckpointInput, CreateGroupInput,
+        CreateServerEventInput, ListServerInput, SetChannelCommitterInput,
+        SetChannelOverwriteInput, SetDwellerAuthorityInput, SetDwellerStatusInput, SetHashInput,
+        SetJoinFeeInput, SetJoinPolicyInput, SetJoinRequirementInput, SetNameInput,
+        UpdateChannelInput, UpdateDwellerProfileInput, UpdateServerProfileInput,
+        WithdrawTreasuryInput,
+    };
+
+    /// assumes not program dweller
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_invite_transaction(
+        payer: &Keypair,
+        server: &Pubkey,
+        dweller_administrator: &Keypair,
+        server_administrator: &Pubkey,
+        administrator_member: &Pubkey,
+        dweller: &Pubkey,
+        member_status: &Pubkey,
+        recent_blockhash: solana_program::hash::Hash,
+    ) -> Transaction {
+        let mut transaction = Transaction::new_with_payer(
+            &[instruction::with_server_audit_log(
+                instruction::with_administrator_member(
+                    instruction::invite_to_server(
+                        &server,
+                        &dweller_administrator.pubkey(),
+                        server_administrator,
+                        dweller,
+                        member_status,
+                    )
+                    .unwrap(),
+                    administrator_member,
+                ),
+                &server_audit_log(server),
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[payer, dweller_administrator], recent_blockhash);
+        transaction
+    }
+
+    /// assumes not program dweller
+    #[allow(clippy::too_many_arguments)]
+    pub fn join_server_transaction(
+        payer: &Keypair,
+        server: &Pubkey,
+        server_member: &Pubkey,
+        server_member_status: &Pubkey,
+        dweller: &Keypair,
+        dweller_server: &Pubkey,
+        server_member_status_last: &Pubkey,
+        recent_blockhash: solana_program::hash::Hash,
+    ) -> Transaction {
+        let mut transaction = Transaction::new_with_payer(
+            &[instruction::with_rent_receiver(
+                instruction::join_server(
+                    server,
+                    server_member,
+                    server_member_status,
+                    &dweller.pubkey(),
+                    dweller_server,
+                    server_member_status_last,
+                    &payer.pubkey(),
+                )
+                .unwrap(),
+                &payer.pubkey(),
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[payer, dweller], recent_blockhash);
+        transaction
+    }
+
+    /// assumes not program dweller
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_group_transaction(
+        payer: &Keypair,
+        dweller_administrator: &Keypair,
+        server_administrator: &Pubkey,
+        administrator_member: &Pubkey,
+        server: &Pubkey,
+        server_group: &Pubkey,
+        input: &CreateGroupInput,
+        recent_blockhash: solana_program::hash::Hash,
+    ) -> Transaction {
+        let mut transaction = Transaction::new_with_payer(
+            &[instruction::with_server_audit_log(
+                instruction::with_administrator_member(
+                    instruction::create_group(
+                        &dweller_administrator.pubkey(),
+                        server_administrator,
+                        server,
+                        &server_group,
+                        input,
+                    )
+                    .unwrap(),
+                    administrator_member,
+                ),
+                &server_audit_log(server),
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[payer, dweller_administrator], recent_blockhash);
+        transaction
+    }
+
+    /// assumes not program dweller
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_channel_transaction(
+        payer: &Keypair,
+        dweller_administrator: &Keypair,
+        server_administrator: &Pubkey,
+        administrator_member: &Pubkey,
+        server: &Pubkey,
+        server_channel: &Pubkey,
+        input: &AddChannelInput,
+        recent_blockhash: solana_program::hash::Hash,
+    ) -> Transaction {
+        let mut transaction = Transaction::new_with_payer(
+            &[instruction::with_server_audit_log(
+                instruction::with_administrator_member(
+                    instruction::add_channel(
+                        &dweller_administrator.pubkey(),
+                        server_administrator,
+                        server,
+                        &server_channel,
+                        input,
+                    )
+                    .unwrap(),
+                    administrator_member,
+                ),
+                &server_audit_log(server),
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[payer, dweller_administrator], recent_blockhash);
+        transaction
+    }
+
+    /// assumes not program dweller
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_channel_to_group_transaction(
+        payer: &Keypair,
+        server: &Pubkey,
+        dweller_administrator: &Keypair,
+        server_administrator: &Pubkey,
+        administrator_member: &Pubkey,
+        server_channel: &Pubkey,
+        server_group: &Pubkey,
+        group_channel: &Pubkey,
+        recent_blockhash: solana_program::hash::Hash,
+    ) -> Transaction {
+        let mut transaction = Transaction::new_with_payer(
+            &[instruction::with_server_audit_log(
+                instruction::with_administrator_member(
+                    instruction::add_channel_to_group(
+                        server,
+                        &dweller_administrator.pubkey(),
+                        server_administrator,
+                        server_channel,
+                        server_group,
+                        group_channel,
+                    )
+                    .unwrap(),
+                    administrator_member,
+                ),
+                &server_audit_log(server),
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[payer, dweller_administrator], recent_blockhash);
+        transaction
+    }
+
+    /// assumes not program dweller
+    #[allow(clippy::too_many_arguments)]
+    pub fn remove_channel_from_group_transaction(
+        payer: &Keypair,
+        server: &Pubkey,
+        dweller_administrator: &Keypair,
+        server_administrator: &Pubkey,
+        administrator_member: &Pubkey,
+        server_group: &Pubkey,
+        group_channel: &Pubkey,
+        group_channel_last: &Pubkey,
+        recent_blockhash: solana_program::hash::Hash,
+    ) -> Transaction {
+        let mut transaction = Transaction::new_with_payer(
+            &[instruction::with_server_audit_log(
+                instruction::with_rent_receiver(
+                    instruction::with_administrator_member(
+                        instruction::remove_channel_from_group(
+                            server,
+                            &dweller_administrator.pubkey(),
+                            server_administrator,
+                            server_group,
+                            group_channel,
+                            group_channel_last,
+                        )
+                        .unwrap(),
+                        administrator_member,
+                    ),
+                    &payer.pubkey(),
+                ),
+                &server_audit_log(server),
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[payer, dweller_administrator], recent_blockhash);
+        transaction
+    }
+
+    /// assumes not program dweller
+    #[allow(clippy::too_many_arguments)]
+    pub fn delete_group_transaction(
+        payer: &Keypair,
+        dweller_administrator: &Keypair,
+        server_administrator: &Pubkey,
+        administrator_member: &Pubkey,
+        server: &Pubkey,
+        server_group: &Pubkey,
+        server_group_last: &Pubkey,
+        group_channels: &Pubkey,
+        recent_blockhash: solana_program::hash::Hash,
+    ) -> Transaction {
+        let mut transaction = Transaction::new_with_payer(
+            &[instruction::with_server_audit_log(
+                instruction::with_rent_receiver(
+                    instruction::with_administrator_member(
+                        instruction::delete_group(
+                            &dweller_administrator.pubkey(),
+                            server_administrator,
+                            server,
+                            server_group,
+                            server_group_last,
+                            &[group_channels],
+                        )
+                        .unwrap(),
+                        administrator_member,
+                    ),
+                    &payer.pubkey(),
+                ),
+                &server_audit_log(server),
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[payer, dweller_administrator], recent_blockhash);
+        transaction
+    }
+
+    /// assumes not program dweller
+    #[allow(clippy::too_many_arguments)]
+    pub fn delete_channel_transaction(
+        payer: &Keypair,
+        dweller_administrator: &Keypair,
+        server_administrator: &Pubkey,
+        administrator_member: &Pubkey,
+        server: &Pubkey,
+        server_channel: &Pubkey,
+        server_channel_last: &Pubkey,
+        recent_blockhash: solana_program::hash::Hash,
+    ) -> Transaction {
+        let mut transaction = Transaction::new_with_payer(
+            &[instruction::with_server_audit_log(
+                instruction::with_rent_receiver(
+                    instruction::with_administrator_member(
+                        instruction::delete_channel(
+                            &dweller_administrator.pubkey(),
+                            server_administrator,
+                            server,
+                            server_channel,
+                            server_channel_last,
+                        )
+                        .unwrap(),
+                        administrator_member,
+                    ),
+                    &payer.pubkey(),
+                ),
+                &server_audit_log(server),
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[payer, dweller_administrator], recent_blockhash);
+        transaction
+    }
+
+    /// assumes not program dweller
+    #[allow(clippy::too_many_arguments)]
+    pub fn leave_server_transaction(
+        payer: &Keypair,
+        server: &Pubkey,
+        server_member: &Pubkey,
+        server_member_last: &Pubkey,
+        dweller: &Keypair,
+        dweller_server: &Pubkey,
+        dweller_server_last: &Pubkey,
+        recent_blockhash: solana_program::hash::Hash,
+    ) -> Transaction {
+        let mut transaction = Transaction::new_with_payer(
+            &[instruction::with_rent_receiver(
+                instruction::leave_server(
+                    server,
+                    server_member,
+                    server_member_last,
+                    &dweller.pubkey(),
+                    dweller_server,
+                    dweller_server_last,
+                )
+                .unwrap(),
+                &payer.pubkey(),
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[payer, dweller], recent_blockhash);
+        transaction
+    }
+
+    /// assumes not program dweller
+    #[allow(clippy::too_many_arguments)]
+    pub fn revoke_invite_server_transaction(
+        payer: &Keypair,
+        server: &Pubkey,
+        dweller_administrator: &Keypair,
+        server_administrator: &Pubkey,
+        administrator_member: &Pubkey,
+        server_member_status: &Pubkey,
+        server_member_status_last: &Pubkey,
+        recent_blockhash: solana_program::hash::Hash,
+    ) -> Transaction {
+        let mut transaction = Transaction::new_with_payer(
+            &[instruction::with_server_audit_log(
+                instruction::with_rent_receiver(
+                    instruction::with_administrator_member(
+                        instruction::revoke_invite_server(
+                            server,
+                            &dweller_administrator.pubkey(),
+                            server_administrator,
+                            server_member_status,
+                            server_member_status_last,
+                        )
+                        .unwrap(),
+                        administrator_member,
+                    ),
+                    &payer.pubkey(),
+                ),
+                &server_audit_log(server),
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[payer, dweller_administrator], recent_blockhash);
+        transaction
+    }
+
+    /// assumes not program dweller
+    pub fn remove_admin_transaction(
+        payer: &Keypair,
+        owner: &Keypair,
+        server: &Pubkey,
+        server_administrator: &Pubkey,
+        server_administrator_last: &Pubkey,
+        dweller: &Pubkey,
+        recent_blockhash: solana_program::hash::Hash,
+    ) -> Transaction {
+        let mut transaction = Transaction::new_with_payer(
+            &[instruction::with_server_audit_log(
+                instruction::with_rent_receiver(
+                    instruction::remove_admin(
+                        &owner.pubkey(),
+                        server,
+                        server_administrator,
+                        server_administrator_last,
+                        dweller,
+                    )
+                    .unwrap(),
+                    &payer.pubkey(),
+                ),
+                &server_audit_log(server),
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[payer, owner], recent_blockhash);
+        transaction
+    }
+
+    /// assumes not program dweller
+    pub fn set_dweller_name_transaction(
+        payer: &Keypair,
+        dweller: &Keypair,
+        input: &SetNameInput,
+        recent_blockhash: solana_program::hash::Hash,
+    ) -> Transaction {
+        let mut transaction = Transaction::new_with_payer(
+            &[instruction::set_dweller_name(&dweller.pubkey(), input).unwrap()],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[payer, dweller], recent_blockhash);
+        transaction
+    }
+
+    /// assumes not program dweller
+    pub fn set_dweller_photo_transaction(
+        payer: &Keypair,
+        dweller: &Keypair,
+        input: &SetHashInput,
+        recent_blockhash: solana_program::hash::Hash,
+    ) -> Transaction {
+        let mut transaction = Transaction::new_with_payer(
+            &[instruction::set_dweller_photo(&dweller.pubkey(), input).unwrap()],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[payer, dweller], recent_blockhash);
+        transaction
+    }
+
+    /// assumes not program dweller
+    pub fn set_dweller_status_transaction(
+        payer: &Keypair,
+        dweller: &Keypair,
+        input: &SetDwellerStatusInput,
+        recent_blockhash: solana_program::hash::Hash,
+    ) -> Transaction {
+        let mut transaction = Transaction::new_with_payer(
+            &[instruction::set_dweller_status(&dweller.pubkey(), input).unwrap()],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[payer, dweller], recent_blockhash);
+        transaction
+    }
+
+    /// assumes not program dweller
+    pub fn set_server_name_transaction(
+        payer: &Keypair,
+        server: &Pubkey,
+        dweller_administrator: &Keypair,
+        server_administrator: &Pubkey,
+        administrator_member: &Pubkey,
+        input: &SetNameInput,
+        recent_blockhash: solana_program::hash::Hash,
+    ) -> Transaction {
+        let mut transaction = Transaction::new_with_payer(
+            &[instruction::with_server_audit_log(
+                instruction::with_administrator_member(
+                    instruction::set_server_name(
+                        server,
+                        &dweller_administrator.pubkey(),
+                        server_administrator,
+                        input,
+                    )
+                    .unwrap(),
+                    administrator_member,
+                ),
+                &server_audit_log(server),
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[payer, dweller_administrator], recent_blockhash);
+        transaction
+    }
+
+    /// assumes not program dweller
+    pub fn set_server_db_transaction(
+        payer: &Keypair,
+        server: &Pubkey,
+        dweller_administrator: &Keypair,
+        server_administrator: &Pubkey,
+        administrator_member: &Pubkey,
+        input: &SetHashInput,
+        recent_blockhash: solana_program::hash::Hash,
+    ) -> Transaction {
+        let mut transaction = Transaction::new_with_payer(
+            &[instruction::with_server_audit_log(
+                instruction::with_administrator_member(
+                    instruction::set_server_db(
+                        server,
+                        &dweller_administrator.pubkey(),
+                        server_administrator,
+                        input,
+                    )
+                    .unwrap(),
+                    administrator_member,
+                ),
+                &server_audit_log(server),
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[payer, dweller_administrator], recent_blockhash);
+        transaction
+    }
     /// assumes not program dweller
-    pub fn add_invite_transaction(
+    pub fn set_join_policy_transaction(
         payer: &Keypair,
-        server: &Pubkey,
         dweller_administrator: &Keypair,
         server_administrator: &Pubkey,
-        dweller: &Pubkey,
-        member_status: &Pubkey,
+        administrator_member: &Pubkey,
+        server: &Pubkey,
+        input: &SetJoinPolicyInput,
         recent_blockhash: solana_program::hash::Hash,
     ) -> Transaction {
         let mut transaction = Transaction::new_with_payer(
-            &[instruction::invite_to_server(
-                &server,
-                &dweller_administrator.pubkey(),
-                server_administrator,
-                dweller,
-                member_status,
-            )
-            .unwrap()],
+            &[instruction::with_server_audit_log(
+                instruction::with_administrator_member(
+                    instruction::set_join_policy(
+                        &dweller_administrator.pubkey(),
+                        server_administrator,
+                        server,
+                        input,
+                    )
+                    .unwrap(),
+                    administrator_member,
+                ),
+                &server_audit_log(server),
+            )],
             Some(&payer.pubkey()),
         );
         transaction.sign(&[payer, dweller_administrator], recent_blockhash);
@@ -697,22 +4615,19 @@ mod sdk {
     }
 
     /// assumes not program dweller
-    pub fn join_server_transaction(
+    pub fn request_join_server_transaction(
         payer: &Keypair,
         server: &Pubkey,
-        server_member: &Pubkey,
-        server_member_status: &Pubkey,
         dweller: &Keypair,
-        dweller_server: &Pubkey,
+        join_request: &Pubkey,
         recent_blockhash: solana_program::hash::Hash,
     ) -> Transaction {
         let mut transaction = Transaction::new_with_payer(
-            &[instruction::join_server(
+            &[instruction::request_join_server(
                 server,
-                server_member,
-                server_member_status,
                 &dweller.pubkey(),
-                dweller_server,
+                join_request,
+                &payer.pubkey(),
             )
             .unwrap()],
             Some(&payer.pubkey()),
@@ -722,24 +4637,41 @@ mod sdk {
     }
 
     /// assumes not program dweller
-    pub fn create_group_transaction(
+    #[allow(clippy::too_many_arguments)]
+    pub fn approve_join_request_transaction(
         payer: &Keypair,
         dweller_administrator: &Keypair,
         server_administrator: &Pubkey,
+        administrator_member: &Pubkey,
         server: &Pubkey,
-        server_group: &Pubkey,
-        input: &CreateGroupInput,
+        join_request: &Pubkey,
+        join_request_last: &Pubkey,
+        server_member: &Pubkey,
+        dweller: &Pubkey,
+        dweller_server: &Pubkey,
         recent_blockhash: solana_program::hash::Hash,
     ) -> Transaction {
         let mut transaction = Transaction::new_with_payer(
-            &[instruction::create_group(
-                &dweller_administrator.pubkey(),
-                server_administrator,
-                server,
-                &server_group,
-                input,
-            )
-            .unwrap()],
+            &[instruction::with_server_audit_log(
+                instruction::with_rent_receiver(
+                    instruction::with_administrator_member(
+                        instruction::approve_join_request(
+                            &dweller_administrator.pubkey(),
+                            server_administrator,
+                            server,
+                            join_request,
+                            join_request_last,
+                            server_member,
+                            dweller,
+                            dweller_server,
+                        )
+                        .unwrap(),
+                        administrator_member,
+                    ),
+                    &payer.pubkey(),
+                ),
+                &server_audit_log(server),
+            )],
             Some(&payer.pubkey()),
         );
         transaction.sign(&[payer, dweller_administrator], recent_blockhash);
@@ -747,24 +4679,60 @@ mod sdk {
     }
 
     /// assumes not program dweller
-    pub fn add_channel_transaction(
+    pub fn decline_invite_transaction(
+        payer: &Keypair,
+        server: &Pubkey,
+        dweller: &Keypair,
+        server_member_status: &Pubkey,
+        server_member_status_last: &Pubkey,
+        recent_blockhash: solana_program::hash::Hash,
+    ) -> Transaction {
+        let mut transaction = Transaction::new_with_payer(
+            &[instruction::with_rent_receiver(
+                instruction::decline_invite(
+                    server,
+                    &dweller.pubkey(),
+                    server_member_status,
+                    server_member_status_last,
+                )
+                .unwrap(),
+                &payer.pubkey(),
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[payer, dweller], recent_blockhash);
+        transaction
+    }
+
+    /// assumes not program dweller
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_channel_transaction(
         payer: &Keypair,
         dweller_administrator: &Keypair,
         server_administrator: &Pubkey,
+        administrator_member: &Pubkey,
         server: &Pubkey,
         server_channel: &Pubkey,
-        input: &AddChannelInput,
+        channel_overwrites: &[&Pubkey],
+        input: &UpdateChannelInput,
         recent_blockhash: solana_program::hash::Hash,
     ) -> Transaction {
         let mut transaction = Transaction::new_with_payer(
-            &[instruction::add_channel(
-                &dweller_administrator.pubkey(),
-                server_administrator,
-                server,
-                &server_channel,
-                input,
-            )
-            .unwrap()],
+            &[instruction::with_server_audit_log(
+                instruction::with_administrator_member(
+                    instruction::update_channel(
+                        &dweller_administrator.pubkey(),
+                        server_administrator,
+                        server,
+                        server_channel,
+                        channel_overwrites,
+                        input,
+                    )
+                    .unwrap(),
+                    administrator_member,
+                ),
+                &server_audit_log(server),
+            )],
             Some(&payer.pubkey()),
         );
         transaction.sign(&[payer, dweller_administrator], recent_blockhash);
@@ -773,26 +4741,35 @@ mod sdk {
 
     /// assumes not program dweller
     #[allow(clippy::too_many_arguments)]
-    pub fn add_channel_to_group_transaction(
+    pub fn set_channel_overwrite_transaction(
         payer: &Keypair,
-        server: &Pubkey,
         dweller_administrator: &Keypair,
         server_administrator: &Pubkey,
+        administrator_member: &Pubkey,
+        server: &Pubkey,
         server_channel: &Pubkey,
-        server_group: &Pubkey,
-        group_channel: &Pubkey,
+        channel_overwrite: &Pubkey,
+        channel_overwrites: &[&Pubkey],
+        input: &SetChannelOverwriteInput,
         recent_blockhash: solana_program::hash::Hash,
     ) -> Transaction {
         let mut transaction = Transaction::new_with_payer(
-            &[instruction::add_channel_to_group(
-                server,
-                &dweller_administrator.pubkey(),
-                server_administrator,
-                server_channel,
-                server_group,
-                group_channel,
-            )
-            .unwrap()],
+            &[instruction::with_server_audit_log(
+                instruction::with_administrator_member(
+                    instruction::set_channel_overwrite(
+                        &dweller_administrator.pubkey(),
+                        server_administrator,
+                        server,
+                        server_channel,
+                        channel_overwrite,
+                        channel_overwrites,
+                        input,
+                    )
+                    .unwrap(),
+                    administrator_member,
+                ),
+                &server_audit_log(server),
+            )],
             Some(&payer.pubkey()),
         );
         transaction.sign(&[payer, dweller_administrator], recent_blockhash);
@@ -801,26 +4778,38 @@ mod sdk {
 
     /// assumes not program dweller
     #[allow(clippy::too_many_arguments)]
-    pub fn remove_channel_from_group_transaction(
+    pub fn remove_channel_overwrite_transaction(
         payer: &Keypair,
-        server: &Pubkey,
         dweller_administrator: &Keypair,
         server_administrator: &Pubkey,
-        server_group: &Pubkey,
-        group_channel: &Pubkey,
-        group_channel_last: &Pubkey,
+        administrator_member: &Pubkey,
+        server: &Pubkey,
+        server_channel: &Pubkey,
+        channel_overwrite: &Pubkey,
+        channel_overwrite_last: &Pubkey,
+        channel_overwrites: &[&Pubkey],
         recent_blockhash: solana_program::hash::Hash,
     ) -> Transaction {
         let mut transaction = Transaction::new_with_payer(
-            &[instruction::remove_channel_from_group(
-                server,
-                &dweller_administrator.pubkey(),
-                server_administrator,
-                server_group,
-                group_channel,
-                group_channel_last,
-            )
-            .unwrap()],
+            &[instruction::with_server_audit_log(
+                instruction::with_rent_receiver(
+                    instruction::with_administrator_member(
+                        instruction::remove_channel_overwrite(
+                            &dweller_administrator.pubkey(),
+                            server_administrator,
+                            server,
+                            server_channel,
+                            channel_overwrite,
+                            channel_overwrite_last,
+                            channel_overwrites,
+                        )
+                        .unwrap(),
+                        administrator_member,
+                    ),
+                    &payer.pubkey(),
+                ),
+                &server_audit_log(server),
+            )],
             Some(&payer.pubkey()),
         );
         transaction.sign(&[payer, dweller_administrator], recent_blockhash);
@@ -829,26 +4818,31 @@ mod sdk {
 
     /// assumes not program dweller
     #[allow(clippy::too_many_arguments)]
-    pub fn delete_group_transaction(
+    pub fn rename_group_transaction(
         payer: &Keypair,
         dweller_administrator: &Keypair,
         server_administrator: &Pubkey,
+        administrator_member: &Pubkey,
         server: &Pubkey,
         server_group: &Pubkey,
-        server_group_last: &Pubkey,
-        group_channels: &Pubkey,
+        input: &SetNameInput,
         recent_blockhash: solana_program::hash::Hash,
     ) -> Transaction {
         let mut transaction = Transaction::new_with_payer(
-            &[instruction::delete_group(
-                &dweller_administrator.pubkey(),
-                server_administrator,
-                server,
-                server_group,
-                server_group_last,
-                &[group_channels],
-            )
-            .unwrap()],
+            &[instruction::with_server_audit_log(
+                instruction::with_administrator_member(
+                    instruction::rename_group(
+                        &dweller_administrator.pubkey(),
+                        server_administrator,
+                        server,
+                        server_group,
+                        input,
+                    )
+                    .unwrap(),
+                    administrator_member,
+                ),
+                &server_audit_log(server),
+            )],
             Some(&payer.pubkey()),
         );
         transaction.sign(&[payer, dweller_administrator], recent_blockhash);
@@ -857,24 +4851,33 @@ mod sdk {
 
     /// assumes not program dweller
     #[allow(clippy::too_many_arguments)]
-    pub fn delete_channel_transaction(
+    pub fn move_group_channel_transaction(
         payer: &Keypair,
         dweller_administrator: &Keypair,
         server_administrator: &Pubkey,
+        administrator_member: &Pubkey,
         server: &Pubkey,
-        server_channel: &Pubkey,
-        server_channel_last: &Pubkey,
+        server_group: &Pubkey,
+        group_channel: &Pubkey,
+        group_channel_other: &Pubkey,
         recent_blockhash: solana_program::hash::Hash,
     ) -> Transaction {
         let mut transaction = Transaction::new_with_payer(
-            &[instruction::delete_channel(
-                &dweller_administrator.pubkey(),
-                server_administrator,
-                server,
-                server_channel,
-                server_channel_last,
-            )
-            .unwrap()],
+            &[instruction::with_server_audit_log(
+                instruction::with_administrator_member(
+                    instruction::move_group_channel(
+                        &dweller_administrator.pubkey(),
+                        server_administrator,
+                        server,
+                        server_group,
+                        group_channel,
+                        group_channel_other,
+                    )
+                    .unwrap(),
+                    administrator_member,
+                ),
+                &server_audit_log(server),
+            )],
             Some(&payer.pubkey()),
         );
         transaction.sign(&[payer, dweller_administrator], recent_blockhash);
@@ -882,27 +4885,17 @@ mod sdk {
     }
 
     /// assumes not program dweller
-    #[allow(clippy::too_many_arguments)]
-    pub fn leave_server_transaction(
+    pub fn update_dweller_profile_transaction(
         payer: &Keypair,
-        server: &Pubkey,
-        server_member: &Pubkey,
-        server_member_last: &Pubkey,
         dweller: &Keypair,
-        dweller_server: &Pubkey,
-        dweller_server_last: &Pubkey,
+        input: &UpdateDwellerProfileInput,
         recent_blockhash: solana_program::hash::Hash,
     ) -> Transaction {
         let mut transaction = Transaction::new_with_payer(
-            &[instruction::leave_server(
-                server,
-                server_member,
-                server_member_last,
-                &dweller.pubkey(),
-                dweller_server,
-                dweller_server_last,
-            )
-            .unwrap()],
+            &[
+                instruction::update_dweller_profile(&dweller.pubkey(), &payer.pubkey(), input)
+                    .unwrap(),
+            ],
             Some(&payer.pubkey()),
         );
         transaction.sign(&[payer, dweller], recent_blockhash);
@@ -910,24 +4903,100 @@ mod sdk {
     }
 
     /// assumes not program dweller
-    pub fn revoke_invite_server_transaction(
+    pub fn migrate_dweller_transaction(
+        payer: &Keypair,
+        dweller: &Keypair,
+        recent_blockhash: solana_program::hash::Hash,
+    ) -> Transaction {
+        let mut transaction = Transaction::new_with_payer(
+            &[instruction::migrate_dweller(&dweller.pubkey(), &payer.pubkey()).unwrap()],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[payer, dweller], recent_blockhash);
+        transaction
+    }
+
+    /// signed by current authority of dweller
+    pub fn set_dweller_authority_transaction(
+        payer: &Keypair,
+        current_authority: &Keypair,
+        dweller: &Pubkey,
+        authority: &Pubkey,
+        recent_blockhash: solana_program::hash::Hash,
+    ) -> Transaction {
+        let mut instruction = instruction::set_dweller_authority(
+            dweller,
+            &SetDwellerAuthorityInput {
+                authority: *authority,
+            },
+        )
+        .unwrap();
+        if current_authority.pubkey() != *dweller {
+            instruction = instruction::with_dweller_authority(
+                instruction,
+                dweller,
+                &current_authority.pubkey(),
+            );
+        }
+        let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+        transaction.sign(&[payer, current_authority], recent_blockhash);
+        transaction
+    }
+
+    /// assumes not program dweller
+    pub fn set_server_photo_transaction(
         payer: &Keypair,
+        dweller_administrator: &Keypair,
+        server_administrator: &Pubkey,
+        administrator_member: &Pubkey,
         server: &Pubkey,
+        input: &SetHashInput,
+        recent_blockhash: solana_program::hash::Hash,
+    ) -> Transaction {
+        let mut transaction = Transaction::new_with_payer(
+            &[instruction::with_server_audit_log(
+                instruction::with_administrator_member(
+                    instruction::set_server_photo(
+                        &dweller_administrator.pubkey(),
+                        server_administrator,
+                        server,
+                        input,
+                    )
+                    .unwrap(),
+                    administrator_member,
+                ),
+                &server_audit_log(server),
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[payer, dweller_administrator], recent_blockhash);
+        transaction
+    }
+
+    /// assumes not program dweller
+    pub fn update_server_profile_transaction(
+        payer: &Keypair,
         dweller_administrator: &Keypair,
         server_administrator: &Pubkey,
-        server_member_status: &Pubkey,
-        server_member_status_last: &Pubkey,
+        administrator_member: &Pubkey,
+        server: &Pubkey,
+        input: &UpdateServerProfileInput,
         recent_blockhash: solana_program::hash::Hash,
     ) -> Transaction {
         let mut transaction = Transaction::new_with_payer(
-            &[instruction::revoke_invite_server(
-                server,
-                &dweller_administrator.pubkey(),
-                server_administrator,
-                server_member_status,
-                server_member_status_last,
-            )
-            .unwrap()],
+            &[instruction::with_server_audit_log(
+                instruction::with_administrator_member(
+                    instruction::update_server_profile(
+                        &dweller_administrator.pubkey(),
+                        server_administrator,
+                        server,
+                        input,
+                    )
+                    .unwrap(),
+                    administrator_member,
+                ),
+                &server_audit_log(server),
+            )],
             Some(&payer.pubkey()),
         );
         transaction.sign(&[payer, dweller_administrator], recent_blockhash);
@@ -935,52 +5004,91 @@ mod sdk {
     }
 
     /// assumes not program dweller
-    pub fn remove_admin_transaction(
+    pub fn list_server_transaction(
         payer: &Keypair,
-        owner: &Keypair,
+        dweller_owner: &Keypair,
         server: &Pubkey,
-        server_administrator: &Pubkey,
-        server_administrator_last: &Pubkey,
+        server_directory: &Pubkey,
+        directory_entry: &Pubkey,
+        input: &ListServerInput,
         recent_blockhash: solana_program::hash::Hash,
     ) -> Transaction {
         let mut transaction = Transaction::new_with_payer(
-            &[instruction::remove_admin(
-                &owner.pubkey(),
-                server,
-                server_administrator,
-                server_administrator_last,
-            )
-            .unwrap()],
+            &[instruction::with_server_audit_log(
+                instruction::list_server(
+                    &dweller_owner.pubkey(),
+                    server,
+                    server_directory,
+                    directory_entry,
+                    input,
+                )
+                .unwrap(),
+                &server_audit_log(server),
+            )],
             Some(&payer.pubkey()),
         );
-        transaction.sign(&[payer, owner], recent_blockhash);
+        transaction.sign(&[payer, dweller_owner], recent_blockhash);
         transaction
     }
 
     /// assumes not program dweller
-    pub fn set_dweller_name_transaction(
+    pub fn unlist_server_transaction(
         payer: &Keypair,
-        dweller: &Keypair,
-        input: &SetNameInput,
+        dweller_owner: &Keypair,
+        server: &Pubkey,
+        server_directory: &Pubkey,
+        directory_entry: &Pubkey,
+        directory_entry_last: &Pubkey,
         recent_blockhash: solana_program::hash::Hash,
     ) -> Transaction {
         let mut transaction = Transaction::new_with_payer(
-            &[instruction::set_dweller_name(&dweller.pubkey(), input).unwrap()],
+            &[instruction::with_server_audit_log(
+                instruction::with_rent_receiver(
+                    instruction::unlist_server(
+                        &dweller_owner.pubkey(),
+                        server,
+                        server_directory,
+                        directory_entry,
+                        directory_entry_last,
+                    )
+                    .unwrap(),
+                    &payer.pubkey(),
+                ),
+                &server_audit_log(server),
+            )],
             Some(&payer.pubkey()),
         );
-        transaction.sign(&[payer, dweller], recent_blockhash);
+        transaction.sign(&[payer, dweller_owner], recent_blockhash);
         transaction
     }
 
     /// assumes not program dweller
-    pub fn set_dweller_photo_transaction(
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_member_nickname_transaction(
         payer: &Keypair,
         dweller: &Keypair,
-        input: &SetHashInput,
+        server: &Pubkey,
+        server_member: &Pubkey,
+        server_administrator: &Pubkey,
+        administrator_member: &Pubkey,
+        input: &SetNameInput,
         recent_blockhash: solana_program::hash::Hash,
     ) -> Transaction {
         let mut transaction = Transaction::new_with_payer(
-            &[instruction::set_dweller_photo(&dweller.pubkey(), input).unwrap()],
+            &[instruction::with_server_audit_log(
+                instruction::with_administrator_member(
+                    instruction::set_member_nickname(
+                        &dweller.pubkey(),
+                        server,
+                        server_member,
+                        server_administrator,
+                        input,
+                    )
+                    .unwrap(),
+                    administrator_member,
+                ),
+                &server_audit_log(server),
+            )],
             Some(&payer.pubkey()),
         );
         transaction.sign(&[payer, dweller], recent_blockhash);
@@ -988,63 +5096,225 @@ mod sdk {
     }
 
     /// assumes not program dweller
-    pub fn set_dweller_status_transaction(
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_channel_committer_transaction(
         payer: &Keypair,
-        dweller: &Keypair,
-        input: &SetDwellerStatusInput,
+        dweller_administrator: &Keypair,
+        server_administrator: &Pubkey,
+        administrator_member: &Pubkey,
+        server: &Pubkey,
+        server_channel: &Pubkey,
+        channel_history: &Pubkey,
+        input: &SetChannelCommitterInput,
         recent_blockhash: solana_program::hash::Hash,
     ) -> Transaction {
         let mut transaction = Transaction::new_with_payer(
-            &[instruction::set_dweller_status(&dweller.pubkey(), input).unwrap()],
+            &[instruction::with_server_audit_log(
+                instruction::with_administrator_member(
+                    instruction::set_channel_committer(
+                        &dweller_administrator.pubkey(),
+                        server_administrator,
+                        server,
+                        server_channel,
+                        channel_history,
+                        input,
+                    )
+                    .unwrap(),
+                    administrator_member,
+                ),
+                &server_audit_log(server),
+            )],
             Some(&payer.pubkey()),
         );
-        transaction.sign(&[payer, dweller], recent_blockhash);
+        transaction.sign(&[payer, dweller_administrator], recent_blockhash);
         transaction
     }
 
     /// assumes not program dweller
-    pub fn set_server_name_transaction(
+    #[allow(clippy::too_many_arguments)]
+    pub fn commit_channel_checkpoint_transaction(
         payer: &Keypair,
+        committer: &Keypair,
+        server_administrator: &Pubkey,
+        administrator_member: &Pubkey,
         server: &Pubkey,
+        server_channel: &Pubkey,
+        channel_history: &Pubkey,
+        channel_checkpoint: &Pubkey,
+        input: &CommitChannelCheckpointInput,
+        recent_blockhash: solana_program::hash::Hash,
+    ) -> Transaction {
+        let mut transaction = Transaction::new_with_payer(
+            &[instruction::with_server_audit_log(
+                instruction::with_administrator_member(
+                    instruction::commit_channel_checkpoint(
+                        &committer.pubkey(),
+                        server_administrator,
+                        server,
+                        server_channel,
+                        channel_history,
+                        channel_checkpoint,
+                        input,
+                    )
+                    .unwrap(),
+                    administrator_member,
+                ),
+                &server_audit_log(server),
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[payer, committer], recent_blockhash);
+        transaction
+    }
+
+    /// assumes not program dweller
+    pub fn set_join_requirement_transaction(
+        payer: &Keypair,
         dweller_administrator: &Keypair,
         server_administrator: &Pubkey,
-        input: &SetNameInput,
+        administrator_member: &Pubkey,
+        server: &Pubkey,
+        input: &SetJoinRequirementInput,
         recent_blockhash: solana_program::hash::Hash,
     ) -> Transaction {
         let mut transaction = Transaction::new_with_payer(
-            &[instruction::set_server_name(
-                server,
-                &dweller_administrator.pubkey(),
-                server_administrator,
-                input,
-            )
-            .unwrap()],
+            &[instruction::with_server_audit_log(
+                instruction::with_administrator_member(
+                    instruction::set_join_requirement(
+                        &dweller_administrator.pubkey(),
+                        server_administrator,
+                        server,
+                        &payer.pubkey(),
+                        input,
+                    )
+                    .unwrap(),
+                    administrator_member,
+                ),
+                &server_audit_log(server),
+            )],
             Some(&payer.pubkey()),
         );
         transaction.sign(&[payer, dweller_administrator], recent_blockhash);
         transaction
     }
 
-    /// assumes not program dweller
-    pub fn set_server_db_transaction(
+    #[allow(clippy::too_many_arguments)]
+    pub fn recheck_member_transaction(
+        payer: &Keypair,
+        server: &Pubkey,
+        server_member: &Pubkey,
+        server_member_last: &Pubkey,
+        dweller: &Pubkey,
+        dweller_server: &Pubkey,
+        dweller_server_last: &Pubkey,
+        token: &Pubkey,
+        recent_blockhash: solana_program::hash::Hash,
+    ) -> Transaction {
+        let mut transaction = Transaction::new_with_payer(
+            &[instruction::with_rent_receiver(
+                instruction::recheck_member(
+                    server,
+                    server_member,
+                    server_member_last,
+                    dweller,
+                    dweller_server,
+                    dweller_server_last,
+                    token,
+                )
+                .unwrap(),
+                &payer.pubkey(),
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[payer], recent_blockhash);
+        transaction
+    }
+
+    pub fn set_join_fee_transaction(
         payer: &Keypair,
+        dweller_owner: &Keypair,
         server: &Pubkey,
+        mint: &Pubkey,
+        input: &SetJoinFeeInput,
+        recent_blockhash: solana_program::hash::Hash,
+    ) -> Transaction {
+        let mut transaction = Transaction::new_with_payer(
+            &[instruction::with_server_audit_log(
+                instruction::set_join_fee(
+                    &dweller_owner.pubkey(),
+                    server,
+                    &payer.pubkey(),
+                    mint,
+                    input,
+                )
+                .unwrap(),
+                &server_audit_log(server),
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[payer, dweller_owner], recent_blockhash);
+        transaction
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_server_event_transaction(
+        payer: &Keypair,
         dweller_administrator: &Keypair,
         server_administrator: &Pubkey,
-        input: &SetHashInput,
+        administrator_member: &Pubkey,
+        server: &Pubkey,
+        server_events: &Pubkey,
+        server_event: &Pubkey,
+        server_channel: &Pubkey,
+        input: &CreateServerEventInput,
+        recent_blockhash: solana_program::hash::Hash,
+    ) -> Transaction {
+        let mut transaction = Transaction::new_with_payer(
+            &[instruction::with_server_audit_log(
+                instruction::with_administrator_member(
+                    instruction::create_server_event(
+                        &dweller_administrator.pubkey(),
+                        server_administrator,
+                        server,
+                        server_events,
+                        server_event,
+                        server_channel,
+                        input,
+                    )
+                    .unwrap(),
+                    administrator_member,
+                ),
+                &server_audit_log(server),
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[payer, dweller_administrator], recent_blockhash);
+        transaction
+    }
+
+    /// assumes not program dweller
+    pub fn rsvp_transaction(
+        payer: &Keypair,
+        dweller: &Keypair,
+        server: &Pubkey,
+        server_member: &Pubkey,
+        server_event: &Pubkey,
+        event_rsvp: &Pubkey,
         recent_blockhash: solana_program::hash::Hash,
     ) -> Transaction {
         let mut transaction = Transaction::new_with_payer(
-            &[instruction::set_server_db(
+            &[instruction::rsvp(
+                &dweller.pubkey(),
                 server,
-                &dweller_administrator.pubkey(),
-                server_administrator,
-                input,
+                server_member,
+                server_event,
+                event_rsvp,
+                &payer.pubkey(),
             )
             .unwrap()],
             Some(&payer.pubkey()),
         );
-        transaction.sign(&[payer, dweller_administrator], recent_blockhash);
+        transaction.sign(&[payer, dweller], recent_blockhash);
         transaction
     }
 }